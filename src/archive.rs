@@ -0,0 +1,154 @@
+//! Parsing of ar(1) archives, so hrelf can report on each object in a
+//! static library.
+//!
+//! Members are a 60 byte text header followed by their data, padded to an
+//! even offset. Besides the objects, GNU archives hold a symbol index named
+//! "/" (or "/SYM64/" with 64-bit offsets) and a "//" table of the member
+//! names too long for the 16 byte header field.
+
+use elf::parse::ParseError;
+
+pub const ARMAG: &[u8] = b"!<arch>\n";
+/// Thin archives only reference their members by path.
+pub const THINMAG: &[u8] = b"!<thin>\n";
+
+const HEADER_SIZE: usize = 60;
+const FMAG: &[u8] = b"`\n";
+
+#[derive(Debug)]
+pub struct Member<'data> {
+    /// Offset of the member's header in the archive, which is what the
+    /// symbol index refers to.
+    pub offset: usize,
+    pub name: &'data str,
+    pub data: &'data [u8],
+}
+
+#[derive(Debug)]
+pub struct Archive<'data> {
+    /// The object members, without the index and name table.
+    pub members: Vec<Member<'data>>,
+    /// Symbol names with the header offset of the member defining them, in
+    /// index order.
+    pub index: Vec<(&'data str, u64)>,
+    /// The bytes of symbol names in the index, which readelf reports as
+    /// its size.
+    pub index_names_size: usize,
+}
+
+impl Archive<'_> {
+    /// The member whose header is at `offset`.
+    pub fn member_at(&self, offset: u64) -> Option<&Member<'_>> {
+        self.members
+            .iter()
+            .find(|member| member.offset as u64 == offset)
+    }
+}
+
+/// Parses an archive that starts with [`ARMAG`].
+pub fn parse_archive(data: &[u8]) -> Result<Archive<'_>, ParseError> {
+    if !data.starts_with(ARMAG) {
+        return Err(ParseError::BadOffset(0));
+    }
+    let mut archive = Archive {
+        members: Vec::new(),
+        index: Vec::new(),
+        index_names_size: 0,
+    };
+    let mut names: &[u8] = &[];
+    let mut offset = ARMAG.len();
+    while offset < data.len() {
+        let header = data
+            .get(offset..offset + HEADER_SIZE)
+            .ok_or(ParseError::BadOffset(offset as u64))?;
+        if &header[58..] != FMAG {
+            return Err(ParseError::BadOffset(offset as u64));
+        }
+        let size = field(header, 48, 10)?
+            .parse::<usize>()
+            .map_err(|_| ParseError::BadOffset(offset as u64))?;
+        let start = offset + HEADER_SIZE;
+        let mut contents = data
+            .get(start..start.saturating_add(size))
+            .ok_or(ParseError::BadOffset(offset as u64))?;
+        let raw_name = field(header, 0, 16)?;
+        match raw_name {
+            "/" | "/SYM64/" => {
+                let word = if raw_name == "/" { 4 } else { 8 };
+                archive.index = parse_index(contents, word)?;
+                archive.index_names_size = size.saturating_sub((archive.index.len() + 1) * word);
+            }
+            "//" => names = contents,
+            _ => {
+                let name = if let Some(name_offset) = raw_name.strip_prefix('/') {
+                    // A long name: an offset into the "//" table, where
+                    // each name ends in "/\n"
+                    let name_offset = name_offset
+                        .parse::<usize>()
+                        .map_err(|_| ParseError::BadOffset(offset as u64))?;
+                    let rest = names
+                        .get(name_offset..)
+                        .ok_or(ParseError::BadOffset(name_offset as u64))?;
+                    let end = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+                    let name = std::str::from_utf8(&rest[..end]).map_err(ParseError::Utf8Error)?;
+                    name.strip_suffix('/').unwrap_or(name)
+                } else if let Some(len) = raw_name.strip_prefix("#1/") {
+                    // BSD keeps long names at the start of the data instead
+                    let len = len
+                        .parse::<usize>()
+                        .map_err(|_| ParseError::BadOffset(offset as u64))?;
+                    let name = contents
+                        .get(..len)
+                        .ok_or(ParseError::BadOffset(offset as u64))?;
+                    contents = &contents[len..];
+                    std::str::from_utf8(name)
+                        .map_err(ParseError::Utf8Error)?
+                        .trim_end_matches('\0')
+                } else {
+                    raw_name.strip_suffix('/').unwrap_or(raw_name)
+                };
+                // BSD's symbol index is an ordinary looking member
+                if !name.starts_with("__.SYMDEF") {
+                    archive.members.push(Member {
+                        offset,
+                        name,
+                        data: contents,
+                    });
+                }
+            }
+        }
+        // Member data is padded to an even offset
+        offset = start + size + size % 2;
+    }
+    Ok(archive)
+}
+
+/// A space-padded text field of a member header.
+fn field(header: &[u8], start: usize, len: usize) -> Result<&str, ParseError> {
+    std::str::from_utf8(&header[start..start + len])
+        .map(str::trim_end)
+        .map_err(ParseError::Utf8Error)
+}
+
+/// The symbol index: a big-endian count, that many member offsets, then
+/// as many NUL-terminated symbol names.
+fn parse_index(data: &[u8], word: usize) -> Result<Vec<(&str, u64)>, ParseError> {
+    let read_word = |offset: usize| -> Result<u64, ParseError> {
+        let bytes = data
+            .get(offset..offset + word)
+            .ok_or(ParseError::BadOffset(offset as u64))?;
+        Ok(bytes.iter().fold(0, |value, &b| value << 8 | b as u64))
+    };
+    let count = read_word(0)? as usize;
+    let mut name_offset = count
+        .checked_add(1)
+        .and_then(|words| words.checked_mul(word))
+        .ok_or(ParseError::IntegerOverflow)?;
+    let mut index = Vec::new();
+    for i in 0..count {
+        let member = read_word((i + 1) * word)?;
+        let name = crate::attributes::read_ntbs(data, &mut name_offset)?;
+        index.push((name, member));
+    }
+    Ok(index)
+}
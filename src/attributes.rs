@@ -0,0 +1,205 @@
+//! Parsing of the processor build attribute sections (.ARM.attributes and
+//! .riscv.attributes), which share one encoding.
+//!
+//! The layout is a format-version byte ('A') followed by vendor subsections,
+//! each holding File/Section/Symbol groups of ULEB128-tagged attributes.
+//! Whether a tag's value is a ULEB128 or a NUL-terminated string is up to the
+//! vendor, so the caller supplies that per tag.
+
+use elf::endian::EndianParse;
+use elf::parse::ParseError;
+
+/// The only attribute format version in use.
+const FORMAT_VERSION: u8 = b'A';
+
+pub const TAG_FILE: u64 = 1;
+pub const TAG_SECTION: u64 = 2;
+pub const TAG_SYMBOL: u64 = 3;
+
+/// How a vendor encodes the value of a given tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Int,
+    Str,
+    /// A ULEB128 followed by a string, as in Tag_compatibility.
+    IntStr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value<'data> {
+    Int(u64),
+    Str(&'data str),
+    IntStr(u64, &'data str),
+}
+
+#[derive(Debug)]
+pub struct Attribute<'data> {
+    pub tag: u64,
+    pub value: Value<'data>,
+}
+
+/// Attributes applying to the whole file, or to the listed section or
+/// symbol indexes.
+#[derive(Debug)]
+pub struct Group<'data> {
+    /// One of TAG_FILE, TAG_SECTION or TAG_SYMBOL.
+    pub scope: u64,
+    pub indexes: Vec<u64>,
+    pub attributes: Vec<Attribute<'data>>,
+}
+
+#[derive(Debug)]
+pub struct Subsection<'data> {
+    pub vendor: &'data str,
+    /// Empty for vendors other than the one the section was parsed for,
+    /// whose tag encodings aren't known.
+    pub groups: Vec<Group<'data>>,
+}
+
+pub fn read_uleb128(data: &[u8], offset: &mut usize) -> Result<u64, ParseError> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *data
+            .get(*offset)
+            .ok_or(ParseError::BadOffset(*offset as u64))?;
+        *offset += 1;
+        if shift >= 64 {
+            return Err(ParseError::IntegerOverflow);
+        }
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
+
+pub fn read_sleb128(data: &[u8], offset: &mut usize) -> Result<i64, ParseError> {
+    let mut result = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = *data
+            .get(*offset)
+            .ok_or(ParseError::BadOffset(*offset as u64))?;
+        *offset += 1;
+        if shift >= 64 {
+            return Err(ParseError::IntegerOverflow);
+        }
+        result |= i64::from(byte & 0x7f) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            // Sign-extend from the last byte's top bit
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= -1 << shift;
+            }
+            return Ok(result);
+        }
+    }
+}
+
+/// A NUL-terminated string starting at `offset`, which is left past the NUL.
+pub fn read_ntbs<'data>(data: &'data [u8], offset: &mut usize) -> Result<&'data str, ParseError> {
+    let rest = data
+        .get(*offset..)
+        .ok_or(ParseError::BadOffset(*offset as u64))?;
+    let len = rest
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or(ParseError::StringTableMissingNul(*offset as u64))?;
+    *offset += len + 1;
+    std::str::from_utf8(&rest[..len]).map_err(ParseError::Utf8Error)
+}
+
+/// Parses a whole attribute section, decoding the subsections of `vendor`
+/// with `value_kind`.
+pub fn parse_attributes<'data, E: EndianParse>(
+    endian: E,
+    data: &'data [u8],
+    vendor: &str,
+    value_kind: fn(u64) -> ValueKind,
+) -> Result<Vec<Subsection<'data>>, ParseError> {
+    match data.first() {
+        Some(&FORMAT_VERSION) => (),
+        Some(&version) => return Err(ParseError::UnsupportedVersion((version as u64, 0x41))),
+        None => return Ok(Vec::new()),
+    }
+    let mut subsections = Vec::new();
+    let mut start = 1;
+    while start < data.len() {
+        let mut offset = start;
+        let len = endian.parse_u32_at(&mut offset, data)? as usize;
+        let end = checked_end(start, len, data.len())?;
+        let name = read_ntbs(data, &mut offset)?;
+        let mut groups = Vec::new();
+        if name == vendor {
+            while offset < end {
+                let group_start = offset;
+                let scope = read_uleb128(data, &mut offset)?;
+                let size = endian.parse_u32_at(&mut offset, data)? as usize;
+                let group_end = checked_end(group_start, size, end)?;
+                let mut indexes = Vec::new();
+                if scope != TAG_FILE {
+                    loop {
+                        match read_uleb128(data, &mut offset)? {
+                            0 => break,
+                            index => indexes.push(index),
+                        }
+                    }
+                }
+                let mut attributes = Vec::new();
+                while offset < group_end {
+                    let tag = read_uleb128(data, &mut offset)?;
+                    let value = match value_kind(tag) {
+                        ValueKind::Int => Value::Int(read_uleb128(data, &mut offset)?),
+                        ValueKind::Str => Value::Str(read_ntbs(data, &mut offset)?),
+                        ValueKind::IntStr => {
+                            let flag = read_uleb128(data, &mut offset)?;
+                            Value::IntStr(flag, read_ntbs(data, &mut offset)?)
+                        }
+                    };
+                    attributes.push(Attribute { tag, value });
+                }
+                groups.push(Group {
+                    scope,
+                    indexes,
+                    attributes,
+                });
+                offset = group_end;
+            }
+        }
+        subsections.push(Subsection {
+            vendor: name,
+            groups,
+        });
+        start = end;
+    }
+    Ok(subsections)
+}
+
+/// The end of a record of `len` bytes at `start`, which must fit in `limit`.
+fn checked_end(start: usize, len: usize, limit: usize) -> Result<usize, ParseError> {
+    match start.checked_add(len) {
+        Some(end) if len > 0 && end <= limit => Ok(end),
+        _ => Err(ParseError::BadOffset(start as u64)),
+    }
+}
+
+/// The value encoding of the "aeabi" tags: strings for the CPU names and
+/// Tag_conformance, and above 32 by the even/odd rule of the ARM ABI addenda.
+pub fn arm_value_kind(tag: u64) -> ValueKind {
+    match tag {
+        4 | 5 | 65 | 67 => ValueKind::Str,
+        32 => ValueKind::IntStr,
+        tag if tag > 32 && tag % 2 == 1 => ValueKind::Str,
+        _ => ValueKind::Int,
+    }
+}
+
+/// RISC-V keeps to the plain rule: odd tags are strings, even ones ULEB128s.
+pub fn riscv_value_kind(tag: u64) -> ValueKind {
+    match tag % 2 {
+        1 => ValueKind::Str,
+        _ => ValueKind::Int,
+    }
+}
@@ -0,0 +1,52 @@
+//! The --color styles. Painting is a no-op unless [`enable`] turned color
+//! on, so printers style their columns unconditionally and piped output
+//! stays plain text.
+
+use anstyle::{AnsiColor, Color, Style};
+use elf::abi;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+const EXECUTABLE: Style = Style::new().fg_color(Some(Color::Ansi(AnsiColor::Red)));
+const WRITABLE: Style = Style::new().fg_color(Some(Color::Ansi(AnsiColor::Yellow)));
+const BOOKKEEPING: Style = Style::new().dimmed();
+
+pub const SECTION_TYPE: Style = Style::new().fg_color(Some(Color::Ansi(AnsiColor::Cyan)));
+
+/// Turns color on for `always`, and for `auto` when stdout is a terminal
+/// and NO_COLOR isn't set.
+pub fn enable(always: bool, auto: bool) {
+    let enabled = always
+        || (auto && std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none());
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// `text`, already padded to its column, wrapped in `style`'s escapes.
+pub fn paint(style: Style, text: &str) -> String {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return text.to_string();
+    }
+    format!("{}{}{}", style.render(), text, style.render_reset())
+}
+
+/// Executable sections and segments in red, writable ones in yellow.
+pub fn permissions(executable: bool, writable: bool) -> Style {
+    match (executable, writable) {
+        (true, _) => EXECUTABLE,
+        (false, true) => WRITABLE,
+        (false, false) => Style::new(),
+    }
+}
+
+/// Code in the executable color and data in the writable one, with the
+/// STT_SECTION and STT_FILE entries every table has dimmed.
+pub fn symbol_type(st_type: u8) -> Style {
+    match st_type {
+        abi::STT_FUNC | abi::STT_GNU_IFUNC => EXECUTABLE,
+        abi::STT_OBJECT | abi::STT_COMMON | abi::STT_TLS => WRITABLE,
+        abi::STT_SECTION | abi::STT_FILE => BOOKKEEPING,
+        _ => Style::new(),
+    }
+}
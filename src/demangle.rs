@@ -0,0 +1,1583 @@
+//! Demangling of C++ (Itanium ABI) and Rust (legacy and v0) symbol names,
+//! which is what -C prints.
+//!
+//! Nothing in the dependency tree demangles, so this covers the manglings
+//! compilers emit for functions, variables and their templates, printed the
+//! way binutils does. Anything outside that makes [demangle] give up, and the
+//! caller keeps the raw name.
+
+/// The demangled form of `name`, or `None` when it isn't a mangling this
+/// module understands.
+pub fn demangle(name: &str) -> Option<String> {
+    // Whatever follows a Rust symbol is a vendor suffix, like .llvm.<n>,
+    // which isn't shown
+    if let Some(rest) = name.strip_prefix("_R") {
+        return RustV0::new(rest).symbol();
+    }
+    let rest = name.strip_prefix("_Z")?;
+    if let Some(demangled) = rust_legacy(rest) {
+        return Some(demangled);
+    }
+    let (demangled, consumed) = Itanium::new(rest).symbol()?;
+    let suffix = clone_suffix(&rest[consumed..])?;
+    Some(demangled + &suffix)
+}
+
+/// Optimizer clones like `.cold` or `.constprop.0` follow the mangling;
+/// binutils shows each as ` [clone .name]`.
+fn clone_suffix(mut rest: &str) -> Option<String> {
+    let mut out = String::new();
+    while !rest.is_empty() {
+        let tail = rest.strip_prefix('.')?;
+        let word = tail
+            .find(|c: char| !(c.is_ascii_alphabetic() || c == '_'))
+            .unwrap_or(tail.len());
+        if word == 0 {
+            return None;
+        }
+        let mut end = 1 + word;
+        // Numbered clones such as .isra.0 keep their numbers
+        while let Some(number) = rest[end..].strip_prefix('.') {
+            let digits = number
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(number.len());
+            if digits == 0 {
+                break;
+            }
+            end += 1 + digits;
+        }
+        out += &format!(" [clone {}]", &rest[..end]);
+        rest = &rest[end..];
+    }
+    Some(out)
+}
+
+/// Guards the recursive descent against hostile input.
+const MAX_DEPTH: u32 = 256;
+
+/// Bounds the work back references can cause, since each one may repeat all
+/// of what it refers to.
+const MAX_EXPANSION: usize = 1 << 16;
+
+/// The shared reading position of both demanglers.
+struct Cursor<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<u8> {
+        self.s.get(self.pos + ahead).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn decimal(&mut self) -> Option<usize> {
+        // A zero stands alone, so that "00" is two numbers
+        if self.eat(b'0') {
+            return Some(0);
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.s[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a str> {
+        let end = self.pos.checked_add(len)?;
+        let bytes = self.s.get(self.pos..end)?;
+        self.pos = end;
+        std::str::from_utf8(bytes).ok()
+    }
+}
+
+/// Rust's original mangling: an Itanium nested name of escaped identifiers
+/// ending in a hash, which readelf leaves out.
+fn rust_legacy(rest: &str) -> Option<String> {
+    let mut cursor = Cursor {
+        s: rest.as_bytes(),
+        pos: 0,
+    };
+    if !cursor.eat(b'N') {
+        return None;
+    }
+    let mut parts = Vec::new();
+    while !cursor.eat(b'E') {
+        let len = cursor.decimal()?;
+        parts.push(cursor.take(len)?);
+    }
+    let hash = parts.pop()?;
+    let is_hash = !parts.is_empty()
+        && hash.len() == 17
+        && hash.starts_with('h')
+        && hash[1..].bytes().all(|c| c.is_ascii_hexdigit());
+    if !is_hash {
+        return None;
+    }
+    let parts = parts
+        .into_iter()
+        .map(rust_legacy_ident)
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("::"))
+}
+
+fn rust_legacy_ident(ident: &str) -> Option<String> {
+    let mut rest = ident.strip_prefix("_$").map_or(ident, |_| &ident[1..]);
+    let mut out = String::new();
+    while let Some(c) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("..") {
+            out += "::";
+            rest = tail;
+        } else if c == '$' {
+            let end = rest[1..].find('$')? + 1;
+            out.push(match &rest[1..end] {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                escape => {
+                    let code = escape.strip_prefix('u')?;
+                    char::from_u32(u32::from_str_radix(code, 16).ok()?)?
+                }
+            });
+            rest = &rest[end + 1..];
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(out)
+}
+
+/// Rust's v0 mangling, printed like rustc-demangle's alternate form (no
+/// crate hashes).
+struct RustV0<'a> {
+    cursor: Cursor<'a>,
+    depth: u32,
+    /// Parse steps left, as backrefs parse their target again.
+    steps: usize,
+    /// How many lifetimes the enclosing for<...> binders introduce.
+    bound_lifetimes: u64,
+}
+
+impl<'a> RustV0<'a> {
+    fn new(rest: &'a str) -> Self {
+        RustV0 {
+            cursor: Cursor {
+                s: rest.as_bytes(),
+                pos: 0,
+            },
+            depth: 0,
+            steps: MAX_EXPANSION,
+            bound_lifetimes: 0,
+        }
+    }
+
+    fn symbol(mut self) -> Option<String> {
+        // An optional encoding version
+        self.cursor.decimal();
+        let path = self.path(true)?;
+        // The instantiating crate isn't shown
+        if self.cursor.peek().is_some_and(|c| c.is_ascii_uppercase()) {
+            self.path(false)?;
+        }
+        Some(path)
+    }
+
+    fn enter(&mut self) -> Option<()> {
+        self.depth += 1;
+        self.steps = self.steps.checked_sub(1)?;
+        (self.depth <= MAX_DEPTH).then_some(())
+    }
+
+    fn base62(&mut self) -> Option<u64> {
+        if self.cursor.eat(b'_') {
+            return Some(0);
+        }
+        let mut value = 0u64;
+        loop {
+            let digit = match self.cursor.next()? {
+                b'_' => break,
+                c @ b'0'..=b'9' => c - b'0',
+                c @ b'a'..=b'z' => c - b'a' + 10,
+                c @ b'A'..=b'Z' => c - b'A' + 36,
+                _ => return None,
+            };
+            value = value.checked_mul(62)?.checked_add(u64::from(digit))?;
+        }
+        value.checked_add(1)
+    }
+
+    fn opt_base62(&mut self, tag: u8) -> Option<u64> {
+        if !self.cursor.eat(tag) {
+            return Some(0);
+        }
+        self.base62()?.checked_add(1)
+    }
+
+    fn ident(&mut self) -> Option<(u64, &'a str)> {
+        let disambiguator = self.opt_base62(b's')?;
+        // Punycode identifiers aren't decoded
+        if self.cursor.peek() == Some(b'u') {
+            return None;
+        }
+        let len = self.cursor.decimal()?;
+        self.cursor.eat(b'_');
+        Some((disambiguator, self.cursor.take(len)?))
+    }
+
+    /// Parses whatever a backref points back at with `parse`, then resumes.
+    fn backref<T>(&mut self, parse: fn(&mut Self) -> Option<T>) -> Option<T> {
+        let start = self.cursor.pos - 1;
+        let target = usize::try_from(self.base62()?).ok()?;
+        if target >= start {
+            return None;
+        }
+        let resume = std::mem::replace(&mut self.cursor.pos, target);
+        let value = parse(self);
+        self.cursor.pos = resume;
+        value
+    }
+
+    fn path(&mut self, in_value: bool) -> Option<String> {
+        self.enter()?;
+        let path = match self.cursor.next()? {
+            b'C' => self.ident()?.1.to_string(),
+            b'M' => {
+                self.opt_base62(b's')?;
+                self.path(false)?;
+                format!("<{}>", self.ty()?)
+            }
+            b'X' => {
+                self.opt_base62(b's')?;
+                self.path(false)?;
+                let ty = self.ty()?;
+                format!("<{} as {}>", ty, self.path(false)?)
+            }
+            b'Y' => {
+                let ty = self.ty()?;
+                format!("<{} as {}>", ty, self.path(false)?)
+            }
+            b'N' => {
+                let namespace = self.cursor.next()?;
+                let prefix = self.path(in_value)?;
+                let (disambiguator, name) = self.ident()?;
+                if namespace.is_ascii_uppercase() {
+                    let kind = match namespace {
+                        b'C' => "closure".to_string(),
+                        b'S' => "shim".to_string(),
+                        other => (other as char).to_string(),
+                    };
+                    let name = if name.is_empty() {
+                        String::new()
+                    } else {
+                        format!(":{name}")
+                    };
+                    format!("{prefix}::{{{kind}{name}#{disambiguator}}}")
+                } else if name.is_empty() {
+                    prefix
+                } else {
+                    format!("{prefix}::{name}")
+                }
+            }
+            b'I' => {
+                let path = self.path(in_value)?;
+                let mut args = Vec::new();
+                while !self.cursor.eat(b'E') {
+                    args.push(self.generic_arg()?);
+                }
+                let separator = if in_value { "::" } else { "" };
+                format!("{}{}<{}>", path, separator, args.join(", "))
+            }
+            b'B' if in_value => self.backref(|v0| v0.path(true))?,
+            b'B' => self.backref(|v0| v0.path(false))?,
+            _ => return None,
+        };
+        self.depth -= 1;
+        Some(path)
+    }
+
+    fn generic_arg(&mut self) -> Option<String> {
+        if self.cursor.eat(b'L') {
+            self.lifetime()
+        } else if self.cursor.eat(b'K') {
+            self.constant()
+        } else {
+            self.ty()
+        }
+    }
+
+    fn lifetime(&mut self) -> Option<String> {
+        let index = self.base62()?;
+        lifetime_name(self.bound_lifetimes, index)
+    }
+
+    /// Reads a for<...> binder, which `parse` is then inside of.
+    fn binder(&mut self, parse: fn(&mut Self) -> Option<String>) -> Option<String> {
+        let count = self.opt_base62(b'G')?;
+        let mut names = Vec::new();
+        for _ in 0..count {
+            self.bound_lifetimes += 1;
+            names.push(lifetime_name(self.bound_lifetimes, 1)?);
+        }
+        let inner = parse(self);
+        self.bound_lifetimes -= count;
+        match count {
+            0 => inner,
+            _ => Some(format!("for<{}> {}", names.join(", "), inner?)),
+        }
+    }
+
+    fn ty(&mut self) -> Option<String> {
+        if let Some(basic) = self.cursor.peek().and_then(rust_basic_type) {
+            self.cursor.pos += 1;
+            return Some(basic.to_string());
+        }
+        self.enter()?;
+        let c = self.cursor.peek()?;
+        if !matches!(c, b'C' | b'M' | b'X' | b'Y' | b'N' | b'I') {
+            self.cursor.pos += 1;
+        }
+        let ty = match c {
+            b'A' => {
+                let ty = self.ty()?;
+                format!("[{}; {}]", ty, self.constant()?)
+            }
+            b'S' => format!("[{}]", self.ty()?),
+            c @ (b'R' | b'Q') => {
+                let lifetime = match self.cursor.eat(b'L') {
+                    true => match self.base62()? {
+                        0 => String::new(),
+                        index => lifetime_name(self.bound_lifetimes, index)? + " ",
+                    },
+                    false => String::new(),
+                };
+                let mutable = if c == b'Q' { "mut " } else { "" };
+                format!("&{}{}{}", lifetime, mutable, self.ty()?)
+            }
+            b'P' => format!("*const {}", self.ty()?),
+            b'O' => format!("*mut {}", self.ty()?),
+            b'T' => {
+                let mut types = Vec::new();
+                while !self.cursor.eat(b'E') {
+                    types.push(self.ty()?);
+                }
+                match types.len() {
+                    1 => format!("({},)", types[0]),
+                    _ => format!("({})", types.join(", ")),
+                }
+            }
+            b'B' => self.backref(|v0| v0.ty())?,
+            b'D' => {
+                let bounds = self.binder(|v0| v0.dyn_bounds())?;
+                if !self.cursor.eat(b'L') {
+                    return None;
+                }
+                match self.base62()? {
+                    0 => format!("dyn {bounds}"),
+                    index => format!(
+                        "dyn {} + {}",
+                        bounds,
+                        lifetime_name(self.bound_lifetimes, index)?
+                    ),
+                }
+            }
+            b'F' => self.binder(|v0| v0.fn_sig())?,
+            b'C' | b'M' | b'X' | b'Y' | b'N' | b'I' => self.path(false)?,
+            _ => return None,
+        };
+        self.depth -= 1;
+        Some(ty)
+    }
+
+    fn dyn_bounds(&mut self) -> Option<String> {
+        let mut traits = Vec::new();
+        while !self.cursor.eat(b'E') {
+            let mut path = self.path(false)?;
+            let mut bindings = Vec::new();
+            while self.cursor.eat(b'p') {
+                let len = self.cursor.decimal()?;
+                self.cursor.eat(b'_');
+                let name = self.cursor.take(len)?;
+                bindings.push(format!("{} = {}", name, self.ty()?));
+            }
+            if !bindings.is_empty() {
+                let bindings = bindings.join(", ");
+                match path.strip_suffix('>') {
+                    Some(open) => path = format!("{open}, {bindings}>"),
+                    None => path = format!("{path}<{bindings}>"),
+                }
+            }
+            traits.push(path);
+        }
+        Some(traits.join(" + "))
+    }
+
+    fn fn_sig(&mut self) -> Option<String> {
+        let mut sig = String::new();
+        if self.cursor.eat(b'U') {
+            sig += "unsafe ";
+        }
+        if self.cursor.eat(b'K') {
+            let abi = if self.cursor.eat(b'C') {
+                "C".to_string()
+            } else {
+                let len = self.cursor.decimal()?;
+                self.cursor.eat(b'_');
+                self.cursor.take(len)?.replace('_', "-")
+            };
+            sig += &format!("extern \"{abi}\" ");
+        }
+        let mut params = Vec::new();
+        while !self.cursor.eat(b'E') {
+            params.push(self.ty()?);
+        }
+        sig += &format!("fn({})", params.join(", "));
+        match self.ty()?.as_str() {
+            "()" => (),
+            ret => sig += &format!(" -> {ret}"),
+        }
+        Some(sig)
+    }
+
+    fn constant(&mut self) -> Option<String> {
+        match self.cursor.next()? {
+            b'p' => return Some("_".to_string()),
+            b'B' => return self.backref(|v0| v0.constant()),
+            _ => self.cursor.pos -= 1,
+        }
+        let ty = self.cursor.next()?;
+        let negative = self.cursor.eat(b'n');
+        let start = self.cursor.pos;
+        while self.cursor.peek()?.is_ascii_hexdigit() {
+            self.cursor.pos += 1;
+        }
+        let digits = std::str::from_utf8(&self.cursor.s[start..self.cursor.pos]).ok()?;
+        self.cursor.eat(b'_').then_some(())?;
+        let value = if digits.is_empty() {
+            0
+        } else {
+            u128::from_str_radix(digits, 16).ok()?
+        };
+        match ty {
+            b'b' => match value {
+                0 => Some("false".to_string()),
+                1 => Some("true".to_string()),
+                _ => None,
+            },
+            b'c' => Some(format!("{:?}", char::from_u32(u32::try_from(value).ok()?)?)),
+            b'a' | b'h' | b'i' | b'j' | b'l' | b'm' | b'n' | b'o' | b's' | b't' | b'x' | b'y' => {
+                Some(format!("{}{}", if negative { "-" } else { "" }, value))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lifetime `index` counted outwards from the innermost bound one; 0 is the
+/// erased lifetime.
+fn lifetime_name(bound_lifetimes: u64, index: u64) -> Option<String> {
+    if index == 0 {
+        return Some("'_".to_string());
+    }
+    let depth = bound_lifetimes.checked_sub(index)?;
+    match u8::try_from(depth) {
+        Ok(depth) if depth < 26 => Some(format!("'{}", (b'a' + depth) as char)),
+        _ => Some(format!("'_{depth}")),
+    }
+}
+
+fn rust_basic_type(c: u8) -> Option<&'static str> {
+    match c {
+        b'a' => Some("i8"),
+        b'b' => Some("bool"),
+        b'c' => Some("char"),
+        b'd' => Some("f64"),
+        b'e' => Some("str"),
+        b'f' => Some("f32"),
+        b'h' => Some("u8"),
+        b'i' => Some("isize"),
+        b'j' => Some("usize"),
+        b'l' => Some("i32"),
+        b'm' => Some("u32"),
+        b'n' => Some("i128"),
+        b'o' => Some("u128"),
+        b's' => Some("i16"),
+        b't' => Some("u16"),
+        b'u' => Some("()"),
+        b'v' => Some("..."),
+        b'x' => Some("i64"),
+        b'y' => Some("u64"),
+        b'z' => Some("!"),
+        b'p' => Some("_"),
+        _ => None,
+    }
+}
+
+/// A C++ type, kept as a tree because declarators print inside out, as in
+/// `void (*)(int)`.
+#[derive(Clone, Debug)]
+enum Node {
+    Name(String),
+    Qualified(Box<Node>, String),
+    Pointer(Box<Node>),
+    LvalueRef(Box<Node>),
+    RvalueRef(Box<Node>),
+    Function(Box<Node>, Vec<Node>),
+    Array(Box<Node>, String),
+    /// A pointer to a member of the class.
+    MemberPointer(String, Box<Node>),
+    Pack(Vec<Node>),
+    PackExpansion(Box<Node>),
+}
+
+impl Node {
+    fn render(&self) -> String {
+        self.declare("")
+    }
+
+    /// This type wrapped around the declarator `inner`.
+    fn declare(&self, inner: &str) -> String {
+        match self {
+            Node::Name(name) => format!("{name}{inner}"),
+            // A qualified array is an array of qualified elements
+            Node::Qualified(ty, qualifiers) => match &**ty {
+                Node::Array(element, dimension) => Node::Array(
+                    Box::new(Node::Qualified(element.clone(), qualifiers.clone())),
+                    dimension.clone(),
+                )
+                .declare(inner),
+                // Qualifiers of a function type follow its parameters
+                Node::Function(ret, params) if inner.is_empty() => {
+                    format!("{} ({}){}", ret.render(), render_params(params), qualifiers)
+                }
+                Node::Function(ret, params) => format!(
+                    "{} ({})({}){}",
+                    ret.render(),
+                    inner,
+                    render_params(params),
+                    qualifiers
+                ),
+                _ => ty.declare(&format!("{qualifiers}{inner}")),
+            },
+            Node::Pointer(ty) => ty.declare(&format!("*{inner}")),
+            // References to references collapse, to & unless both are &&
+            Node::LvalueRef(ty) | Node::RvalueRef(ty) if matches!(**ty, Node::LvalueRef(_)) => {
+                ty.declare(inner)
+            }
+            Node::LvalueRef(ty) => match &**ty {
+                Node::RvalueRef(referent) => referent.declare(&format!("&{inner}")),
+                _ => ty.declare(&format!("&{inner}")),
+            },
+            Node::RvalueRef(ty) => ty.declare(&format!("&&{inner}")),
+            Node::Function(ret, params) if inner.is_empty() => {
+                format!("{} ({})", ret.render(), render_params(params))
+            }
+            Node::Function(ret, params) => {
+                format!("{} ({})({})", ret.render(), inner, render_params(params))
+            }
+            // The dimensions of nested arrays all follow the declarator
+            Node::Array(..) => {
+                let (element, dimensions) = self.dimensions();
+                match inner {
+                    "" => format!("{} {}", element.render(), dimensions),
+                    inner => format!("{} ({}) {}", element.render(), inner, dimensions),
+                }
+            }
+            // Only a pointer that isn't parenthesized is spaced off the type
+            Node::MemberPointer(class, member) => match member.unqualified() {
+                Node::Function(..) | Node::Array(..) => {
+                    member.declare(&format!("{class}::*{inner}"))
+                }
+                _ => member.declare(&format!(" {class}::*{inner}")),
+            },
+            Node::Pack(types) => render_list(types) + inner,
+            Node::PackExpansion(pattern) => match pattern.pack() {
+                Some(pack) => {
+                    let expanded: Vec<_> = pack
+                        .iter()
+                        .map(|element| pattern.replace_pack(element))
+                        .collect();
+                    render_list(&expanded) + inner
+                }
+                None => pattern.declare(inner) + "...",
+            },
+        }
+    }
+}
+
+impl Node {
+    /// The type under any qualifiers.
+    fn unqualified(&self) -> &Node {
+        match self {
+            Node::Qualified(ty, _) => ty.unqualified(),
+            ty => ty,
+        }
+    }
+
+    /// An array's element type and its dimensions, outermost first, as
+    /// "[12][8]". Qualifiers on an inner array belong to its elements.
+    fn dimensions(&self) -> (Node, String) {
+        match self {
+            Node::Array(ty, dimension) => {
+                let (element, dimensions) = ty.dimensions();
+                (element, format!("[{dimension}]{dimensions}"))
+            }
+            Node::Qualified(ty, qualifiers) if matches!(**ty, Node::Array(..)) => {
+                let (element, dimensions) = ty.dimensions();
+                (
+                    Node::Qualified(Box::new(element), qualifiers.clone()),
+                    dimensions,
+                )
+            }
+            ty => (ty.clone(), String::new()),
+        }
+    }
+
+    /// The argument pack a pack expansion's pattern expands.
+    fn pack(&self) -> Option<&[Node]> {
+        match self {
+            Node::Pack(elements) => Some(elements),
+            Node::Qualified(ty, _)
+            | Node::Pointer(ty)
+            | Node::LvalueRef(ty)
+            | Node::RvalueRef(ty)
+            | Node::Array(ty, _)
+            | Node::MemberPointer(_, ty) => ty.pack(),
+            Node::Function(ret, params) => {
+                ret.pack().or_else(|| params.iter().find_map(Node::pack))
+            }
+            Node::Name(_) | Node::PackExpansion(_) => None,
+        }
+    }
+
+    /// The pattern with `element` standing in for its pack.
+    fn replace_pack(&self, element: &Node) -> Node {
+        let replace = |ty: &Node| Box::new(ty.replace_pack(element));
+        match self {
+            Node::Pack(_) => element.clone(),
+            Node::Qualified(ty, qualifiers) => Node::Qualified(replace(ty), qualifiers.clone()),
+            Node::Pointer(ty) => Node::Pointer(replace(ty)),
+            Node::LvalueRef(ty) => Node::LvalueRef(replace(ty)),
+            Node::RvalueRef(ty) => Node::RvalueRef(replace(ty)),
+            Node::Array(ty, dimension) => Node::Array(replace(ty), dimension.clone()),
+            Node::MemberPointer(class, ty) => Node::MemberPointer(class.clone(), replace(ty)),
+            Node::Function(ret, params) => Node::Function(
+                replace(ret),
+                params.iter().map(|ty| ty.replace_pack(element)).collect(),
+            ),
+            Node::Name(_) | Node::PackExpansion(_) => self.clone(),
+        }
+    }
+}
+
+fn render_list(types: &[Node]) -> String {
+    types
+        .iter()
+        .map(Node::render)
+        .filter(|ty| !ty.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A parameter list, where a lone void means none.
+fn render_params(params: &[Node]) -> String {
+    match params {
+        [Node::Name(name)] if name == "void" => String::new(),
+        _ => render_list(params),
+    }
+}
+
+fn render_template_args(args: &[Node]) -> String {
+    let list = render_list(args);
+    // binutils keeps C++03's `> >`, unless an empty pack comes last
+    if args.last().is_some_and(|arg| arg.render().ends_with('>')) {
+        format!("<{list} >")
+    } else {
+        format!("<{list}>")
+    }
+}
+
+/// What a substitution refers back to. Template parameters stay unresolved,
+/// as they are to be read with the arguments in scope where they're used.
+enum Candidate {
+    Node(Node),
+    TemplateParam(usize),
+}
+
+/// What the encoding needs to know about the name it just read.
+#[derive(Default)]
+struct NameInfo {
+    /// Function templates mangle their return type.
+    template_args: bool,
+    /// ...except for constructors, destructors and conversion operators.
+    ctor_dtor_conversion: bool,
+    /// The cv and ref qualifiers of a member function.
+    qualifiers: String,
+}
+
+/// The Itanium C++ ABI mangling used by GCC and Clang.
+struct Itanium<'a> {
+    cursor: Cursor<'a>,
+    /// Everything `S_`, `S0_`, ... may refer back to.
+    substitutions: Vec<Candidate>,
+    /// The arguments `T_`, `T0_`, ... refer to.
+    template_args: Vec<Node>,
+    depth: u32,
+    /// Output left to spend on repeating substitutions and template
+    /// arguments.
+    expansion: usize,
+}
+
+impl<'a> Itanium<'a> {
+    fn new(rest: &'a str) -> Self {
+        Itanium {
+            cursor: Cursor {
+                s: rest.as_bytes(),
+                pos: 0,
+            },
+            substitutions: Vec::new(),
+            template_args: Vec::new(),
+            depth: 0,
+            expansion: MAX_EXPANSION,
+        }
+    }
+
+    fn symbol(mut self) -> Option<(String, usize)> {
+        let demangled = self.encoding(true)?;
+        Some((demangled, self.cursor.pos))
+    }
+
+    fn enter(&mut self) -> Option<()> {
+        self.depth += 1;
+        (self.depth <= MAX_DEPTH).then_some(())
+    }
+
+    fn eat_str(&mut self, prefix: &str) -> bool {
+        let found = self.cursor.s[self.cursor.pos..].starts_with(prefix.as_bytes());
+        if found {
+            self.cursor.pos += prefix.len();
+        }
+        found
+    }
+
+    /// Whether the parameters of the current function have run out.
+    fn at_params_end(&self) -> bool {
+        matches!(self.cursor.peek(), None | Some(b'E') | Some(b'.'))
+    }
+
+    fn encoding(&mut self, with_return: bool) -> Option<String> {
+        self.enter()?;
+        let special = [
+            ("TV", "vtable for "),
+            ("TT", "VTT for "),
+            ("TI", "typeinfo for "),
+            ("TS", "typeinfo name for "),
+        ];
+        for (prefix, what) in special {
+            if self.eat_str(prefix) {
+                return Some(format!("{}{}", what, self.ty()?.render()));
+            }
+        }
+        if self.eat_str("GV") {
+            return Some(format!("guard variable for {}", self.name(false)?.0));
+        }
+        if self.eat_str("GTt") {
+            return Some(format!("transaction clone for {}", self.encoding(true)?));
+        }
+        if self.eat_str("Th") {
+            self.call_offset()?;
+            return Some(format!("non-virtual thunk to {}", self.encoding(true)?));
+        }
+        if self.eat_str("Tv") {
+            self.call_offset()?;
+            self.call_offset()?;
+            return Some(format!("virtual thunk to {}", self.encoding(true)?));
+        }
+
+        let (name, info) = self.name(true)?;
+        if self.at_params_end() {
+            self.depth -= 1;
+            return Some(name);
+        }
+        let ret = if info.template_args && !info.ctor_dtor_conversion {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let mut params = Vec::new();
+        while !self.at_params_end() {
+            params.push(self.ty()?);
+        }
+        let ret = match ret {
+            Some(ret) if with_return => ret.render() + " ",
+            _ => String::new(),
+        };
+        self.depth -= 1;
+        Some(format!(
+            "{}{}({}){}",
+            ret,
+            name,
+            render_params(&params),
+            info.qualifiers
+        ))
+    }
+
+    /// A thunk's this-adjustment, which isn't shown.
+    fn call_offset(&mut self) -> Option<()> {
+        self.cursor.eat(b'n');
+        self.cursor.decimal()?;
+        self.cursor.eat(b'_').then_some(())
+    }
+
+    fn name(&mut self, top: bool) -> Option<(String, NameInfo)> {
+        match self.cursor.peek()? {
+            b'N' => self.nested_name(top),
+            b'Z' => self.local_name(top),
+            b'S' if self.cursor.peek_at(1) != Some(b't') => {
+                let name = self.substitution()?.render();
+                self.unscoped_template_args(name, top)
+            }
+            _ => {
+                let std = if self.eat_str("St") { "std::" } else { "" };
+                self.cursor.eat(b'L');
+                let (name, conversion) = self.unqualified_name()?;
+                let name = format!("{std}{name}");
+                if self.cursor.peek() == Some(b'I') {
+                    self.substitutions
+                        .push(Candidate::Node(Node::Name(name.clone())));
+                }
+                let (name, mut info) = self.unscoped_template_args(name, top)?;
+                info.ctor_dtor_conversion = conversion;
+                Some((name, info))
+            }
+        }
+    }
+
+    fn unscoped_template_args(&mut self, name: String, top: bool) -> Option<(String, NameInfo)> {
+        let mut info = NameInfo::default();
+        if self.cursor.peek() != Some(b'I') {
+            return Some((name, info));
+        }
+        let args = self.template_args()?;
+        let name = with_template_args(name, &args);
+        if top {
+            self.template_args = args;
+        }
+        info.template_args = true;
+        Some((name, info))
+    }
+
+    fn nested_name(&mut self, top: bool) -> Option<(String, NameInfo)> {
+        self.enter()?;
+        self.cursor.next();
+        let mut info = NameInfo {
+            qualifiers: self.cv_qualifiers(),
+            ..NameInfo::default()
+        };
+        if self.cursor.eat(b'R') {
+            info.qualifiers += " &";
+        } else if self.cursor.eat(b'O') {
+            info.qualifiers += " &&";
+        }
+        let mut name = String::new();
+        loop {
+            match self.cursor.peek()? {
+                b'E' => {
+                    self.cursor.pos += 1;
+                    break;
+                }
+                b'S' if name.is_empty() => {
+                    name = if self.eat_str("St") {
+                        "std".to_string()
+                    } else {
+                        self.substitution()?.render()
+                    };
+                    // Substitutions are already candidates themselves
+                    continue;
+                }
+                b'L' => {
+                    self.cursor.pos += 1;
+                    continue;
+                }
+                b'I' if !name.is_empty() => {
+                    let args = self.template_args()?;
+                    name = with_template_args(name, &args);
+                    if top {
+                        self.template_args = args;
+                    }
+                    info.template_args = true;
+                }
+                b'T' if name.is_empty() => {
+                    name = self.template_param()?.render();
+                }
+                b'C' | b'D' if !matches!(self.cursor.peek_at(1), Some(b't' | b'T' | b'C')) => {
+                    let destructor = self.cursor.next()? == b'D';
+                    match (destructor, self.cursor.next()?) {
+                        (false, b'1'..=b'5') | (true, b'0'..=b'5') => (),
+                        _ => return None,
+                    }
+                    let tilde = if destructor { "~" } else { "" };
+                    name = format!("{}::{}{}", name, tilde, class_name(&name));
+                    info.template_args = false;
+                    info.ctor_dtor_conversion = true;
+                }
+                _ => {
+                    let (component, conversion) = self.unqualified_name()?;
+                    if !name.is_empty() {
+                        name += "::";
+                    }
+                    name += &component;
+                    info.template_args = false;
+                    info.ctor_dtor_conversion = conversion;
+                }
+            }
+            // Every prefix is a candidate; the name as a whole only as a type
+            if self.cursor.peek() != Some(b'E') {
+                self.substitutions
+                    .push(Candidate::Node(Node::Name(name.clone())));
+            }
+        }
+        self.depth -= 1;
+        Some((name, info))
+    }
+
+    fn local_name(&mut self, top: bool) -> Option<(String, NameInfo)> {
+        self.cursor.next();
+        // Outside the symbol's own name, the enclosing function's template
+        // arguments must not replace the ones in scope
+        let outer_args = (!top).then(|| self.template_args.clone());
+        // binutils leaves out the enclosing function's return type
+        let function = self.encoding(false)?;
+        if !self.cursor.eat(b'E') {
+            return None;
+        }
+        let (entity, info) = if self.cursor.eat(b's') {
+            ("string literal".to_string(), NameInfo::default())
+        } else {
+            self.name(true)?
+        };
+        // A discriminator telling apart same-named entities
+        if self.cursor.eat(b'_') {
+            if self.cursor.eat(b'_') {
+                self.cursor.decimal()?;
+                self.cursor.eat(b'_').then_some(())?;
+            } else {
+                self.cursor.next()?.is_ascii_digit().then_some(())?;
+            }
+        }
+        if let Some(outer_args) = outer_args {
+            self.template_args = outer_args;
+        }
+        Some((format!("{function}::{entity}"), info))
+    }
+
+    /// r, V and K, printed in binutils' order.
+    fn cv_qualifiers(&mut self) -> String {
+        let restrict = self.cursor.eat(b'r');
+        let volatile = self.cursor.eat(b'V');
+        let constant = self.cursor.eat(b'K');
+        let mut qualifiers = String::new();
+        if constant {
+            qualifiers += " const";
+        }
+        if volatile {
+            qualifiers += " volatile";
+        }
+        if restrict {
+            qualifiers += " restrict";
+        }
+        qualifiers
+    }
+
+    /// A name component, and whether it's a conversion operator.
+    fn unqualified_name(&mut self) -> Option<(String, bool)> {
+        let (mut name, conversion) = match self.cursor.peek()? {
+            b'0'..=b'9' => (self.source_name()?, false),
+            b'U' => (self.unnamed_type_name()?, false),
+            b'a'..=b'z' => self.operator_name()?,
+            _ => return None,
+        };
+        while self.cursor.eat(b'B') {
+            name += &format!("[abi:{}]", self.source_name()?);
+        }
+        Some((name, conversion))
+    }
+
+    fn source_name(&mut self) -> Option<String> {
+        let len = self.cursor.decimal()?;
+        let ident = self.cursor.take(len)?;
+        // GCC's name for anonymous namespaces
+        let anonymous = ident.len() > 9
+            && ident.starts_with("_GLOBAL_")
+            && matches!(ident.as_bytes()[8], b'.' | b'_' | b'$')
+            && ident.as_bytes()[9] == b'N';
+        if anonymous {
+            Some("(anonymous namespace)".to_string())
+        } else {
+            Some(ident.to_string())
+        }
+    }
+
+    fn unnamed_type_name(&mut self) -> Option<String> {
+        self.cursor.next();
+        let kind = self.cursor.next()?;
+        let params = match kind {
+            b'l' => {
+                let mut params = Vec::new();
+                while !self.cursor.eat(b'E') {
+                    params.push(self.ty()?);
+                }
+                Some(params)
+            }
+            b't' => None,
+            _ => return None,
+        };
+        let number = match self.cursor.decimal() {
+            Some(n) => n + 2,
+            None => 1,
+        };
+        self.cursor.eat(b'_').then_some(())?;
+        match params {
+            Some(params) => Some(format!("{{lambda({})#{}}}", render_params(&params), number)),
+            None => Some(format!("{{unnamed type#{number}}}")),
+        }
+    }
+
+    fn operator_name(&mut self) -> Option<(String, bool)> {
+        if self.eat_str("cv") {
+            return Some((format!("operator {}", self.ty()?.render()), true));
+        }
+        if self.eat_str("li") {
+            return Some((format!("operator\"\" {}", self.source_name()?), false));
+        }
+        let code = self.cursor.take(2)?;
+        let op = match code {
+            "nw" => "new",
+            "na" => "new[]",
+            "dl" => "delete",
+            "da" => "delete[]",
+            "ps" | "pl" => "+",
+            "ng" | "mi" => "-",
+            "ad" | "an" => "&",
+            "de" | "ml" => "*",
+            "co" => "~",
+            "dv" => "/",
+            "rm" => "%",
+            "or" => "|",
+            "eo" => "^",
+            "aS" => "=",
+            "pL" => "+=",
+            "mI" => "-=",
+            "mL" => "*=",
+            "dV" => "/=",
+            "rM" => "%=",
+            "aN" => "&=",
+            "oR" => "|=",
+            "eO" => "^=",
+            "ls" => "<<",
+            "rs" => ">>",
+            "lS" => "<<=",
+            "rS" => ">>=",
+            "eq" => "==",
+            "ne" => "!=",
+            "lt" => "<",
+            "gt" => ">",
+            "le" => "<=",
+            "ge" => ">=",
+            "ss" => "<=>",
+            "nt" => "!",
+            "aa" => "&&",
+            "oo" => "||",
+            "pp" => "++",
+            "mm" => "--",
+            "cm" => ",",
+            "pm" => "->*",
+            "pt" => "->",
+            "cl" => "()",
+            "ix" => "[]",
+            "qu" => "?",
+            _ => return None,
+        };
+        let space = if op.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            " "
+        } else {
+            ""
+        };
+        Some((format!("operator{space}{op}"), false))
+    }
+
+    fn substitution(&mut self) -> Option<Node> {
+        self.cursor.next();
+        // Spelled out in full when a constructor or destructor follows,
+        // which is named after the template
+        let full = matches!(self.cursor.peek_at(1), Some(b'C' | b'D'));
+        let std = match (self.cursor.peek()?, full) {
+            (b'a', _) => Some("std::allocator"),
+            (b'b', _) => Some("std::basic_string"),
+            (b's', false) => Some("std::string"),
+            (b'i', false) => Some("std::istream"),
+            (b'o', false) => Some("std::ostream"),
+            (b'd', false) => Some("std::iostream"),
+            (b's', true) => {
+                Some("std::basic_string<char, std::char_traits<char>, std::allocator<char> >")
+            }
+            (b'i', true) => Some("std::basic_istream<char, std::char_traits<char> >"),
+            (b'o', true) => Some("std::basic_ostream<char, std::char_traits<char> >"),
+            (b'd', true) => Some("std::basic_iostream<char, std::char_traits<char> >"),
+            _ => None,
+        };
+        if let Some(std) = std {
+            self.cursor.pos += 1;
+            return Some(Node::Name(std.to_string()));
+        }
+        let mut index = 0usize;
+        if !self.cursor.eat(b'_') {
+            loop {
+                let digit = match self.cursor.next()? {
+                    b'_' => break,
+                    c @ b'0'..=b'9' => c - b'0',
+                    c @ b'A'..=b'Z' => c - b'A' + 10,
+                    _ => return None,
+                };
+                index = index.checked_mul(36)?.checked_add(usize::from(digit))?;
+            }
+            index += 1;
+        }
+        let node = match self.substitutions.get(index)? {
+            Candidate::Node(node) => node.clone(),
+            Candidate::TemplateParam(index) => self.template_args.get(*index)?.clone(),
+        };
+        self.charge(node)
+    }
+
+    /// Spends the size of a repeated node from the expansion budget.
+    fn charge(&mut self, node: Node) -> Option<Node> {
+        let size = node.render().len() + 1;
+        self.expansion = self.expansion.checked_sub(size)?;
+        Some(node)
+    }
+
+    fn template_param(&mut self) -> Option<Node> {
+        let index = self.template_param_index()?;
+        let param = self.template_args.get(index)?.clone();
+        self.charge(param)
+    }
+
+    fn template_param_index(&mut self) -> Option<usize> {
+        self.cursor.next();
+        if self.cursor.eat(b'_') {
+            return Some(0);
+        }
+        let n = self.cursor.decimal()?;
+        self.cursor.eat(b'_').then_some(())?;
+        Some(n + 1)
+    }
+
+    fn template_args(&mut self) -> Option<Vec<Node>> {
+        self.enter()?;
+        self.cursor.next();
+        let mut args = Vec::new();
+        while !self.cursor.eat(b'E') {
+            args.push(self.template_arg()?);
+        }
+        self.depth -= 1;
+        Some(args)
+    }
+
+    fn template_arg(&mut self) -> Option<Node> {
+        match self.cursor.peek()? {
+            b'L' => self.literal(),
+            b'J' => {
+                self.cursor.next();
+                let mut pack = Vec::new();
+                while !self.cursor.eat(b'E') {
+                    pack.push(self.template_arg()?);
+                }
+                Some(Node::Pack(pack))
+            }
+            // Expressions aren't demangled
+            b'X' => None,
+            _ => self.ty(),
+        }
+    }
+
+    fn literal(&mut self) -> Option<Node> {
+        self.cursor.next();
+        if self.eat_str("_Z") {
+            let name = self.encoding(true)?;
+            self.cursor.eat(b'E').then_some(())?;
+            return Some(Node::Name(name));
+        }
+        if self.eat_str("DnE") {
+            return Some(Node::Name("nullptr".to_string()));
+        }
+        let ty = match self.cursor.peek()? {
+            c if cpp_builtin_type(c).is_some() => {
+                self.cursor.pos += 1;
+                Ok(c)
+            }
+            // Enumerators and the like print as a cast
+            _ => Err(self.ty()?.render()),
+        };
+        let negative = if self.cursor.eat(b'n') { "-" } else { "" };
+        let value = self.cursor.decimal()?;
+        self.cursor.eat(b'E').then_some(())?;
+        let ty = match ty {
+            Ok(ty) => ty,
+            Err(ty) => return Some(Node::Name(format!("({ty}){negative}{value}"))),
+        };
+        let literal = match ty {
+            b'b' => match value {
+                0 => "false".to_string(),
+                1 => "true".to_string(),
+                _ => return None,
+            },
+            b'i' => format!("{negative}{value}"),
+            b'j' => format!("{negative}{value}u"),
+            b'l' => format!("{negative}{value}l"),
+            b'm' => format!("{negative}{value}ul"),
+            b'x' => format!("{negative}{value}ll"),
+            b'y' => format!("{negative}{value}ull"),
+            other => format!("({}){}{}", cpp_builtin_type(other)?, negative, value),
+        };
+        Some(Node::Name(literal))
+    }
+
+    fn ty(&mut self) -> Option<Node> {
+        let c = self.cursor.peek()?;
+        if let Some(builtin) = cpp_builtin_type(c) {
+            self.cursor.pos += 1;
+            return Some(Node::Name(builtin.to_string()));
+        }
+        self.enter()?;
+        let ty = match c {
+            b'r' | b'V' | b'K' => {
+                let qualifiers = self.cv_qualifiers();
+                match self.ty()? {
+                    // A template argument that was qualified already
+                    Node::Qualified(ty, inner) => {
+                        Node::Qualified(ty, merge_qualifiers(&qualifiers, &inner))
+                    }
+                    ty => Node::Qualified(Box::new(ty), qualifiers),
+                }
+            }
+            b'P' | b'R' | b'O' => {
+                self.cursor.pos += 1;
+                let ty = Box::new(self.ty()?);
+                match c {
+                    b'P' => Node::Pointer(ty),
+                    b'R' => Node::LvalueRef(ty),
+                    _ => Node::RvalueRef(ty),
+                }
+            }
+            b'F' => {
+                self.cursor.pos += 1;
+                self.cursor.eat(b'Y');
+                let ret = self.ty()?;
+                let mut params = Vec::new();
+                while !self.cursor.eat(b'E') {
+                    params.push(self.ty()?);
+                }
+                Node::Function(Box::new(ret), params)
+            }
+            b'M' => {
+                self.cursor.pos += 1;
+                let class = self.ty()?.render();
+                Node::MemberPointer(class, Box::new(self.ty()?))
+            }
+            b'A' => {
+                self.cursor.pos += 1;
+                let dimension = self
+                    .cursor
+                    .decimal()
+                    .map_or(String::new(), |n| n.to_string());
+                self.cursor.eat(b'_').then_some(())?;
+                Node::Array(Box::new(self.ty()?), dimension)
+            }
+            b'D' => {
+                self.cursor.pos += 1;
+                match self.cursor.next()? {
+                    b'p' => Node::PackExpansion(Box::new(self.ty()?)),
+                    b'n' => {
+                        self.depth -= 1;
+                        return Some(Node::Name("decltype(nullptr)".to_string()));
+                    }
+                    other => {
+                        self.depth -= 1;
+                        return cpp_d_builtin_type(other).map(|ty| Node::Name(ty.to_string()));
+                    }
+                }
+            }
+            b'u' => {
+                self.cursor.pos += 1;
+                Node::Name(self.source_name()?)
+            }
+            b'T' => {
+                let index = self.template_param_index()?;
+                let param = self.template_args.get(index)?.clone();
+                let param = self.charge(param)?;
+                self.substitutions.push(Candidate::TemplateParam(index));
+                if self.cursor.peek() != Some(b'I') {
+                    self.depth -= 1;
+                    return Some(param);
+                }
+                let args = self.template_args()?;
+                Node::Name(with_template_args(param.render(), &args))
+            }
+            b'S' if self.cursor.peek_at(1) != Some(b't') => {
+                let substitution = self.substitution()?;
+                if self.cursor.peek() != Some(b'I') {
+                    self.depth -= 1;
+                    return Some(substitution);
+                }
+                let args = self.template_args()?;
+                Node::Name(with_template_args(substitution.render(), &args))
+            }
+            b'N' | b'Z' | b'S' | b'0'..=b'9' => Node::Name(self.name(false)?.0),
+            _ => return None,
+        };
+        self.substitutions.push(Candidate::Node(ty.clone()));
+        self.depth -= 1;
+        Some(ty)
+    }
+}
+
+/// The last component of a scoped name without its template arguments or ABI
+/// tags, which is what a constructor is called.
+fn class_name(scoped: &str) -> &str {
+    let mut end = scoped.len();
+    let mut depth = 0;
+    for (i, c) in scoped.char_indices().rev() {
+        match c {
+            '>' | ']' => depth += 1,
+            '[' => depth -= 1,
+            '<' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    end = i;
+                }
+            }
+            ':' if depth == 0 && i < end => return without_abi_tags(&scoped[i + 1..end]),
+            _ => (),
+        }
+    }
+    without_abi_tags(&scoped[..end])
+}
+
+fn without_abi_tags(name: &str) -> &str {
+    name.find("[abi:").map_or(name, |tag| &name[..tag])
+}
+
+fn merge_qualifiers(outer: &str, inner: &str) -> String {
+    [" const", " volatile", " restrict"]
+        .into_iter()
+        .filter(|qualifier| outer.contains(qualifier) || inner.contains(qualifier))
+        .collect()
+}
+
+/// `name<args>`, spaced so that operator< doesn't run into the list.
+fn with_template_args(name: String, args: &[Node]) -> String {
+    let space = if name.ends_with('<') { " " } else { "" };
+    format!("{}{}{}", name, space, render_template_args(args))
+}
+
+fn cpp_builtin_type(c: u8) -> Option<&'static str> {
+    match c {
+        b'v' => Some("void"),
+        b'w' => Some("wchar_t"),
+        b'b' => Some("bool"),
+        b'c' => Some("char"),
+        b'a' => Some("signed char"),
+        b'h' => Some("unsigned char"),
+        b's' => Some("short"),
+        b't' => Some("unsigned short"),
+        b'i' => Some("int"),
+        b'j' => Some("unsigned int"),
+        b'l' => Some("long"),
+        b'm' => Some("unsigned long"),
+        b'x' => Some("long long"),
+        b'y' => Some("unsigned long long"),
+        b'n' => Some("__int128"),
+        b'o' => Some("unsigned __int128"),
+        b'f' => Some("float"),
+        b'd' => Some("double"),
+        b'e' => Some("long double"),
+        b'g' => Some("__float128"),
+        b'z' => Some("..."),
+        _ => None,
+    }
+}
+
+/// The builtin types spelled with a leading D.
+fn cpp_d_builtin_type(c: u8) -> Option<&'static str> {
+    match c {
+        b'i' => Some("char32_t"),
+        b's' => Some("char16_t"),
+        b'u' => Some("char8_t"),
+        b'a' => Some("auto"),
+        b'c' => Some("decltype(auto)"),
+        b'd' => Some("decimal64"),
+        b'e' => Some("decimal128"),
+        b'f' => Some("decimal32"),
+        b'h' => Some("half"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::demangle;
+
+    /// Checked against readelf -sWC on symbols by these names.
+    const CASES: &[(&str, &str)] = &[
+        // Plain functions and parameter types
+        ("_Z1fv", "f()"),
+        ("_Z1fPKc", "f(char const*)"),
+        ("_Z1fRA3_Kc", "f(char const (&) [3])"),
+        ("_Z1fA10_i", "f(int [10])"),
+        ("_Z1fA2_A3_A4_i", "f(int [2][3][4])"),
+        ("_Z1fPA2_KA3_i", "f(int const (*) [2][3])"),
+        ("_Z1fM3FooA2_A3_i", "f(int (Foo::*) [2][3])"),
+        (
+            "_ZN6icu_728Calendar13resolveFieldsEPA12_A8_Ki",
+            "icu_72::Calendar::resolveFields(int const (*) [12][8])",
+        ),
+        ("_Z1fPFviE", "f(void (*)(int))"),
+        ("_Z1fM3FooFivE", "f(int (Foo::*)())"),
+        ("_Z1fM3FooKFvvE", "f(void (Foo::*)() const)"),
+        ("_Z1fM3FooVKFivE", "f(int (Foo::*)() const volatile)"),
+        ("_Z1fM3FooKi", "f(int const Foo::*)"),
+        ("_Z1fM3FooA2_i", "f(int (Foo::*) [2])"),
+        ("_Z1fDn", "f(decltype(nullptr))"),
+        ("_ZN12_GLOBAL__N_11fEv", "(anonymous namespace)::f()"),
+        // Templates and their substitutions
+        ("_Z5twiceIiET_S0_", "int twice<int>(int)"),
+        ("_Z3maxIiET_S0_S0_", "int max<int>(int, int)"),
+        ("_Z1fIJidEEvDpT_", "void f<int, double>(int, double)"),
+        ("_ZZ5twiceIiET_S0_E5calls", "twice<int>(int)::calls"),
+        ("_ZGVZ4mainE1x", "guard variable for main::x"),
+        (
+            "_ZNSt6vectorIiSaIiEE9push_backERKi",
+            "std::vector<int, std::allocator<int> >::push_back(int const&)",
+        ),
+        (
+            "_ZSt4endlIcSt11char_traitsIcEERSt13basic_ostreamIT_T0_ES6_",
+            "std::basic_ostream<char, std::char_traits<char> >& \
+             std::endl<char, std::char_traits<char> >\
+             (std::basic_ostream<char, std::char_traits<char> >&)",
+        ),
+        // std:: abbreviations, which binutils keeps short
+        ("_ZSt4cout", "std::cout"),
+        ("_ZlsRSoRK3Foo", "operator<<(std::ostream&, Foo const&)"),
+        (
+            "_ZNSsC1ERKSs",
+            "std::basic_string<char, std::char_traits<char>, std::allocator<char> >\
+             ::basic_string(std::string const&)",
+        ),
+        (
+            "_ZNSt3mapIiSsSt4lessIiESaISt4pairIKiSsEEEixERS3_",
+            "std::map<int, std::string, std::less<int>, \
+             std::allocator<std::pair<int const, std::string> > >::operator[](int const&)",
+        ),
+        (
+            "_ZNKSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEE4sizeEv",
+            "std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char> >\
+             ::size() const",
+        ),
+        // Operators
+        ("_ZN3FooplERKS_", "Foo::operator+(Foo const&)"),
+        ("_ZN3FooixEi", "Foo::operator[](int)"),
+        ("_ZN3FooaSEOS_", "Foo::operator=(Foo&&)"),
+        ("_ZN3FoocvbEv", "Foo::operator bool()"),
+        ("_ZeqRK3FooS1_", "operator==(Foo const&, Foo const&)"),
+        ("_ZnwmPv", "operator new(unsigned long, void*)"),
+        ("_ZdlPv", "operator delete(void*)"),
+        // Constructors and destructors
+        ("_ZN3FooC2Ev", "Foo::Foo()"),
+        ("_ZN3FooD0Ev", "Foo::~Foo()"),
+        ("_ZNSt8ios_base4InitC1Ev", "std::ios_base::Init::Init()"),
+        ("_ZNSt8ios_base4InitD1Ev", "std::ios_base::Init::~Init()"),
+        (
+            "_ZN9__gnu_cxx13new_allocatorIcED2Ev",
+            "__gnu_cxx::new_allocator<char>::~new_allocator()",
+        ),
+        // Special names and clones
+        ("_ZTV3Foo", "vtable for Foo"),
+        ("_ZTI3Foo", "typeinfo for Foo"),
+        ("_ZTS3Foo", "typeinfo name for Foo"),
+        ("_ZThn8_N3Foo3barEv", "non-virtual thunk to Foo::bar()"),
+        ("_ZN3foo3barEv.cold", "foo::bar() [clone .cold]"),
+        ("_Z3fooi.constprop.0", "foo(int) [clone .constprop.0]"),
+        // Rust, without the hash or crate disambiguator
+        ("_ZN4core3fmt5write17h0123456789abcdefE", "core::fmt::write"),
+        (
+            "_ZN3std2io5stdio6_print17h0123456789abcdefE",
+            "std::io::stdio::_print",
+        ),
+        ("_RNvCs1234_7mycrate3foo", "mycrate::foo"),
+        ("_RNvNtCs1234_7mycrate3bar3baz", "mycrate::bar::baz"),
+        (
+            "_RINvNtCs1234_4core3mem4swapjEB4_",
+            "core::mem::swap::<usize>",
+        ),
+    ];
+
+    #[test]
+    fn demangles_like_binutils() {
+        for &(mangled, demangled) in CASES {
+            assert_eq!(demangle(mangled).as_deref(), Some(demangled), "{}", mangled);
+        }
+    }
+
+    #[test]
+    fn malformed_names_are_none() {
+        let nested = format!("_Z1f{}i", "P".repeat(300));
+        let cases = [
+            "",
+            "hello",
+            "_Z",
+            "_Z1",
+            "_Z4foo",
+            "_ZN3Foo",
+            "_Z3fooILi",
+            "_Z1fIi",
+            "_ZSt",
+            // Back references to nothing
+            "_Z1fS_",
+            "_Z1fT_",
+            "_Z3foov.123",
+            "_ZN4core3fmt5write17hXYZE",
+            "_RNv",
+            // Deeper than the recursion limit
+            &nested,
+        ];
+        for mangled in cases {
+            assert_eq!(demangle(mangled), None, "{}", mangled);
+        }
+    }
+}
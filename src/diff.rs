@@ -0,0 +1,268 @@
+//! Structural comparison of two ELF files for --diff: the header fields,
+//! the sections and segments, and the symbols, as a list of one-line
+//! differences rather than two full dumps.
+
+use elf::abi;
+use elf::parse::ParseError;
+use hrelf::{to_str, ElfReport, NamedSymbol, Section};
+use std::collections::{BTreeMap, HashMap};
+
+/// The differences between `old` and `new`, each a line starting with "-"
+/// for something only `old` has, "+" for something only `new` has, or "~"
+/// for something both have that changed. Empty when the two match.
+pub fn diff(old: &ElfReport, new: &ElfReport) -> Result<Vec<String>, ParseError> {
+    let mut lines = header_diff(old, new);
+    lines.extend(section_diff(old, new)?);
+    lines.extend(segment_diff(old, new));
+    lines.extend(symbol_diff(old, new)?);
+    Ok(lines)
+}
+
+/// The "field old -> new" pairs of the fields that differ.
+fn changes(fields: &[(&str, String, String)]) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| format!("{} {} -> {}", field, old, new))
+        .collect()
+}
+
+fn header_diff(old: &ElfReport, new: &ElfReport) -> Vec<String> {
+    let fields = |report: &ElfReport| {
+        let ehdr = report.header();
+        let counts = report.header_counts();
+        [
+            ("class", format!("{:?}", ehdr.class)),
+            ("data", format!("{:?}", ehdr.endianness)),
+            ("OS/ABI", to_str::e_osabi_to_string(ehdr.osabi)),
+            ("ABI version", ehdr.abiversion.to_string()),
+            ("e_type", to_str::e_type_to_string(ehdr.e_type)),
+            ("e_machine", to_str::e_machine_to_string(ehdr.e_machine)),
+            ("e_version", format!("{:#x}", ehdr.version)),
+            ("e_entry", format!("{:#x}", ehdr.e_entry)),
+            ("e_phoff", format!("{:#x}", ehdr.e_phoff)),
+            ("e_shoff", format!("{:#x}", ehdr.e_shoff)),
+            ("e_flags", format!("{:#x}", ehdr.e_flags)),
+            ("e_ehsize", ehdr.e_ehsize.to_string()),
+            ("e_phentsize", ehdr.e_phentsize.to_string()),
+            ("e_phnum", counts.phnum.to_string()),
+            ("e_shentsize", ehdr.e_shentsize.to_string()),
+            ("e_shnum", counts.shnum.to_string()),
+            ("e_shstrndx", counts.shstrndx.to_string()),
+        ]
+    };
+    let fields: Vec<_> = fields(old)
+        .into_iter()
+        .zip(fields(new))
+        .map(|((field, old), (_, new))| (field, old, new))
+        .collect();
+    changes(&fields)
+        .into_iter()
+        .map(|change| format!("~ header: {}", change))
+        .collect()
+}
+
+/// Sections are matched by name, and repeated names by their order.
+fn section_diff(old: &ElfReport, new: &ElfReport) -> Result<Vec<String>, ParseError> {
+    let (old_sections, new_sections) = (sections_by_name(old)?, sections_by_name(new)?);
+    let mut lines = Vec::new();
+    for key in old_sections.keys() {
+        if !new_sections.contains_key(key) {
+            lines.push(format!("- section {}", key.0));
+        }
+    }
+    for (key, section) in &new_sections {
+        let Some(before) = old_sections.get(key) else {
+            lines.push(format!("+ section {}", key.0));
+            continue;
+        };
+        let (a, b) = (&before.shdr, &section.shdr);
+        let mut fields = changes(&[
+            (
+                "type",
+                to_str::sh_type_to_string(a.sh_type),
+                to_str::sh_type_to_string(b.sh_type),
+            ),
+            (
+                "flags",
+                to_str::sh_flags_to_string(a.sh_flags),
+                to_str::sh_flags_to_string(b.sh_flags),
+            ),
+            (
+                "address",
+                format!("{:#x}", a.sh_addr),
+                format!("{:#x}", b.sh_addr),
+            ),
+            (
+                "offset",
+                format!("{:#x}", a.sh_offset),
+                format!("{:#x}", b.sh_offset),
+            ),
+            (
+                "size",
+                format!("{:#x}", a.sh_size),
+                format!("{:#x}", b.sh_size),
+            ),
+            (
+                "align",
+                a.sh_addralign.to_string(),
+                b.sh_addralign.to_string(),
+            ),
+        ]);
+        // Same-sized sections can still differ byte for byte
+        if a.sh_size == b.sh_size
+            && a.sh_type != abi::SHT_NOBITS
+            && old.section_data(before)? != new.section_data(section)?
+        {
+            fields.push("contents differ".to_string());
+        }
+        if !fields.is_empty() {
+            lines.push(format!("~ section {}: {}", key.0, fields.join(", ")));
+        }
+    }
+    Ok(lines)
+}
+
+/// The sections after the null one, keyed by name and the how-manyth
+/// section by that name each is.
+fn sections_by_name<'data>(
+    report: &ElfReport<'data>,
+) -> Result<BTreeMap<(String, usize), Section<'data>>, ParseError> {
+    let mut sections = BTreeMap::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for section in report.sections()?.unwrap_or_default().into_iter().skip(1) {
+        let nth = seen.entry(section.name).or_default();
+        sections.insert((section.name.to_string(), *nth), section);
+        *nth += 1;
+    }
+    Ok(sections)
+}
+
+/// Segments have no names, so they're matched by program header index.
+fn segment_diff(old: &ElfReport, new: &ElfReport) -> Vec<String> {
+    let old_phdrs = old.segments().unwrap_or_default();
+    let new_phdrs = new.segments().unwrap_or_default();
+    let mut lines = Vec::new();
+    for index in 0..old_phdrs.len().max(new_phdrs.len()) {
+        let (a, b) = match (old_phdrs.get(index), new_phdrs.get(index)) {
+            (Some(a), Some(b)) => (a, b),
+            (Some(a), None) => {
+                let p_type = to_str::p_type_to_string(a.p_type);
+                lines.push(format!("- segment {} ({})", index, p_type));
+                continue;
+            }
+            (None, Some(b)) => {
+                let p_type = to_str::p_type_to_string(b.p_type);
+                lines.push(format!("+ segment {} ({})", index, p_type));
+                continue;
+            }
+            (None, None) => unreachable!(),
+        };
+        let fields = changes(&[
+            (
+                "type",
+                to_str::p_type_to_string(a.p_type),
+                to_str::p_type_to_string(b.p_type),
+            ),
+            (
+                "flags",
+                to_str::p_flags_to_string(a.p_flags).trim_end().to_string(),
+                to_str::p_flags_to_string(b.p_flags).trim_end().to_string(),
+            ),
+            (
+                "offset",
+                format!("{:#x}", a.p_offset),
+                format!("{:#x}", b.p_offset),
+            ),
+            (
+                "address",
+                format!("{:#x}", a.p_vaddr),
+                format!("{:#x}", b.p_vaddr),
+            ),
+            (
+                "file size",
+                format!("{:#x}", a.p_filesz),
+                format!("{:#x}", b.p_filesz),
+            ),
+            (
+                "memory size",
+                format!("{:#x}", a.p_memsz),
+                format!("{:#x}", b.p_memsz),
+            ),
+            ("align", a.p_align.to_string(), b.p_align.to_string()),
+        ]);
+        if !fields.is_empty() {
+            let p_type = to_str::p_type_to_string(b.p_type);
+            lines.push(format!(
+                "~ segment {} ({}): {}",
+                index,
+                p_type,
+                fields.join(", ")
+            ));
+        }
+    }
+    lines
+}
+
+/// The parts of a symbol compared: its size, type and binding.
+type Compared = (u64, String, String);
+
+/// Symbols are matched by table and name. Their values are left out, as
+/// they shift with any change in the code before them.
+fn symbol_diff(old: &ElfReport, new: &ElfReport) -> Result<Vec<String>, ParseError> {
+    let by_name = |report: &ElfReport<'_>| -> Result<_, ParseError> {
+        let mut symbols: BTreeMap<(String, String), Vec<Compared>> = BTreeMap::new();
+        let tables = [report.symbols()?, report.dynamic_symbols()?];
+        for table in tables.into_iter().flatten() {
+            for entry in &table.entries {
+                if let Some(key) = symbol_key(table.name, entry) {
+                    let symbol = &entry.symbol;
+                    symbols.entry(key).or_default().push((
+                        symbol.st_size,
+                        to_str::st_symtype_to_string(symbol.st_symtype()),
+                        to_str::st_bind_to_string(symbol.st_bind()),
+                    ));
+                }
+            }
+        }
+        Ok(symbols)
+    };
+    let (old_symbols, new_symbols) = (by_name(old)?, by_name(new)?);
+    let mut lines = Vec::new();
+    for (key, entries) in &old_symbols {
+        let kept = new_symbols.get(key).map_or(0, Vec::len);
+        for _ in kept..entries.len() {
+            lines.push(format!("- symbol {} ({})", key.1, key.0));
+        }
+    }
+    for (key, entries) in &new_symbols {
+        let before = old_symbols.get(key).map_or(&[][..], Vec::as_slice);
+        for (i, (size, symtype, bind)) in entries.iter().enumerate() {
+            let Some((old_size, old_symtype, old_bind)) = before.get(i) else {
+                lines.push(format!("+ symbol {} ({})", key.1, key.0));
+                continue;
+            };
+            let fields = changes(&[
+                ("size", old_size.to_string(), size.to_string()),
+                ("type", old_symtype.clone(), symtype.clone()),
+                ("binding", old_bind.clone(), bind.clone()),
+            ]);
+            if !fields.is_empty() {
+                lines.push(format!(
+                    "~ symbol {} ({}): {}",
+                    key.1,
+                    key.0,
+                    fields.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// The (table, name) a symbol is matched on; None for the unnamed ones and
+/// section symbols, which have nothing to match them by.
+fn symbol_key(table: &str, entry: &NamedSymbol) -> Option<(String, String)> {
+    let unnamed = entry.name.is_empty() || entry.symbol.st_symtype() == abi::STT_SECTION;
+    (!unnamed).then(|| (table.to_string(), entry.name.to_string()))
+}
@@ -0,0 +1,788 @@
+//! Decoding of the DWARF debug sections that --debug-dump prints.
+//!
+//! Only what the dumps need is read: the .debug_line programs are run to
+//! produce their row tables, and of .debug_info just each unit's header
+//! and root DIE. Both the DWARF 2-4 layouts and DWARF 5's are understood.
+
+use crate::attributes::{read_ntbs, read_sleb128, read_uleb128};
+use elf::endian::EndianParse;
+use elf::parse::ParseError;
+
+const DW_LNS_COPY: u8 = 1;
+const DW_LNS_ADVANCE_PC: u8 = 2;
+const DW_LNS_ADVANCE_LINE: u8 = 3;
+const DW_LNS_SET_FILE: u8 = 4;
+const DW_LNS_SET_COLUMN: u8 = 5;
+const DW_LNS_NEGATE_STMT: u8 = 6;
+const DW_LNS_SET_BASIC_BLOCK: u8 = 7;
+const DW_LNS_CONST_ADD_PC: u8 = 8;
+const DW_LNS_FIXED_ADVANCE_PC: u8 = 9;
+
+const DW_LNE_END_SEQUENCE: u8 = 1;
+const DW_LNE_SET_ADDRESS: u8 = 2;
+const DW_LNE_DEFINE_FILE: u8 = 3;
+
+const DW_LNCT_PATH: u64 = 1;
+const DW_LNCT_DIRECTORY_INDEX: u64 = 2;
+
+const DW_FORM_ADDR: u64 = 0x01;
+const DW_FORM_BLOCK2: u64 = 0x03;
+const DW_FORM_BLOCK4: u64 = 0x04;
+const DW_FORM_DATA2: u64 = 0x05;
+const DW_FORM_DATA4: u64 = 0x06;
+const DW_FORM_DATA8: u64 = 0x07;
+const DW_FORM_STRING: u64 = 0x08;
+const DW_FORM_BLOCK: u64 = 0x09;
+const DW_FORM_BLOCK1: u64 = 0x0a;
+const DW_FORM_DATA1: u64 = 0x0b;
+const DW_FORM_FLAG: u64 = 0x0c;
+const DW_FORM_SDATA: u64 = 0x0d;
+const DW_FORM_STRP: u64 = 0x0e;
+const DW_FORM_UDATA: u64 = 0x0f;
+const DW_FORM_REF_ADDR: u64 = 0x10;
+const DW_FORM_REF1: u64 = 0x11;
+const DW_FORM_REF2: u64 = 0x12;
+const DW_FORM_REF4: u64 = 0x13;
+const DW_FORM_REF8: u64 = 0x14;
+const DW_FORM_REF_UDATA: u64 = 0x15;
+const DW_FORM_INDIRECT: u64 = 0x16;
+const DW_FORM_SEC_OFFSET: u64 = 0x17;
+const DW_FORM_EXPRLOC: u64 = 0x18;
+const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+const DW_FORM_STRX: u64 = 0x1a;
+const DW_FORM_ADDRX: u64 = 0x1b;
+const DW_FORM_REF_SUP4: u64 = 0x1c;
+const DW_FORM_STRP_SUP: u64 = 0x1d;
+const DW_FORM_DATA16: u64 = 0x1e;
+const DW_FORM_LINE_STRP: u64 = 0x1f;
+const DW_FORM_REF_SIG8: u64 = 0x20;
+const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+const DW_FORM_LOCLISTX: u64 = 0x22;
+const DW_FORM_RNGLISTX: u64 = 0x23;
+const DW_FORM_REF_SUP8: u64 = 0x24;
+const DW_FORM_STRX1: u64 = 0x25;
+const DW_FORM_STRX2: u64 = 0x26;
+const DW_FORM_STRX3: u64 = 0x27;
+const DW_FORM_STRX4: u64 = 0x28;
+const DW_FORM_ADDRX1: u64 = 0x29;
+const DW_FORM_ADDRX2: u64 = 0x2a;
+const DW_FORM_ADDRX3: u64 = 0x2b;
+const DW_FORM_ADDRX4: u64 = 0x2c;
+const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1f01;
+const DW_FORM_GNU_STR_INDEX: u64 = 0x1f02;
+const DW_FORM_GNU_REF_ALT: u64 = 0x1f20;
+const DW_FORM_GNU_STRP_ALT: u64 = 0x1f21;
+
+const DW_AT_NAME: u64 = 0x03;
+const DW_AT_LANGUAGE: u64 = 0x13;
+const DW_AT_COMP_DIR: u64 = 0x1b;
+const DW_AT_PRODUCER: u64 = 0x25;
+const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+
+const DW_UT_TYPE: u8 = 0x02;
+const DW_UT_SKELETON: u8 = 0x04;
+const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+/// The string sections that DWARF forms point into; empty when absent.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringSections<'data> {
+    pub debug_str: &'data [u8],
+    pub debug_line_str: &'data [u8],
+    pub debug_str_offsets: &'data [u8],
+}
+
+#[derive(Debug)]
+pub struct FileEntry<'data> {
+    pub name: &'data str,
+    pub directory: u64,
+}
+
+/// One row of the line table the state machine builds.
+#[derive(Debug, Clone, Copy)]
+pub struct LineRow {
+    pub address: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    pub end_sequence: bool,
+}
+
+/// A line number program: its header, file tables and the rows it produces.
+#[derive(Debug)]
+pub struct LineProgram<'data> {
+    /// Offset of the program within .debug_line.
+    pub offset: usize,
+    pub version: u16,
+    /// Whether the unit uses the 64-bit DWARF format.
+    pub dwarf64: bool,
+    pub address_size: u8,
+    pub min_inst_length: u8,
+    pub max_ops_per_inst: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    pub directories: Vec<&'data str>,
+    /// Indexed from 0 in DWARF 5 and from 1 before it.
+    pub files: Vec<FileEntry<'data>>,
+    pub rows: Vec<LineRow>,
+}
+
+/// Parses and runs every line number program in .debug_line.
+/// `address_size` is used for units whose header doesn't state one.
+pub fn parse_line_programs<'data, E: EndianParse>(
+    endian: E,
+    data: &'data [u8],
+    strings: StringSections<'data>,
+    address_size: u8,
+) -> Result<Vec<LineProgram<'data>>, ParseError> {
+    let mut programs = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let program = parse_line_program(endian, data, offset, strings, address_size)?;
+        offset = program.0;
+        programs.push(program.1);
+    }
+    Ok(programs)
+}
+
+/// The unit at `start`, and the offset just past it.
+fn parse_line_program<'data, E: EndianParse>(
+    endian: E,
+    data: &'data [u8],
+    start: usize,
+    strings: StringSections<'data>,
+    address_size: u8,
+) -> Result<(usize, LineProgram<'data>), ParseError> {
+    let mut offset = start;
+    let (unit_length, dwarf64) = read_initial_length(endian, data, &mut offset)?;
+    let end = offset
+        .checked_add(unit_length as usize)
+        .filter(|&end| end <= data.len())
+        .ok_or(ParseError::BadOffset(start as u64))?;
+    let unit = &data[..end];
+
+    let version = endian.parse_u16_at(&mut offset, unit)?;
+    if !(2..=5).contains(&version) {
+        return Err(ParseError::UnsupportedVersion((version as u64, 5)));
+    }
+    let mut address_size = address_size;
+    if version >= 5 {
+        address_size = endian.parse_u8_at(&mut offset, unit)?;
+        let _segment_selector_size = endian.parse_u8_at(&mut offset, unit)?;
+    }
+    let header_length = read_offset(endian, unit, &mut offset, dwarf64)?;
+    let program_start = offset
+        .checked_add(header_length as usize)
+        .filter(|&program_start| program_start <= end)
+        .ok_or(ParseError::BadOffset(offset as u64))?;
+    let min_inst_length = endian.parse_u8_at(&mut offset, unit)?;
+    let max_ops_per_inst = match version {
+        4.. => endian.parse_u8_at(&mut offset, unit)?,
+        _ => 1,
+    };
+    let default_is_stmt = endian.parse_u8_at(&mut offset, unit)? != 0;
+    let line_base = endian.parse_u8_at(&mut offset, unit)? as i8;
+    let line_range = endian.parse_u8_at(&mut offset, unit)?;
+    let opcode_base = endian.parse_u8_at(&mut offset, unit)?;
+    let mut standard_opcode_lengths = Vec::new();
+    for _ in 1..opcode_base {
+        standard_opcode_lengths.push(endian.parse_u8_at(&mut offset, unit)?);
+    }
+
+    let mut directories = Vec::new();
+    let mut files = Vec::new();
+    if version >= 5 {
+        let forms = Forms {
+            endian,
+            unit,
+            strings,
+            dwarf64,
+            address_size,
+            version,
+            str_offsets_base: None,
+        };
+        for entry in read_entries(&forms, &mut offset)? {
+            directories.push(entry.name);
+        }
+        files = read_entries(&forms, &mut offset)?;
+    } else {
+        loop {
+            match read_ntbs(unit, &mut offset)? {
+                "" => break,
+                directory => directories.push(directory),
+            }
+        }
+        loop {
+            match read_ntbs(unit, &mut offset)? {
+                "" => break,
+                name => files.push(read_file_entry(unit, &mut offset, name)?),
+            }
+        }
+    }
+
+    let mut program = LineProgram {
+        offset: start,
+        version,
+        dwarf64,
+        address_size,
+        min_inst_length,
+        max_ops_per_inst,
+        default_is_stmt,
+        line_base,
+        line_range,
+        opcode_base,
+        directories,
+        files,
+        rows: Vec::new(),
+    };
+    program.run(endian, unit, program_start, &standard_opcode_lengths)?;
+    Ok((end, program))
+}
+
+impl<'data> LineProgram<'data> {
+    /// Runs the line number state machine over the opcodes in
+    /// `unit[offset..]`, appending its rows.
+    fn run<E: EndianParse>(
+        &mut self,
+        endian: E,
+        unit: &'data [u8],
+        mut offset: usize,
+        standard_opcode_lengths: &[u8],
+    ) -> Result<(), ParseError> {
+        let initial = LineRow {
+            address: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: self.default_is_stmt,
+            end_sequence: false,
+        };
+        let min_inst_length = self.min_inst_length as u64;
+        // A zero line_range would make every special opcode divide by zero
+        let line_range = self.line_range.max(1);
+        let mut row = initial;
+        while offset < unit.len() {
+            let opcode = endian.parse_u8_at(&mut offset, unit)?;
+            if opcode >= self.opcode_base {
+                let adjusted = opcode - self.opcode_base;
+                row.address = row
+                    .address
+                    .wrapping_add((adjusted / line_range) as u64 * min_inst_length);
+                row.line = row
+                    .line
+                    .wrapping_add_signed(self.line_base as i64 + (adjusted % line_range) as i64);
+                self.rows.push(row);
+                continue;
+            }
+            match opcode {
+                0 => {
+                    let len = read_uleb128(unit, &mut offset)? as usize;
+                    let next = offset
+                        .checked_add(len)
+                        .filter(|&next| next <= unit.len() && len > 0)
+                        .ok_or(ParseError::BadOffset(offset as u64))?;
+                    let mut operand = offset + 1;
+                    match unit[offset] {
+                        DW_LNE_END_SEQUENCE => {
+                            row.end_sequence = true;
+                            self.rows.push(row);
+                            row = initial;
+                        }
+                        DW_LNE_SET_ADDRESS => {
+                            row.address = match len - 1 {
+                                2 => endian.parse_u16_at(&mut operand, unit)? as u64,
+                                4 => endian.parse_u32_at(&mut operand, unit)? as u64,
+                                8 => endian.parse_u64_at(&mut operand, unit)?,
+                                _ => return Err(ParseError::BadOffset(offset as u64)),
+                            };
+                        }
+                        DW_LNE_DEFINE_FILE => {
+                            let name = read_ntbs(unit, &mut operand)?;
+                            let entry = read_file_entry(unit, &mut operand, name)?;
+                            self.files.push(entry);
+                        }
+                        // DW_LNE_set_discriminator and vendor opcodes
+                        // don't affect the rows
+                        _ => (),
+                    }
+                    offset = next;
+                }
+                DW_LNS_COPY => {
+                    self.rows.push(row);
+                }
+                DW_LNS_ADVANCE_PC => {
+                    let advance = read_uleb128(unit, &mut offset)?;
+                    row.address = row
+                        .address
+                        .wrapping_add(advance.wrapping_mul(min_inst_length));
+                }
+                DW_LNS_ADVANCE_LINE => {
+                    row.line = row
+                        .line
+                        .wrapping_add_signed(read_sleb128(unit, &mut offset)?);
+                }
+                DW_LNS_SET_FILE => row.file = read_uleb128(unit, &mut offset)?,
+                DW_LNS_SET_COLUMN => row.column = read_uleb128(unit, &mut offset)?,
+                DW_LNS_NEGATE_STMT => row.is_stmt = !row.is_stmt,
+                DW_LNS_SET_BASIC_BLOCK => (),
+                DW_LNS_CONST_ADD_PC => {
+                    let adjusted = 255 - self.opcode_base;
+                    row.address = row
+                        .address
+                        .wrapping_add((adjusted / line_range) as u64 * min_inst_length);
+                }
+                DW_LNS_FIXED_ADVANCE_PC => {
+                    row.address = row
+                        .address
+                        .wrapping_add(endian.parse_u16_at(&mut offset, unit)? as u64);
+                }
+                // Prologue/epilogue markers, ISA and anything newer: skip the
+                // ULEB128 operands the header says the opcode takes
+                _ => {
+                    let operands = standard_opcode_lengths[opcode as usize - 1];
+                    for _ in 0..operands {
+                        read_uleb128(unit, &mut offset)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The header of one .debug_info unit and what its root DIE says about
+/// where the unit came from.
+#[derive(Debug)]
+pub struct CompileUnit<'data> {
+    /// Offset of the unit within .debug_info.
+    pub offset: usize,
+    pub length: u64,
+    /// Whether the unit uses the 64-bit DWARF format.
+    pub dwarf64: bool,
+    pub version: u16,
+    /// The DW_UT_* unit type, which only DWARF 5 headers record.
+    pub unit_type: Option<u8>,
+    pub abbrev_offset: u64,
+    pub address_size: u8,
+    pub name: Option<&'data str>,
+    pub comp_dir: Option<&'data str>,
+    pub producer: Option<&'data str>,
+    /// A DW_LANG_* code.
+    pub language: Option<u64>,
+}
+
+/// A unit's offset in .debug_info and its summary, or why it didn't parse.
+pub type UnitSummary<'data> = (usize, Result<CompileUnit<'data>, ParseError>);
+
+/// Summarizes every unit in .debug_info, in order. A unit that doesn't
+/// parse is returned as the error at its offset and the listing goes on
+/// with the next; only a unit whose length is unreadable ends it.
+pub fn parse_compile_units<'data, E: EndianParse>(
+    endian: E,
+    data: &'data [u8],
+    abbrev: &'data [u8],
+    strings: StringSections<'data>,
+) -> Vec<UnitSummary<'data>> {
+    let mut units = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let start = offset;
+        let length =
+            read_initial_length(endian, data, &mut offset).and_then(|(length, dwarf64)| {
+                offset
+                    .checked_add(length as usize)
+                    .filter(|&end| end <= data.len())
+                    .map(|end| (length, dwarf64, end))
+                    .ok_or(ParseError::BadOffset(start as u64))
+            });
+        let (length, dwarf64, end) = match length {
+            Ok(length) => length,
+            Err(err) => {
+                units.push((start, Err(err)));
+                break;
+            }
+        };
+        let header = UnitHeader {
+            start,
+            length,
+            dwarf64,
+        };
+        let unit = parse_compile_unit(endian, &data[..end], offset, header, abbrev, strings);
+        units.push((start, unit));
+        offset = end;
+    }
+    units
+}
+
+/// What's known of a unit once its initial length has been read.
+struct UnitHeader {
+    start: usize,
+    length: u64,
+    dwarf64: bool,
+}
+
+fn parse_compile_unit<'data, E: EndianParse>(
+    endian: E,
+    unit: &'data [u8],
+    mut offset: usize,
+    header: UnitHeader,
+    abbrev: &'data [u8],
+    strings: StringSections<'data>,
+) -> Result<CompileUnit<'data>, ParseError> {
+    let dwarf64 = header.dwarf64;
+    let version = endian.parse_u16_at(&mut offset, unit)?;
+    if !(2..=5).contains(&version) {
+        return Err(ParseError::UnsupportedVersion((version as u64, 5)));
+    }
+    let (unit_type, address_size, abbrev_offset);
+    if version >= 5 {
+        unit_type = Some(endian.parse_u8_at(&mut offset, unit)?);
+        address_size = endian.parse_u8_at(&mut offset, unit)?;
+        abbrev_offset = read_offset(endian, unit, &mut offset, dwarf64)?;
+        match unit_type {
+            Some(DW_UT_SKELETON | DW_UT_SPLIT_COMPILE) => offset += 8,
+            Some(DW_UT_TYPE | DW_UT_SPLIT_TYPE) => offset += if dwarf64 { 16 } else { 12 },
+            _ => (),
+        }
+    } else {
+        unit_type = None;
+        abbrev_offset = read_offset(endian, unit, &mut offset, dwarf64)?;
+        address_size = endian.parse_u8_at(&mut offset, unit)?;
+    }
+    let mut compile_unit = CompileUnit {
+        offset: header.start,
+        length: header.length,
+        dwarf64,
+        version,
+        unit_type,
+        abbrev_offset,
+        address_size,
+        name: None,
+        comp_dir: None,
+        producer: None,
+        language: None,
+    };
+
+    // A zero abbreviation code is a unit with no DIEs at all
+    let code = read_uleb128(unit, &mut offset)?;
+    if code == 0 {
+        return Ok(compile_unit);
+    }
+    let specs = find_abbrev(abbrev, abbrev_offset as usize, code)?;
+    let mut forms = Forms {
+        endian,
+        unit,
+        strings,
+        dwarf64,
+        address_size,
+        version,
+        str_offsets_base: None,
+    };
+    let mut values = Vec::new();
+    for (attribute, form, implicit_const) in specs {
+        let value = match form {
+            DW_FORM_IMPLICIT_CONST => FormValue::Sdata(implicit_const),
+            _ => forms.read(form, &mut offset)?,
+        };
+        if attribute == DW_AT_STR_OFFSETS_BASE {
+            forms.str_offsets_base = value.udata();
+        }
+        values.push((attribute, value));
+    }
+    // Indexed strings can only be looked up once the base has been seen,
+    // which may come after them
+    for (attribute, value) in values {
+        match attribute {
+            DW_AT_NAME => compile_unit.name = forms.string(value)?,
+            DW_AT_COMP_DIR => compile_unit.comp_dir = forms.string(value)?,
+            DW_AT_PRODUCER => compile_unit.producer = forms.string(value)?,
+            DW_AT_LANGUAGE => compile_unit.language = value.udata(),
+            _ => (),
+        }
+    }
+    Ok(compile_unit)
+}
+
+/// The (attribute, form, implicit constant) specs of abbreviation `code`
+/// in the table at `offset` of .debug_abbrev.
+fn find_abbrev(
+    abbrev: &[u8],
+    mut offset: usize,
+    code: u64,
+) -> Result<Vec<(u64, u64, i64)>, ParseError> {
+    loop {
+        let start = offset;
+        let entry_code = read_uleb128(abbrev, &mut offset)?;
+        if entry_code == 0 {
+            return Err(ParseError::BadOffset(start as u64));
+        }
+        let _tag = read_uleb128(abbrev, &mut offset)?;
+        let _has_children = abbrev
+            .get(offset)
+            .ok_or(ParseError::BadOffset(offset as u64))?;
+        offset += 1;
+        let mut specs = Vec::new();
+        loop {
+            let attribute = read_uleb128(abbrev, &mut offset)?;
+            let form = read_uleb128(abbrev, &mut offset)?;
+            if attribute == 0 && form == 0 {
+                break;
+            }
+            let implicit_const = match form {
+                DW_FORM_IMPLICIT_CONST => read_sleb128(abbrev, &mut offset)?,
+                _ => 0,
+            };
+            specs.push((attribute, form, implicit_const));
+        }
+        if entry_code == code {
+            return Ok(specs);
+        }
+    }
+}
+
+/// A pre-DWARF 5 file entry after its name: directory, mtime and length.
+fn read_file_entry<'data>(
+    data: &[u8],
+    offset: &mut usize,
+    name: &'data str,
+) -> Result<FileEntry<'data>, ParseError> {
+    let directory = read_uleb128(data, offset)?;
+    let _mtime = read_uleb128(data, offset)?;
+    let _length = read_uleb128(data, offset)?;
+    Ok(FileEntry { name, directory })
+}
+
+/// Reads DWARF 5's self-describing directory and file tables: a format
+/// description followed by the entries it describes.
+fn read_entries<'data, E: EndianParse>(
+    forms: &Forms<'data, E>,
+    offset: &mut usize,
+) -> Result<Vec<FileEntry<'data>>, ParseError> {
+    let unit = forms.unit;
+    let format_count = forms.endian.parse_u8_at(offset, unit)?;
+    let mut format = Vec::new();
+    for _ in 0..format_count {
+        let content_type = read_uleb128(unit, offset)?;
+        let form = read_uleb128(unit, offset)?;
+        format.push((content_type, form));
+    }
+    let count = read_uleb128(unit, offset)?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let mut entry = FileEntry {
+            name: "",
+            directory: 0,
+        };
+        for &(content_type, form) in &format {
+            let value = forms.read(form, offset)?;
+            // Timestamps, sizes and MD5s aren't shown
+            match content_type {
+                DW_LNCT_PATH => entry.name = forms.string(value)?.unwrap_or(""),
+                DW_LNCT_DIRECTORY_INDEX => entry.directory = value.udata().unwrap_or(0),
+                _ => (),
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// An attribute value, as far as the dumps interpret it.
+#[derive(Debug, Clone, Copy)]
+enum FormValue<'data> {
+    Udata(u64),
+    Sdata(i64),
+    Str(&'data str),
+    /// An offset into .debug_str or .debug_line_str.
+    StrOffset(&'data [u8], u64),
+    /// An index into .debug_str_offsets, resolved against the unit's
+    /// DW_AT_str_offsets_base.
+    StrIndex(u64),
+    /// Blocks, references and the like, which are only skipped over.
+    Other,
+}
+
+impl FormValue<'_> {
+    fn udata(self) -> Option<u64> {
+        match self {
+            FormValue::Udata(value) => Some(value),
+            FormValue::Sdata(value) => Some(value as u64),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes attribute values in one unit, whose header fixes the sizes of
+/// addresses and offsets.
+struct Forms<'data, E: EndianParse> {
+    endian: E,
+    unit: &'data [u8],
+    strings: StringSections<'data>,
+    dwarf64: bool,
+    address_size: u8,
+    version: u16,
+    /// Where the unit's entries start in .debug_str_offsets.
+    str_offsets_base: Option<u64>,
+}
+
+impl<'data, E: EndianParse> Forms<'data, E> {
+    fn read(&self, form: u64, offset: &mut usize) -> Result<FormValue<'data>, ParseError> {
+        let (endian, unit) = (self.endian, self.unit);
+        let offset_size = if self.dwarf64 { 8 } else { 4 };
+        let skip = |offset: &mut usize, len: usize| -> Result<FormValue<'data>, ParseError> {
+            *offset = offset
+                .checked_add(len)
+                .filter(|&end| end <= unit.len())
+                .ok_or(ParseError::BadOffset(*offset as u64))?;
+            Ok(FormValue::Other)
+        };
+        Ok(match form {
+            DW_FORM_DATA1 | DW_FORM_REF1 | DW_FORM_FLAG | DW_FORM_STRX1 | DW_FORM_ADDRX1 => {
+                let value = endian.parse_u8_at(offset, unit)? as u64;
+                match form {
+                    DW_FORM_STRX1 => FormValue::StrIndex(value),
+                    _ => FormValue::Udata(value),
+                }
+            }
+            DW_FORM_DATA2 | DW_FORM_REF2 | DW_FORM_STRX2 | DW_FORM_ADDRX2 => {
+                let value = endian.parse_u16_at(offset, unit)? as u64;
+                match form {
+                    DW_FORM_STRX2 => FormValue::StrIndex(value),
+                    _ => FormValue::Udata(value),
+                }
+            }
+            DW_FORM_STRX3 | DW_FORM_ADDRX3 => {
+                let bytes = unit
+                    .get(*offset..*offset + 3)
+                    .ok_or(ParseError::BadOffset(*offset as u64))?;
+                *offset += 3;
+                let value = match endian.is_little() {
+                    true => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]),
+                    false => u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]),
+                } as u64;
+                match form {
+                    DW_FORM_STRX3 => FormValue::StrIndex(value),
+                    _ => FormValue::Udata(value),
+                }
+            }
+            DW_FORM_DATA4 | DW_FORM_REF4 | DW_FORM_REF_SUP4 | DW_FORM_STRX4 | DW_FORM_ADDRX4 => {
+                let value = endian.parse_u32_at(offset, unit)? as u64;
+                match form {
+                    DW_FORM_STRX4 => FormValue::StrIndex(value),
+                    _ => FormValue::Udata(value),
+                }
+            }
+            DW_FORM_DATA8 | DW_FORM_REF8 | DW_FORM_REF_SIG8 | DW_FORM_REF_SUP8 => {
+                FormValue::Udata(endian.parse_u64_at(offset, unit)?)
+            }
+            DW_FORM_UDATA
+            | DW_FORM_REF_UDATA
+            | DW_FORM_ADDRX
+            | DW_FORM_LOCLISTX
+            | DW_FORM_RNGLISTX
+            | DW_FORM_GNU_ADDR_INDEX => FormValue::Udata(read_uleb128(unit, offset)?),
+            DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => {
+                FormValue::StrIndex(read_uleb128(unit, offset)?)
+            }
+            DW_FORM_SDATA => FormValue::Sdata(read_sleb128(unit, offset)?),
+            DW_FORM_ADDR => match self.address_size {
+                4 => FormValue::Udata(endian.parse_u32_at(offset, unit)? as u64),
+                8 => FormValue::Udata(endian.parse_u64_at(offset, unit)?),
+                size => skip(offset, size as usize)?,
+            },
+            DW_FORM_STRING => FormValue::Str(read_ntbs(unit, offset)?),
+            DW_FORM_STRP => {
+                let string_offset = read_offset(endian, unit, offset, self.dwarf64)?;
+                FormValue::StrOffset(self.strings.debug_str, string_offset)
+            }
+            DW_FORM_LINE_STRP => {
+                let string_offset = read_offset(endian, unit, offset, self.dwarf64)?;
+                FormValue::StrOffset(self.strings.debug_line_str, string_offset)
+            }
+            // Supplementary and alternate files aren't read
+            DW_FORM_SEC_OFFSET | DW_FORM_STRP_SUP | DW_FORM_GNU_REF_ALT | DW_FORM_GNU_STRP_ALT => {
+                FormValue::Udata(read_offset(endian, unit, offset, self.dwarf64)?)
+            }
+            // DWARF 2 sized references like addresses
+            DW_FORM_REF_ADDR if self.version <= 2 => skip(offset, self.address_size as usize)?,
+            DW_FORM_REF_ADDR => skip(offset, offset_size)?,
+            DW_FORM_FLAG_PRESENT => FormValue::Udata(1),
+            DW_FORM_DATA16 => skip(offset, 16)?,
+            DW_FORM_BLOCK1 => {
+                let len = endian.parse_u8_at(offset, unit)? as usize;
+                skip(offset, len)?
+            }
+            DW_FORM_BLOCK2 => {
+                let len = endian.parse_u16_at(offset, unit)? as usize;
+                skip(offset, len)?
+            }
+            DW_FORM_BLOCK4 => {
+                let len = endian.parse_u32_at(offset, unit)? as usize;
+                skip(offset, len)?
+            }
+            DW_FORM_BLOCK | DW_FORM_EXPRLOC => {
+                let len = read_uleb128(unit, offset)? as usize;
+                skip(offset, len)?
+            }
+            DW_FORM_INDIRECT => {
+                let form = read_uleb128(unit, offset)?;
+                self.read(form, offset)?
+            }
+            _ => return Err(ParseError::BadOffset(*offset as u64)),
+        })
+    }
+
+    /// The string a value names, or None for values that aren't strings.
+    fn string(&self, value: FormValue<'data>) -> Result<Option<&'data str>, ParseError> {
+        let (section, string_offset) = match value {
+            FormValue::Str(string) => return Ok(Some(string)),
+            FormValue::StrOffset(section, string_offset) => (section, string_offset),
+            FormValue::StrIndex(index) => {
+                let offsets = self.strings.debug_str_offsets;
+                // Without DW_AT_str_offsets_base, the entries follow the
+                // first unit's header
+                let base = self
+                    .str_offsets_base
+                    .unwrap_or(if self.dwarf64 { 16 } else { 8 });
+                let entry_size = if self.dwarf64 { 8 } else { 4 };
+                let mut entry = index
+                    .checked_mul(entry_size)
+                    .and_then(|entry| entry.checked_add(base))
+                    .ok_or(ParseError::IntegerOverflow)? as usize;
+                let string_offset = read_offset(self.endian, offsets, &mut entry, self.dwarf64)?;
+                (self.strings.debug_str, string_offset)
+            }
+            _ => return Ok(None),
+        };
+        let mut string_offset = string_offset as usize;
+        read_ntbs(section, &mut string_offset).map(Some)
+    }
+}
+
+/// A unit length, which is escaped to 64 bits by 0xffffffff.
+fn read_initial_length<E: EndianParse>(
+    endian: E,
+    data: &[u8],
+    offset: &mut usize,
+) -> Result<(u64, bool), ParseError> {
+    match endian.parse_u32_at(offset, data)? {
+        0xffff_ffff => Ok((endian.parse_u64_at(offset, data)?, true)),
+        length => Ok((length as u64, false)),
+    }
+}
+
+/// A section offset, 8 bytes wide in 64-bit DWARF and 4 otherwise.
+fn read_offset<E: EndianParse>(
+    endian: E,
+    data: &[u8],
+    offset: &mut usize,
+    dwarf64: bool,
+) -> Result<u64, ParseError> {
+    match dwarf64 {
+        true => endian.parse_u64_at(offset, data),
+        false => Ok(endian.parse_u32_at(offset, data)? as u64),
+    }
+}
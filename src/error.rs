@@ -0,0 +1,71 @@
+//! The failures to read or parse a file that hrelf reports instead of
+//! panicking, each carrying the file it came from so a one-line
+//! "<file>: <message>" is enough.
+
+use elf::parse::ParseError;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input file couldn't be read.
+    Io(PathBuf, std::io::Error),
+    /// The input doesn't start with the ELF magic.
+    NotElf(PathBuf),
+    /// The input is a thin archive, whose members live in other files.
+    ThinArchive(PathBuf),
+    /// The input looks like an ar archive but its member headers don't parse.
+    Archive(PathBuf, ParseError),
+    /// The ELF header didn't parse, so nothing else can be shown.
+    Header(PathBuf, ParseError),
+    /// A table one of the reports needs didn't parse.
+    Table(PathBuf, &'static str, ParseError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Every message leads with the file, as in "<file>: <message>"
+        match self {
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::NotElf(path) => write!(f, "{}: not an ELF file", path.display()),
+            Error::ThinArchive(path) => write!(
+                f,
+                "{}: a thin archive; run hrelf on the member files it names instead",
+                path.display()
+            ),
+            Error::Archive(path, err) => {
+                write!(
+                    f,
+                    "{}: failed to parse the archive: {}",
+                    path.display(),
+                    err
+                )
+            }
+            Error::Header(path, err) => {
+                write!(
+                    f,
+                    "{}: failed to parse the ELF header: {}",
+                    path.display(),
+                    err
+                )
+            }
+            Error::Table(path, table, err) => write!(
+                f,
+                "{}: failed to parse the {}: {}",
+                path.display(),
+                table,
+                err
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, err) => Some(err),
+            Error::NotElf(_) | Error::ThinArchive(_) => None,
+            Error::Archive(_, err) | Error::Header(_, err) | Error::Table(_, _, err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Bucket chain lengths of the symbol hash tables (.hash and .gnu.hash), which is what -I charts.
+//!
+//! The elf crate only exposes lookups on these tables, so the bucket and
+//! chain arrays are walked here directly.
+
+use elf::endian::EndianParse;
+use elf::file::Class;
+use elf::parse::ParseError;
+
+/// The end of `count` words of `size` bytes starting at `start`, or an
+/// error if they run past the end of `data`.
+fn words_end(data: &[u8], start: usize, count: u32, size: usize) -> Result<usize, ParseError> {
+    let end = (count as usize)
+        .checked_mul(size)
+        .and_then(|len| len.checked_add(start))
+        .ok_or(ParseError::IntegerOverflow)?;
+    if end > data.len() {
+        return Err(ParseError::SliceReadError((start, end)));
+    }
+    Ok(end)
+}
+
+/// The bucket array of a .gnu.hash section and where its chains start.
+struct GnuHash {
+    symoffset: u32,
+    buckets: Vec<u32>,
+    chains_start: usize,
+}
+
+impl GnuHash {
+    fn parse<E: EndianParse>(endian: E, class: Class, data: &[u8]) -> Result<Self, ParseError> {
+        let mut offset = 0;
+        let nbucket = endian.parse_u32_at(&mut offset, data)?;
+        let symoffset = endian.parse_u32_at(&mut offset, data)?;
+        let nbloom = endian.parse_u32_at(&mut offset, data)?;
+        let _nshift = endian.parse_u32_at(&mut offset, data)?;
+        // Bloom filter words are the native word size
+        let bloom_word = match class {
+            Class::ELF32 => 4,
+            Class::ELF64 => 8,
+        };
+        offset = words_end(data, offset, nbloom, bloom_word)?;
+
+        // Only reserve for as many buckets as the section can hold
+        words_end(data, offset, nbucket, 4)?;
+        let mut buckets = Vec::with_capacity(nbucket as usize);
+        for _ in 0..nbucket {
+            buckets.push(endian.parse_u32_at(&mut offset, data)?);
+        }
+        Ok(GnuHash {
+            symoffset,
+            buckets,
+            chains_start: offset,
+        })
+    }
+
+    /// The symbol index one past the end of the chain starting at `start`.
+    fn chain_end<E: EndianParse>(
+        &self,
+        endian: E,
+        data: &[u8],
+        start: u32,
+    ) -> Result<u32, ParseError> {
+        // Chain entries start at the first hashed symbol, symoffset
+        let index = start
+            .checked_sub(self.symoffset)
+            .ok_or(ParseError::IntegerOverflow)?;
+        let mut offset = self.chains_start + index as usize * 4;
+        let mut end = start;
+        loop {
+            end += 1;
+            // The low bit marks the last entry of a chain
+            if endian.parse_u32_at(&mut offset, data)? & 1 != 0 {
+                return Ok(end);
+            }
+        }
+    }
+}
+
+/// The length of every bucket's chain in a .gnu.hash section.
+pub fn gnu_chain_lengths<E: EndianParse>(
+    endian: E,
+    class: Class,
+    data: &[u8],
+) -> Result<Vec<u32>, ParseError> {
+    let hash = GnuHash::parse(endian, class, data)?;
+    hash.buckets
+        .iter()
+        .map(|&start| match start {
+            0 => Ok(0),
+            start => Ok(hash.chain_end(endian, data, start)? - start),
+        })
+        .collect()
+}
+
+/// The number of dynamic symbols a .gnu.hash table implies: one past the
+/// end of its last chain, or symoffset if every bucket is empty. Only the
+/// hashed symbols are chained, so this is all the loader knows of the
+/// table's size.
+pub fn gnu_symbol_count<E: EndianParse>(
+    endian: E,
+    class: Class,
+    data: &[u8],
+) -> Result<u32, ParseError> {
+    let hash = GnuHash::parse(endian, class, data)?;
+    let mut count = hash.symoffset;
+    for &start in &hash.buckets {
+        // Chains are laid out in bucket order, so the last one is enough
+        if start != 0 {
+            count = count.max(hash.chain_end(endian, data, start)?);
+        }
+    }
+    Ok(count)
+}
+
+/// The number of dynamic symbols a SysV .hash table covers, its nchain.
+pub fn sysv_symbol_count<E: EndianParse>(endian: E, data: &[u8]) -> Result<u32, ParseError> {
+    let mut offset = 4;
+    endian.parse_u32_at(&mut offset, data)
+}
+
+/// The length of every bucket's chain in a SysV .hash section.
+pub fn sysv_chain_lengths<E: EndianParse>(endian: E, data: &[u8]) -> Result<Vec<u32>, ParseError> {
+    let mut offset = 0;
+    let nbucket = endian.parse_u32_at(&mut offset, data)?;
+    let nchain = endian.parse_u32_at(&mut offset, data)?;
+    // Only reserve for as many buckets as the section can hold, chains included
+    let buckets_end = words_end(data, offset, nbucket, 4)?;
+    words_end(data, buckets_end, nchain, 4)?;
+    let mut buckets = Vec::with_capacity(nbucket as usize);
+    for _ in 0..nbucket {
+        buckets.push(endian.parse_u32_at(&mut offset, data)?);
+    }
+    let chains_start = offset;
+
+    let mut lengths = Vec::with_capacity(buckets.len());
+    for start in buckets {
+        let mut length = 0;
+        let mut index = start;
+        // Chains end at STN_UNDEF; a chain longer than the table is looping
+        while index != 0 {
+            if length >= nchain {
+                return Err(ParseError::BadOffset(index as u64));
+            }
+            length += 1;
+            let mut offset = chains_start + index as usize * 4;
+            index = endian.parse_u32_at(&mut offset, data)?;
+        }
+        lengths.push(length);
+    }
+    Ok(lengths)
+}
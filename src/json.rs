@@ -0,0 +1,157 @@
+//! Just enough of a JSON document model to serialize the parsed tables.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i128),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(&'static str, Value)>),
+}
+
+impl Value {
+    /// A raw constant alongside its decoded name, e.g. `{"raw": 3, "name": "ET_DYN"}`
+    pub fn named(raw: impl Into<Value>, name: impl Into<Value>) -> Value {
+        Value::Object(vec![("raw", raw.into()), ("name", name.into())])
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Value {
+            fn from(n: $t) -> Self {
+                Value::Int(n as i128)
+            }
+        })*
+    };
+}
+
+impl_from_int!(u8, u16, u32, u64, usize, i64);
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Self {
+        Value::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::String(s) => write_str(f, s),
+            Value::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Value::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_str(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_are_escaped() {
+        let cases = [
+            ("plain", r#""plain""#),
+            ("say \"hi\"", r#""say \"hi\"""#),
+            (r"C:\path", r#""C:\\path""#),
+            ("a\nb\rc\td", r#""a\nb\rc\td""#),
+            ("\u{0}\u{1}\u{1f}", r#""\u0000\u0001\u001f""#),
+            // Only control characters need escapes; the rest is UTF-8
+            ("\u{7f}é→", "\"\u{7f}é→\""),
+        ];
+        for (string, json) in cases {
+            assert_eq!(Value::from(string).to_string(), json);
+        }
+    }
+
+    #[test]
+    fn keys_are_escaped() {
+        let object = Value::Object(vec![("a\"b", Value::Null)]);
+        assert_eq!(object.to_string(), r#"{"a\"b":null}"#);
+    }
+
+    #[test]
+    fn values_nest() {
+        let value = Value::Object(vec![
+            ("e_type", Value::named(3u16, "ET_DYN")),
+            ("flags", vec![true, false].into()),
+            ("link", Value::from(None::<u32>)),
+            ("empty", Value::Array(Vec::new())),
+            ("table", Value::Object(Vec::new())),
+            ("offset", Value::from(u64::MAX)),
+            ("addend", Value::from(-8i64)),
+        ]);
+        assert_eq!(
+            value.to_string(),
+            concat!(
+                r#"{"e_type":{"raw":3,"name":"ET_DYN"},"flags":[true,false],"link":null,"#,
+                r#""empty":[],"table":{},"offset":18446744073709551615,"addend":-8}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,1725 @@
+//! The parsing half of hrelf: [`ElfReport`] wraps an ELF image and hands its
+//! tables out as plain data, leaving all formatting to the caller.
+//!
+//! ```no_run
+//! let data = std::fs::read("a.out").unwrap();
+//! let report = hrelf::ElfReport::parse(&data).unwrap();
+//! for section in report.sections().unwrap().unwrap_or_default() {
+//!     println!("{} at 0x{:x}", section.name, section.shdr.sh_addr);
+//! }
+//! ```
+
+pub mod archive;
+pub mod attributes;
+pub mod demangle;
+pub mod dwarf;
+mod error;
+pub mod hash;
+pub mod r_type;
+pub mod symver;
+pub mod to_str;
+pub mod unwind;
+
+pub use elf;
+pub use error::Error;
+
+use elf::abi;
+use elf::compression::CompressionHeader;
+use elf::dynamic::Dyn;
+use elf::endian::{AnyEndian, EndianParse};
+use elf::file::FileHeader;
+use elf::note::{Note, NoteAny, NoteGnuAbiTag, NoteGnuBuildId};
+use elf::parse::{ParseAt, ParseError};
+use elf::section::SectionHeader;
+use elf::segment::ProgramHeader;
+use elf::string_table::StringTable;
+use elf::symbol::Symbol;
+use elf::ElfBytes;
+use std::collections::{HashMap, HashSet};
+
+/// e_phnum, e_shnum and e_shstrndx as they apply, after PN_XNUM, a zero
+/// e_shnum and SHN_XINDEX send them to section 0.
+#[derive(Debug, Clone, Copy)]
+pub struct HeaderCounts {
+    pub phnum: u32,
+    pub shnum: u64,
+    pub shstrndx: u32,
+}
+
+/// A section header together with its name from the section header string table.
+#[derive(Debug, Clone, Copy)]
+pub struct Section<'data> {
+    pub name: &'data str,
+    pub shdr: SectionHeader,
+}
+
+/// The name of a section or symbol whose sh_name or st_name doesn't point
+/// at a string in its table. The bad offset is still in the header, for
+/// printers that want to show it.
+pub const CORRUPT_NAME: &str = "<corrupt>";
+
+/// elf::abi has no GRP_* constants; this is the only flag in use.
+pub const GRP_COMDAT: u32 = 0x1;
+
+/// An SHT_GROUP section: its flag word, signature and member sections.
+#[derive(Debug)]
+pub struct SectionGroup<'data> {
+    pub index: usize,
+    pub name: &'data str,
+    pub flags: u32,
+    /// The name of the sh_info symbol, or of its section for STT_SECTION.
+    pub signature: &'data str,
+    pub members: Vec<(u32, &'data str)>,
+}
+
+/// The dynamic table up to and including its first DT_NULL.
+#[derive(Debug)]
+pub struct Dynamic {
+    /// File offset of the table, from SHT_DYNAMIC or else PT_DYNAMIC.
+    pub offset: u64,
+    pub entries: Vec<Dyn>,
+}
+
+/// The libraries a dynamic section names, as strings of its DT_STRTAB.
+#[derive(Debug, Default)]
+pub struct Libraries<'data> {
+    pub needed: Vec<&'data str>,
+    pub soname: Option<&'data str>,
+    pub rpath: Option<&'data str>,
+    pub runpath: Option<&'data str>,
+}
+
+/// The constructors and destructors a dynamic section names, in the order
+/// the loader runs each list.
+#[derive(Debug, Default)]
+pub struct InitFini<'data> {
+    pub init: Option<Function<'data>>,
+    pub fini: Option<Function<'data>>,
+    pub preinit_array: Vec<Function<'data>>,
+    pub init_array: Vec<Function<'data>>,
+    pub fini_array: Vec<Function<'data>>,
+}
+
+/// A function address, with the name of the symbol starting there if any.
+#[derive(Debug, Clone, Copy)]
+pub struct Function<'data> {
+    pub address: u64,
+    pub name: Option<&'data str>,
+}
+
+/// One SHT_REL or SHT_RELA section; `shdr.sh_type` tells which.
+#[derive(Debug)]
+pub struct RelocationSection<'data> {
+    pub name: &'data str,
+    pub shdr: SectionHeader,
+    pub relocations: Vec<Relocation<'data>>,
+}
+
+#[derive(Debug)]
+pub struct Relocation<'data> {
+    pub r_offset: u64,
+    pub r_sym: u32,
+    pub r_type: u32,
+    /// Only SHT_RELA entries carry an explicit addend.
+    pub r_addend: Option<i64>,
+    /// The referenced symbol, unless r_sym is 0 or the linked table is missing.
+    pub symbol: Option<RelocationSymbol<'data>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RelocationSymbol<'data> {
+    pub value: u64,
+    /// Section symbols are named after their section.
+    pub name: &'data str,
+}
+
+/// A symbol table section and its entries.
+#[derive(Debug)]
+pub struct Symbols<'data> {
+    pub name: &'data str,
+    pub entries: Vec<NamedSymbol<'data>>,
+}
+
+#[derive(Debug)]
+pub struct NamedSymbol<'data> {
+    pub name: &'data str,
+    pub symbol: Symbol,
+    /// The symbol's .gnu.version entry, for dynamic symbols with a named version.
+    pub version: Option<VersionIndex<'data>>,
+    /// For st_shndx SHN_XINDEX, the real section index, from the table's
+    /// SHT_SYMTAB_SHNDX section.
+    pub xindex: Option<u32>,
+}
+
+impl NamedSymbol<'_> {
+    /// The index of the section the symbol is defined in; None for
+    /// SHN_UNDEF and the other reserved st_shndx values.
+    pub fn section_index(&self) -> Option<usize> {
+        section_index(&self.symbol, self.xindex)
+    }
+}
+
+/// A symbol table entry found by name.
+#[derive(Debug)]
+pub struct SymbolMatch<'data> {
+    /// The table it was found in, .symtab or .dynsym.
+    pub table: &'data str,
+    pub index: usize,
+    pub entry: NamedSymbol<'data>,
+}
+
+/// Where an address falls, as --addr reports it.
+#[derive(Debug)]
+pub enum AddressLookup<'data> {
+    /// The tightest defined symbol whose [st_value, st_value + st_size)
+    /// holds the address, and the section it's in.
+    Symbol {
+        entry: NamedSymbol<'data>,
+        section: Option<Section<'data>>,
+    },
+    /// No symbol covers the address, but this allocated section does.
+    Section(Section<'data>),
+    Nowhere,
+}
+
+/// How many symbols are defined in an allocated section, for
+/// --section-symbol-counts.
+#[derive(Debug)]
+pub struct SectionSymbolCount<'data> {
+    pub index: usize,
+    pub section: Section<'data>,
+    pub symbols: usize,
+    /// The sum of those symbols' st_size.
+    pub total_size: u64,
+}
+
+/// The .eh_frame_hdr section and its decoded lookup table.
+#[derive(Debug)]
+pub struct UnwindTable<'data> {
+    pub section: Section<'data>,
+    pub hdr: unwind::EhFrameHdr,
+}
+
+/// A symbol hash section and the length of each of its bucket chains.
+#[derive(Debug)]
+pub struct HashHistogram<'data> {
+    pub section: Section<'data>,
+    pub chain_lengths: Vec<u32>,
+}
+
+/// A stretch of the file that one of the headers lays claim to.
+#[derive(Debug, Clone, Copy)]
+pub enum Extent<'data> {
+    ElfHeader,
+    ProgramHeaders,
+    SectionHeaders,
+    Section(usize, &'data str),
+    Segment(usize),
+}
+
+/// Something --check found wrong with where the headers put things.
+#[derive(Debug)]
+pub enum Anomaly<'data> {
+    /// [offset, offset + size) runs past the end of the file.
+    OutOfBounds {
+        extent: Extent<'data>,
+        offset: u64,
+        size: u64,
+    },
+    /// The second extent starts inside the first.
+    Overlap(Extent<'data>, Extent<'data>),
+    /// A section or segment whose type says it holds a table, but holds
+    /// no bytes.
+    Empty(Extent<'data>),
+    /// Two PT_LOAD segments, by program header index, whose
+    /// [p_vaddr, p_vaddr + p_memsz) ranges overlap.
+    LoadOverlap(usize, usize),
+    /// A PT_LOAD segment with more bytes in the file than in memory.
+    FileSizeExceedsMemSize(usize),
+    ExecutableStack(ExecutableStack),
+}
+
+/// Why a program or library will run with an executable stack.
+#[derive(Debug, Clone, Copy)]
+pub enum ExecutableStack {
+    /// The PT_GNU_STACK segment at this program header index has PF_X set.
+    Requested(usize),
+    /// There's no PT_GNU_STACK segment, and on this machine the loader then
+    /// maps the stack executable.
+    ByDefault,
+}
+
+/// Where a group of notes was read from.
+#[derive(Debug)]
+pub enum NoteSource<'data> {
+    Section(&'data str),
+    /// Used only when the file has no SHT_NOTE sections.
+    Segment(ProgramHeader),
+}
+
+#[derive(Debug)]
+pub struct Notes<'data> {
+    pub source: NoteSource<'data>,
+    pub notes: Vec<Note<'data>>,
+    /// The file offset of a note that didn't parse, where the notes stop.
+    pub corrupt: Option<u64>,
+}
+
+/// One VERSYM entry with the version its index refers to.
+#[derive(Debug, Clone, Copy)]
+pub struct VersionIndex<'data> {
+    pub index: u16,
+    pub hidden: bool,
+    /// None for VER_NDX_LOCAL, VER_NDX_GLOBAL and indexes nothing defines.
+    pub name: Option<&'data str>,
+    /// Whether the version is required from another object (.gnu.version_r)
+    /// rather than defined by this one (.gnu.version_d).
+    pub needed: bool,
+}
+
+/// A GNU symbol versioning section, with the name of its sh_link section.
+#[derive(Debug)]
+pub enum VersionSection<'data> {
+    Symbols {
+        section: Section<'data>,
+        link: &'data str,
+        versions: Vec<VersionIndex<'data>>,
+    },
+    Needs {
+        section: Section<'data>,
+        link: &'data str,
+        needs: Vec<symver::VerNeed<'data>>,
+    },
+    Definitions {
+        section: Section<'data>,
+        link: &'data str,
+        defs: Vec<symver::VerDef<'data>>,
+    },
+}
+
+/// A parsed ELF image. Each accessor parses only the tables it returns, so a
+/// damaged table only fails the calls that need it.
+pub struct ElfReport<'data> {
+    data: &'data [u8],
+    file: ElfBytes<'data, AnyEndian>,
+}
+
+impl<'data> ElfReport<'data> {
+    pub fn parse(data: &'data [u8]) -> Result<Self, ParseError> {
+        let file = ElfBytes::<AnyEndian>::minimal_parse(data)?;
+        Ok(ElfReport { data, file })
+    }
+
+    /// The underlying elf crate view, for anything not covered here.
+    pub fn file(&self) -> &ElfBytes<'data, AnyEndian> {
+        &self.file
+    }
+
+    pub fn ident(&self) -> &'data [u8] {
+        &self.data[..abi::EI_NIDENT]
+    }
+
+    pub fn header(&self) -> &FileHeader<AnyEndian> {
+        &self.file.ehdr
+    }
+
+    /// The header's table sizes with their escapes followed: counts too big
+    /// for 16 bits are kept in section 0's header instead. Where section 0
+    /// can't be read the header's own values stand.
+    pub fn header_counts(&self) -> HeaderCounts {
+        header_counts(&self.file.ehdr, self.data)
+    }
+
+    /// The section headers, or None when the file has no section header table.
+    /// e_shstrndx may be SHN_UNDEF, which only loses the names ("<no-strings>").
+    pub fn sections(&self) -> Result<Option<Vec<Section<'data>>>, ParseError> {
+        let (shdrs, strtab) = self.file.section_headers_with_strtab()?;
+        let Some(shdrs) = shdrs else {
+            return Ok(None);
+        };
+        shdrs
+            .iter()
+            .map(|shdr| {
+                let name = match &strtab {
+                    Some(strtab) => strtab.get(shdr.sh_name as usize).unwrap_or(CORRUPT_NAME),
+                    None => "<no-strings>",
+                };
+                Ok(Section { name, shdr })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+
+    /// The section a command-line selector names: a section index, or
+    /// else the first section with that name.
+    pub fn find_section(
+        &self,
+        selector: &str,
+    ) -> Result<Option<(usize, Section<'data>)>, ParseError> {
+        let sections = self.sections()?.unwrap_or_default();
+        let found = match selector.parse::<usize>() {
+            Ok(index) => sections.get(index).map(|section| (index, *section)),
+            Err(_) => sections
+                .iter()
+                .position(|section| section.name == selector)
+                .map(|index| (index, sections[index])),
+        };
+        Ok(found)
+    }
+
+    /// The section's bytes as stored in the file; empty for SHT_NOBITS.
+    pub fn section_data(&self, section: &Section) -> Result<&'data [u8], ParseError> {
+        let (data, _) = self.file.section_data(&section.shdr)?;
+        Ok(data)
+    }
+
+    /// The compression header of an SHF_COMPRESSED section.
+    pub fn compression_header(
+        &self,
+        section: &Section,
+    ) -> Result<Option<CompressionHeader>, ParseError> {
+        let (_, chdr) = self.file.section_data(&section.shdr)?;
+        Ok(chdr)
+    }
+
+    /// Every SHT_GROUP section, in section header order.
+    pub fn section_groups(&self) -> Result<Vec<SectionGroup<'data>>, ParseError> {
+        let sections = self.sections()?.unwrap_or_default();
+        let endian = self.file.ehdr.endianness;
+        let mut groups = Vec::new();
+        for (index, section) in sections.iter().enumerate() {
+            let shdr = &section.shdr;
+            if shdr.sh_type != abi::SHT_GROUP {
+                continue;
+            }
+            // The signature symbol lives in the sh_link symbol table
+            let symtab_shdr = sections
+                .get(shdr.sh_link as usize)
+                .ok_or(ParseError::BadOffset(shdr.sh_link as u64))?
+                .shdr;
+            let strtab_shdr = sections
+                .get(symtab_shdr.sh_link as usize)
+                .ok_or(ParseError::BadOffset(symtab_shdr.sh_link as u64))?
+                .shdr;
+            let (symtab_data, _) = self.file.section_data(&symtab_shdr)?;
+            let symtab = elf::symbol::SymbolTable::new(endian, self.file.ehdr.class, symtab_data);
+            let symbol = symtab.get(shdr.sh_info as usize)?;
+            let xindex = self
+                .extended_indices(shdr.sh_link as usize)?
+                .and_then(|xindices| xindices.get(shdr.sh_info as usize));
+            let target = section_index(&symbol, xindex).and_then(|index| sections.get(index));
+            let signature = match target {
+                Some(target) if symbol.st_symtype() == abi::STT_SECTION => target.name,
+                _ => self
+                    .file
+                    .section_data_as_strtab(&strtab_shdr)?
+                    .get(symbol.st_name as usize)
+                    .unwrap_or(CORRUPT_NAME),
+            };
+
+            let (data, _) = self.file.section_data(shdr)?;
+            let mut offset = 0;
+            let flags = endian.parse_u32_at(&mut offset, data)?;
+            let mut members = Vec::new();
+            while offset + 4 <= data.len() {
+                let member = endian.parse_u32_at(&mut offset, data)?;
+                let name = sections
+                    .get(member as usize)
+                    .map_or(CORRUPT_NAME, |section| section.name);
+                members.push((member, name));
+            }
+            groups.push(SectionGroup {
+                index,
+                name: section.name,
+                flags,
+                signature,
+                members,
+            });
+        }
+        Ok(groups)
+    }
+
+    /// Cross-checks the headers against the file: every section's and
+    /// segment's bytes must lie inside it, sections mustn't share bytes with
+    /// each other or the header tables, and tables mustn't be empty.
+    /// Segments may overlap anything in the file, as they're meant to, but
+    /// PT_LOAD segments mustn't overlap in memory.
+    pub fn check(&self) -> Result<Vec<Anomaly<'data>>, ParseError> {
+        let ehdr = &self.file.ehdr;
+        let counts = self.header_counts();
+        let file_len = self.data.len() as u64;
+        let mut anomalies = Vec::new();
+        // The extents with file bytes, as (offset, size), for the overlap pass
+        let mut claimed = vec![(0, ehdr.e_ehsize as u64, Extent::ElfHeader)];
+        for (offset, size, extent) in header_tables(ehdr, &counts) {
+            match out_of_bounds(extent, offset, size, file_len) {
+                Some(anomaly) => anomalies.push(anomaly),
+                None => claimed.push((offset, size, extent)),
+            }
+        }
+
+        for (i, section) in self.sections()?.unwrap_or_default().into_iter().enumerate() {
+            let shdr = &section.shdr;
+            let extent = Extent::Section(i, section.name);
+            if matches!(shdr.sh_type, abi::SHT_NULL | abi::SHT_NOBITS) {
+                continue;
+            }
+            if shdr.sh_size == 0 {
+                let table = matches!(
+                    shdr.sh_type,
+                    abi::SHT_SYMTAB
+                        | abi::SHT_DYNSYM
+                        | abi::SHT_STRTAB
+                        | abi::SHT_DYNAMIC
+                        | abi::SHT_HASH
+                        | abi::SHT_GNU_HASH
+                        | abi::SHT_REL
+                        | abi::SHT_RELA
+                        | abi::SHT_GROUP
+                        | abi::SHT_SYMTAB_SHNDX
+                );
+                if table {
+                    anomalies.push(Anomaly::Empty(extent));
+                }
+            } else if let Some(anomaly) =
+                out_of_bounds(extent, shdr.sh_offset, shdr.sh_size, file_len)
+            {
+                anomalies.push(anomaly);
+            } else {
+                claimed.push((shdr.sh_offset, shdr.sh_size, extent));
+            }
+        }
+        let phdrs = self.segments().unwrap_or_default();
+        for (i, phdr) in phdrs.iter().enumerate() {
+            let extent = Extent::Segment(i);
+            if phdr.p_type == abi::PT_LOAD && phdr.p_filesz > phdr.p_memsz {
+                anomalies.push(Anomaly::FileSizeExceedsMemSize(i));
+            }
+            if phdr.p_filesz == 0 {
+                if matches!(phdr.p_type, abi::PT_DYNAMIC | abi::PT_INTERP | abi::PT_NOTE) {
+                    anomalies.push(Anomaly::Empty(extent));
+                }
+            } else {
+                anomalies.extend(out_of_bounds(
+                    extent,
+                    phdr.p_offset,
+                    phdr.p_filesz,
+                    file_len,
+                ));
+            }
+        }
+
+        anomalies.extend(self.executable_stack().map(Anomaly::ExecutableStack));
+
+        let loads: Vec<(usize, &ProgramHeader)> = phdrs
+            .iter()
+            .enumerate()
+            .filter(|(_, phdr)| phdr.p_type == abi::PT_LOAD && phdr.p_memsz != 0)
+            .collect();
+        for (n, &(i, first)) in loads.iter().enumerate() {
+            for &(j, second) in &loads[n + 1..] {
+                let first_end = first.p_vaddr.saturating_add(first.p_memsz);
+                let second_end = second.p_vaddr.saturating_add(second.p_memsz);
+                if first.p_vaddr < second_end && second.p_vaddr < first_end {
+                    anomalies.push(Anomaly::LoadOverlap(i, j));
+                }
+            }
+        }
+
+        // In offset order, anything starting before the furthest end so far
+        // overlaps the extent that reached it
+        claimed.sort_by_key(|&(offset, _, _)| offset);
+        let mut furthest: Option<(u64, Extent)> = None;
+        for (offset, size, extent) in claimed {
+            // Everything else fits in the file, but e_ehsize went unchecked
+            let end = offset
+                .checked_add(size)
+                .ok_or(ParseError::IntegerOverflow)?;
+            match furthest {
+                Some((reach, other)) if offset < reach => {
+                    anomalies.push(Anomaly::Overlap(other, extent));
+                    if end > reach {
+                        furthest = Some((end, extent));
+                    }
+                }
+                _ => furthest = Some((end, extent)),
+            }
+        }
+        Ok(anomalies)
+    }
+
+    /// Whether an executable or shared object gets an executable stack,
+    /// which is almost always a mistake: None if it doesn't, or isn't one.
+    pub fn executable_stack(&self) -> Option<ExecutableStack> {
+        let ehdr = &self.file.ehdr;
+        if !matches!(ehdr.e_type, abi::ET_EXEC | abi::ET_DYN) {
+            return None;
+        }
+        let phdrs = self.segments().unwrap_or_default();
+        match phdrs
+            .iter()
+            .position(|phdr| phdr.p_type == abi::PT_GNU_STACK)
+        {
+            Some(index) if phdrs[index].p_flags & abi::PF_X != 0 => {
+                Some(ExecutableStack::Requested(index))
+            }
+            Some(_) => None,
+            None => {
+                stack_executable_by_default(ehdr.e_machine).then_some(ExecutableStack::ByDefault)
+            }
+        }
+    }
+
+    /// The program headers, or None when the file has none.
+    pub fn segments(&self) -> Option<Vec<ProgramHeader>> {
+        self.file
+            .segments()
+            .map(|phdrs| phdrs.iter().collect::<Vec<_>>())
+            .filter(|phdrs| !phdrs.is_empty())
+    }
+
+    /// The segment's bytes as stored in the file, p_filesz of them.
+    pub fn segment_data(&self, phdr: &ProgramHeader) -> Result<&'data [u8], ParseError> {
+        self.file.segment_data(phdr)
+    }
+
+    /// For each program header, the named sections it holds, by readelf's
+    /// rules; None without both tables.
+    pub fn section_to_segment_mapping(
+        &self,
+    ) -> Result<Option<Vec<Vec<Section<'data>>>>, ParseError> {
+        let (Some(sections), Some(phdrs)) = (self.sections()?, self.segments()) else {
+            return Ok(None);
+        };
+        let mapping = phdrs
+            .iter()
+            .map(|phdr| {
+                sections
+                    .iter()
+                    .skip(1)
+                    .filter(|section| section_in_segment(&section.shdr, phdr))
+                    .filter(|section| !section.name.is_empty())
+                    .copied()
+                    .collect()
+            })
+            .collect();
+        Ok(Some(mapping))
+    }
+
+    pub fn dynamic(&self) -> Result<Option<Dynamic>, ParseError> {
+        let Some(table) = self.file.dynamic()? else {
+            return Ok(None);
+        };
+        // Like the loader, stop at the first DT_NULL; anything after it is padding
+        let mut entries = Vec::new();
+        for entry in table.iter() {
+            let null = entry.d_tag == abi::DT_NULL;
+            entries.push(entry);
+            if null {
+                break;
+            }
+        }
+        // Prefer the section header, but the loader only needs PT_DYNAMIC
+        let offset = self
+            .sections_of_type(abi::SHT_DYNAMIC)?
+            .first()
+            .map(|section| section.shdr.sh_offset)
+            .or_else(|| {
+                self.segments()?
+                    .iter()
+                    .find(|phdr| phdr.p_type == abi::PT_DYNAMIC)
+                    .map(|phdr| phdr.p_offset)
+            })
+            .unwrap_or_default();
+        Ok(Some(Dynamic { offset, entries }))
+    }
+
+    /// Every SHT_REL and SHT_RELA section, in section header order.
+    pub fn relocations(&self) -> Result<Vec<RelocationSection<'data>>, ParseError> {
+        let sections = self.sections()?.unwrap_or_default();
+        let mut result = Vec::new();
+        for section in &sections {
+            let shdr = &section.shdr;
+            let entries: Vec<(u64, u32, u32, Option<i64>)> = match shdr.sh_type {
+                abi::SHT_REL => self
+                    .file
+                    .section_data_as_rels(shdr)?
+                    .map(|rel| (rel.r_offset, rel.r_sym, rel.r_type, None))
+                    .collect(),
+                abi::SHT_RELA => self
+                    .file
+                    .section_data_as_relas(shdr)?
+                    .map(|rel| (rel.r_offset, rel.r_sym, rel.r_type, Some(rel.r_addend)))
+                    .collect(),
+                _ => continue,
+            };
+            let symbols = self.relocation_symbols(&sections, shdr)?;
+            let relocations = entries
+                .into_iter()
+                .map(|(r_offset, r_sym, r_type, r_addend)| Relocation {
+                    r_offset,
+                    r_sym,
+                    r_type,
+                    r_addend,
+                    symbol: symbols.get(r_sym as usize).copied().filter(|_| r_sym != 0),
+                })
+                .collect();
+            result.push(RelocationSection {
+                name: section.name,
+                shdr: *shdr,
+                relocations,
+            });
+        }
+        Ok(result)
+    }
+
+    /// The symbol table a relocation section's sh_link points at.
+    fn relocation_symbols(
+        &self,
+        sections: &[Section<'data>],
+        rel_shdr: &SectionHeader,
+    ) -> Result<Vec<RelocationSymbol<'data>>, ParseError> {
+        let tables = match sections
+            .get(rel_shdr.sh_link as usize)
+            .map(|section| section.shdr.sh_type)
+        {
+            Some(abi::SHT_SYMTAB) => self.file.symbol_table()?,
+            Some(abi::SHT_DYNSYM) => self.file.dynamic_symbol_table()?,
+            _ => None,
+        };
+        let Some((symtab, strtab)) = tables else {
+            return Ok(Vec::new());
+        };
+        let xindices = self.extended_indices(rel_shdr.sh_link as usize)?;
+        symtab
+            .iter()
+            .enumerate()
+            .map(|(index, sym)| {
+                let xindex = xindices.as_ref().and_then(|xindices| xindices.get(index));
+                let section = section_index(&sym, xindex).and_then(|index| sections.get(index));
+                let name = match section {
+                    Some(section) if sym.st_symtype() == abi::STT_SECTION => section.name,
+                    _ => strtab.get(sym.st_name as usize).unwrap_or(CORRUPT_NAME),
+                };
+                Ok(RelocationSymbol {
+                    value: sym.st_value,
+                    name,
+                })
+            })
+            .collect()
+    }
+
+    /// The SHT_DYNSYM table, if any, with each symbol's version from .gnu.version.
+    pub fn dynamic_symbols(&self) -> Result<Option<Symbols<'data>>, ParseError> {
+        let tables = self.file.dynamic_symbol_table()?;
+        let Some(mut symbols) = self.named_symbols(abi::SHT_DYNSYM, tables)? else {
+            return Ok(None);
+        };
+        for version_section in self.version_info()? {
+            if let VersionSection::Symbols { versions, .. } = version_section {
+                for (entry, version) in symbols.entries.iter_mut().zip(versions) {
+                    entry.version = version.name.map(|_| version);
+                }
+            }
+        }
+        Ok(Some(symbols))
+    }
+
+    /// The SHT_SYMTAB table, if the file hasn't been stripped of it.
+    pub fn symbols(&self) -> Result<Option<Symbols<'data>>, ParseError> {
+        let tables = self.file.symbol_table()?;
+        self.named_symbols(abi::SHT_SYMTAB, tables)
+    }
+
+    /// The dynamic symbol table as the loader finds it, from DT_SYMTAB and
+    /// DT_STRTAB alone, for -D on files whose section headers are gone or
+    /// can't be trusted. The table's size isn't recorded anywhere, so it's
+    /// taken from DT_HASH or DT_GNU_HASH, or failing both assumed to run up
+    /// to the string table, where linkers place it. Versions come from
+    /// DT_VERSYM, DT_VERDEF and DT_VERNEED in place of their sections.
+    pub fn dynamic_symbols_from_dynamic(&self) -> Result<Option<Symbols<'data>>, ParseError> {
+        let Some(dynamic) = self.dynamic()? else {
+            return Ok(None);
+        };
+        let (Some(symtab), Some(strtab)) = (
+            dynamic_value(&dynamic, abi::DT_SYMTAB),
+            dynamic_value(&dynamic, abi::DT_STRTAB),
+        ) else {
+            return Ok(None);
+        };
+        let ehdr = &self.file.ehdr;
+        let entsize = Symbol::size_for(ehdr.class);
+        if let Some(syment) = dynamic_value(&dynamic, abi::DT_SYMENT) {
+            if syment != entsize as u64 {
+                return Err(ParseError::BadEntsize((syment, entsize as u64)));
+            }
+        }
+        let count = if let Some(hash) = dynamic_value(&dynamic, abi::DT_HASH) {
+            hash::sysv_symbol_count(ehdr.endianness, self.data_at_vaddr(hash)?)? as u64
+        } else if let Some(hash) = dynamic_value(&dynamic, abi::DT_GNU_HASH) {
+            hash::gnu_symbol_count(ehdr.endianness, ehdr.class, self.data_at_vaddr(hash)?)? as u64
+        } else {
+            strtab.saturating_sub(symtab) / entsize as u64
+        };
+        let size = count
+            .checked_mul(entsize as u64)
+            .ok_or(ParseError::IntegerOverflow)?;
+        let symbols = self
+            .data_at_vaddr(symtab)?
+            .get(..size as usize)
+            .ok_or(ParseError::BadOffset(symtab))?;
+        let symtab = elf::symbol::SymbolTable::new(ehdr.endianness, ehdr.class, symbols);
+        let strtab = self.dynamic_strings(&dynamic, strtab)?;
+        // Nothing here goes near the section headers, which may be missing.
+        // Without them there's no SHT_SYMTAB_SHNDX either.
+        let mut entries = named_entries(&symtab, &strtab, None);
+        if let Some(versym) = dynamic_value(&dynamic, abi::DT_VERSYM) {
+            let size = count.checked_mul(2).ok_or(ParseError::IntegerOverflow)?;
+            let versyms = self
+                .data_at_vaddr(versym)?
+                .get(..size as usize)
+                .ok_or(ParseError::BadOffset(versym))?;
+            let mut versions: Vec<_> = symver::parse_versym(ehdr.endianness, versyms)?
+                .into_iter()
+                .map(version_index)
+                .collect();
+            let mut names = HashMap::new();
+            if let Some(verdef) = dynamic_value(&dynamic, abi::DT_VERDEF) {
+                let count = dynamic_value(&dynamic, abi::DT_VERDEFNUM).unwrap_or_default();
+                let data = self.data_at_vaddr(verdef)?;
+                let defs = symver::parse_verdef(ehdr.endianness, data, count as u32, &strtab)?;
+                add_version_names(&mut names, &defs, &[]);
+            }
+            if let Some(verneed) = dynamic_value(&dynamic, abi::DT_VERNEED) {
+                let count = dynamic_value(&dynamic, abi::DT_VERNEEDNUM).unwrap_or_default();
+                let data = self.data_at_vaddr(verneed)?;
+                let needs = symver::parse_verneed(ehdr.endianness, data, count as u32, &strtab)?;
+                add_version_names(&mut names, &[], &needs);
+            }
+            name_versions(&mut versions, &names);
+            for (entry, version) in entries.iter_mut().zip(versions) {
+                entry.version = version.name.map(|_| version);
+            }
+        }
+        // readelf -D still calls it .dynsym
+        Ok(Some(Symbols {
+            name: ".dynsym",
+            entries,
+        }))
+    }
+
+    /// The string table the string-valued dynamic entries (DT_NEEDED,
+    /// DT_SONAME, ...) index: DT_STRTAB's, or else the one the SHT_DYNAMIC
+    /// section links to.
+    pub fn dynamic_string_table(
+        &self,
+        dynamic: &Dynamic,
+    ) -> Result<Option<StringTable<'data>>, ParseError> {
+        if let Some(strtab) = dynamic_value(dynamic, abi::DT_STRTAB) {
+            return self.dynamic_strings(dynamic, strtab).map(Some);
+        }
+        let Some(section) = self.sections_of_type(abi::SHT_DYNAMIC)?.into_iter().next() else {
+            return Ok(None);
+        };
+        let (shdrs, _) = self.file.section_headers_with_strtab()?;
+        let Some(link) = shdrs.and_then(|shdrs| shdrs.get(section.shdr.sh_link as usize).ok())
+        else {
+            return Ok(None);
+        };
+        self.file.section_data_as_strtab(&link).map(Some)
+    }
+
+    /// The libraries the dynamic section names: the DT_NEEDED entries in
+    /// load order, and the file's own DT_SONAME and search paths.
+    pub fn libraries(&self) -> Result<Option<Libraries<'data>>, ParseError> {
+        let Some(dynamic) = self.dynamic()? else {
+            return Ok(None);
+        };
+        let mut libraries = Libraries::default();
+        let Some(strings) = self.dynamic_string_table(&dynamic)? else {
+            return Ok(Some(libraries));
+        };
+        for entry in &dynamic.entries {
+            let name = || strings.get(entry.clone().d_val() as usize);
+            match entry.d_tag {
+                abi::DT_NEEDED => libraries.needed.push(name()?),
+                abi::DT_SONAME => libraries.soname = Some(name()?),
+                abi::DT_RPATH => libraries.rpath = Some(name()?),
+                abi::DT_RUNPATH => libraries.runpath = Some(name()?),
+                _ => (),
+            }
+        }
+        Ok(Some(libraries))
+    }
+
+    /// DT_INIT, DT_FINI and the DT_*_ARRAY function pointer arrays.
+    ///
+    /// The arrays are read from the file. In a position-independent file
+    /// the pointers are only written by dynamic relocations, so a RELA
+    /// entry for a slot supplies its address in place of the stored word.
+    pub fn init_fini(&self) -> Result<Option<InitFini<'data>>, ParseError> {
+        let Some(dynamic) = self.dynamic()? else {
+            return Ok(None);
+        };
+        let relocated: HashMap<u64, u64> = self
+            .relocations_from_dynamic()?
+            .iter()
+            .flat_map(|section| &section.relocations)
+            .filter_map(|rel| {
+                let addend = rel.r_addend?;
+                let base = rel.symbol.map_or(0, |symbol| symbol.value);
+                Some((rel.r_offset, base.wrapping_add_signed(addend)))
+            })
+            .collect();
+        // Function symbols by address, .symtab's names winning over .dynsym's
+        let mut names = HashMap::new();
+        for symbols in [self.dynamic_symbols()?, self.symbols()?]
+            .into_iter()
+            .flatten()
+        {
+            for entry in symbols.entries {
+                if entry.symbol.st_symtype() == abi::STT_FUNC && entry.symbol.st_value != 0 {
+                    names.insert(entry.symbol.st_value, entry.name);
+                }
+            }
+        }
+        let function = |address| Function {
+            address,
+            name: names.get(&address).copied(),
+        };
+
+        let ehdr = &self.file.ehdr;
+        let word = match ehdr.class {
+            elf::file::Class::ELF32 => 4,
+            elf::file::Class::ELF64 => 8,
+        };
+        let array = |addr_tag, size_tag| -> Result<Vec<Function<'data>>, ParseError> {
+            let (Some(addr), Some(size)) = (
+                dynamic_value(&dynamic, addr_tag),
+                dynamic_value(&dynamic, size_tag),
+            ) else {
+                return Ok(Vec::new());
+            };
+            let data = self
+                .data_at_vaddr(addr)?
+                .get(..size as usize)
+                .ok_or(ParseError::BadOffset(addr))?;
+            (0..size / word)
+                .map(|i| {
+                    let slot = addr + i * word;
+                    let mut offset = (i * word) as usize;
+                    let stored = match word {
+                        4 => ehdr.endianness.parse_u32_at(&mut offset, data)? as u64,
+                        _ => ehdr.endianness.parse_u64_at(&mut offset, data)?,
+                    };
+                    Ok(function(*relocated.get(&slot).unwrap_or(&stored)))
+                })
+                .collect()
+        };
+        Ok(Some(InitFini {
+            init: dynamic_value(&dynamic, abi::DT_INIT).map(function),
+            fini: dynamic_value(&dynamic, abi::DT_FINI).map(function),
+            preinit_array: array(abi::DT_PREINIT_ARRAY, abi::DT_PREINIT_ARRAYSZ)?,
+            init_array: array(abi::DT_INIT_ARRAY, abi::DT_INIT_ARRAYSZ)?,
+            fini_array: array(abi::DT_FINI_ARRAY, abi::DT_FINI_ARRAYSZ)?,
+        }))
+    }
+
+    /// The relocation tables DT_RELA, DT_REL and DT_JMPREL point at, for -D.
+    /// Each gets a made-up section header with its file offset and size,
+    /// named "RELA", "REL" or "PLT" like readelf, and its symbols come from
+    /// [`Self::dynamic_symbols_from_dynamic`].
+    pub fn relocations_from_dynamic(&self) -> Result<Vec<RelocationSection<'data>>, ParseError> {
+        let Some(dynamic) = self.dynamic()? else {
+            return Ok(Vec::new());
+        };
+        let symbols: Vec<RelocationSymbol<'data>> = self
+            .dynamic_symbols_from_dynamic()?
+            .map(|symbols| symbols.entries)
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| RelocationSymbol {
+                value: entry.symbol.st_value,
+                name: entry.name,
+            })
+            .collect();
+        let plt_type = match dynamic_value(&dynamic, abi::DT_PLTREL) {
+            Some(pltrel) if pltrel == abi::DT_REL as u64 => abi::SHT_REL,
+            _ => abi::SHT_RELA,
+        };
+        let tables = [
+            ("RELA", abi::DT_RELA, abi::DT_RELASZ, abi::SHT_RELA),
+            ("REL", abi::DT_REL, abi::DT_RELSZ, abi::SHT_REL),
+            ("PLT", abi::DT_JMPREL, abi::DT_PLTRELSZ, plt_type),
+        ];
+        let ehdr = &self.file.ehdr;
+        let mut result = Vec::new();
+        for (name, addr_tag, size_tag, sh_type) in tables {
+            let (Some(addr), Some(size)) = (
+                dynamic_value(&dynamic, addr_tag),
+                dynamic_value(&dynamic, size_tag),
+            ) else {
+                continue;
+            };
+            let data = self
+                .data_at_vaddr(addr)?
+                .get(..size as usize)
+                .ok_or(ParseError::BadOffset(addr))?;
+            let entries: Vec<(u64, u32, u32, Option<i64>)> = match sh_type {
+                abi::SHT_REL => {
+                    elf::relocation::RelIterator::new(ehdr.endianness, ehdr.class, data)
+                        .map(|rel| (rel.r_offset, rel.r_sym, rel.r_type, None))
+                        .collect()
+                }
+                _ => elf::relocation::RelaIterator::new(ehdr.endianness, ehdr.class, data)
+                    .map(|rel| (rel.r_offset, rel.r_sym, rel.r_type, Some(rel.r_addend)))
+                    .collect(),
+            };
+            let entsize = match sh_type {
+                abi::SHT_REL => elf::relocation::Rel::size_for(ehdr.class),
+                _ => elf::relocation::Rela::size_for(ehdr.class),
+            };
+            let relocations = entries
+                .into_iter()
+                .map(|(r_offset, r_sym, r_type, r_addend)| Relocation {
+                    r_offset,
+                    r_sym,
+                    r_type,
+                    r_addend,
+                    symbol: symbols.get(r_sym as usize).copied().filter(|_| r_sym != 0),
+                })
+                .collect();
+            let shdr = SectionHeader {
+                sh_name: 0,
+                sh_type,
+                sh_flags: abi::SHF_ALLOC as u64,
+                sh_addr: addr,
+                sh_offset: self.vaddr_to_offset(addr).unwrap_or_default(),
+                sh_size: size,
+                sh_link: 0,
+                sh_info: 0,
+                sh_addralign: 0,
+                sh_entsize: entsize as u64,
+            };
+            result.push(RelocationSection {
+                name,
+                shdr,
+                relocations,
+            });
+        }
+        Ok(result)
+    }
+
+    fn named_symbols(
+        &self,
+        sh_type: u32,
+        tables: Option<(
+            elf::symbol::SymbolTable<'data, AnyEndian>,
+            StringTable<'data>,
+        )>,
+    ) -> Result<Option<Symbols<'data>>, ParseError> {
+        let Some((symtab, strtab)) = tables else {
+            return Ok(None);
+        };
+        let sections = self.sections()?.unwrap_or_default();
+        let table = sections
+            .iter()
+            .position(|section| section.shdr.sh_type == sh_type);
+        let name = table.map_or("", |index| sections[index].name);
+        let xindices = match table {
+            Some(index) => self.extended_indices(index)?,
+            None => None,
+        };
+        Ok(Some(Symbols {
+            name,
+            entries: named_entries(&symtab, &strtab, xindices.as_ref()),
+        }))
+    }
+
+    /// Every .symtab and .dynsym entry named exactly `name`, in that order.
+    pub fn find_symbols(&self, name: &str) -> Result<Vec<SymbolMatch<'data>>, ParseError> {
+        let tables = [self.symbols()?, self.dynamic_symbols()?];
+        Ok(tables
+            .into_iter()
+            .flatten()
+            .flat_map(|symbols| {
+                let table = symbols.name;
+                symbols
+                    .entries
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.name == name)
+                    .map(move |(index, entry)| SymbolMatch {
+                        table,
+                        index,
+                        entry,
+                    })
+            })
+            .collect())
+    }
+
+    /// The symbol, or failing that the allocated section, that an address
+    /// falls inside. Ties between equally tight symbols go to .symtab.
+    pub fn lookup_address(&self, address: u64) -> Result<AddressLookup<'data>, ParseError> {
+        let sections = self.sections()?.unwrap_or_default();
+        let containing = sections.iter().copied().find(|section| {
+            section.shdr.sh_flags & abi::SHF_ALLOC as u64 != 0
+                && address >= section.shdr.sh_addr
+                && address - section.shdr.sh_addr < section.shdr.sh_size
+        });
+        let tables = [self.symbols()?, self.dynamic_symbols()?];
+        let tightest = tables
+            .into_iter()
+            .flatten()
+            .flat_map(|symbols| symbols.entries)
+            .filter(|entry| {
+                let symbol = &entry.symbol;
+                !symbol.is_undefined()
+                    && address >= symbol.st_value
+                    && address - symbol.st_value < symbol.st_size
+            })
+            .min_by_key(|entry| entry.symbol.st_size);
+        Ok(match tightest {
+            Some(entry) => {
+                // Prefer the section the symbol says it's defined in
+                let section = entry
+                    .section_index()
+                    .and_then(|index| sections.get(index).copied())
+                    .or(containing);
+                AddressLookup::Symbol { entry, section }
+            }
+            None => match containing {
+                Some(section) => AddressLookup::Section(section),
+                None => AddressLookup::Nowhere,
+            },
+        })
+    }
+
+    /// Every SHF_ALLOC section with the .symtab and .dynsym symbols defined
+    /// in it, most symbols first. A symbol in both tables, by name, value
+    /// and section, counts once.
+    pub fn section_symbol_counts(&self) -> Result<Vec<SectionSymbolCount<'data>>, ParseError> {
+        let sections = self.sections()?.unwrap_or_default();
+        let mut counts: Vec<SectionSymbolCount> = sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| section.shdr.sh_flags & abi::SHF_ALLOC as u64 != 0)
+            .map(|(index, section)| SectionSymbolCount {
+                index,
+                section: *section,
+                symbols: 0,
+                total_size: 0,
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        let tables = [self.symbols()?, self.dynamic_symbols()?];
+        for entry in tables
+            .into_iter()
+            .flatten()
+            .flat_map(|symbols| symbols.entries)
+        {
+            let symbol = &entry.symbol;
+            let index = entry.section_index();
+            if !seen.insert((entry.name, symbol.st_value, index)) {
+                continue;
+            }
+            if let Some(count) = counts.iter_mut().find(|count| Some(count.index) == index) {
+                count.symbols += 1;
+                count.total_size += symbol.st_size;
+            }
+        }
+        counts.sort_by_key(|count| std::cmp::Reverse(count.symbols));
+        Ok(counts)
+    }
+
+    /// The bucket chain lengths of every .hash and then every .gnu.hash
+    /// section, the order readelf charts them in. Empty when the file has
+    /// neither, as for relocatable objects.
+    pub fn hash_histograms(&self) -> Result<Vec<HashHistogram<'data>>, ParseError> {
+        let ehdr = &self.file.ehdr;
+        let mut histograms = Vec::new();
+        for section in self.sections_of_type(abi::SHT_HASH)? {
+            let data = self.section_data(&section)?;
+            histograms.push(HashHistogram {
+                section,
+                chain_lengths: hash::sysv_chain_lengths(ehdr.endianness, data)?,
+            });
+        }
+        for section in self.sections_of_type(abi::SHT_GNU_HASH)? {
+            let data = self.section_data(&section)?;
+            histograms.push(HashHistogram {
+                section,
+                chain_lengths: hash::gnu_chain_lengths(ehdr.endianness, ehdr.class, data)?,
+            });
+        }
+        Ok(histograms)
+    }
+
+    /// The .eh_frame_hdr lookup table, if the file has one.
+    pub fn unwind_table(&self) -> Result<Option<UnwindTable<'data>>, ParseError> {
+        let Some((_, section)) = self.find_section(".eh_frame_hdr")? else {
+            return Ok(None);
+        };
+        let ehdr = &self.file.ehdr;
+        let data = self.section_data(&section)?;
+        let hdr =
+            unwind::parse_eh_frame_hdr(ehdr.endianness, ehdr.class, data, section.shdr.sh_addr)?;
+        Ok(Some(UnwindTable { section, hdr }))
+    }
+
+    /// The .debug_line programs, run to their row tables; empty without
+    /// .debug_line. Sections are read as stored: neither decompressed nor,
+    /// in relocatable objects, relocated.
+    pub fn line_programs(&self) -> Result<Vec<dwarf::LineProgram<'data>>, ParseError> {
+        let Some((_, section)) = self.find_section(".debug_line")? else {
+            return Ok(Vec::new());
+        };
+        let ehdr = &self.file.ehdr;
+        let address_size = match ehdr.class {
+            elf::file::Class::ELF32 => 4,
+            elf::file::Class::ELF64 => 8,
+        };
+        dwarf::parse_line_programs(
+            ehdr.endianness,
+            self.section_data(&section)?,
+            self.dwarf_strings()?,
+            address_size,
+        )
+    }
+
+    /// A summary of each .debug_info unit, or the error that kept it from
+    /// being read; empty without .debug_info. Read as stored, like
+    /// [`ElfReport::line_programs`].
+    pub fn compile_units(&self) -> Result<Vec<dwarf::UnitSummary<'data>>, ParseError> {
+        let Some((_, section)) = self.find_section(".debug_info")? else {
+            return Ok(Vec::new());
+        };
+        let abbrev = match self.find_section(".debug_abbrev")? {
+            Some((_, abbrev)) => self.section_data(&abbrev)?,
+            None => &[],
+        };
+        Ok(dwarf::parse_compile_units(
+            self.file.ehdr.endianness,
+            self.section_data(&section)?,
+            abbrev,
+            self.dwarf_strings()?,
+        ))
+    }
+
+    fn dwarf_strings(&self) -> Result<dwarf::StringSections<'data>, ParseError> {
+        let data = |name| match self.find_section(name)? {
+            Some((_, section)) => self.section_data(&section),
+            None => Ok(&[][..]),
+        };
+        Ok(dwarf::StringSections {
+            debug_str: data(".debug_str")?,
+            debug_line_str: data(".debug_line_str")?,
+            debug_str_offsets: data(".debug_str_offsets")?,
+        })
+    }
+
+    /// Notes from the SHT_NOTE sections, or from the PT_NOTE segments when
+    /// the file has no note sections.
+    pub fn notes(&self) -> Result<Vec<Notes<'data>>, ParseError> {
+        let endian = self.file.ehdr.endianness;
+        let sections = self.sections_of_type(abi::SHT_NOTE)?;
+        if !sections.is_empty() {
+            // Name and desc are padded to sh_addralign, so both the usual
+            // 4-byte and the 8-byte aligned (.note.gnu.property) forms parse
+            return sections
+                .iter()
+                .map(|section| {
+                    let (data, _) = self.file.section_data(&section.shdr)?;
+                    let (notes, corrupt) = parse_notes(endian, section.shdr.sh_addralign, data);
+                    Ok(Notes {
+                        source: NoteSource::Section(section.name),
+                        notes,
+                        corrupt: corrupt.map(|offset| section.shdr.sh_offset + offset as u64),
+                    })
+                })
+                .collect();
+        }
+        self.segments()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|phdr| phdr.p_type == abi::PT_NOTE)
+            .map(|phdr| {
+                let data = self.file.segment_data(&phdr)?;
+                let (notes, corrupt) = parse_notes(endian, phdr.p_align, data);
+                Ok(Notes {
+                    notes,
+                    corrupt: corrupt.map(|offset| phdr.p_offset + offset as u64),
+                    source: NoteSource::Segment(phdr),
+                })
+            })
+            .collect()
+    }
+
+    /// The .gnu.version, .gnu.version_r and .gnu.version_d sections, in
+    /// section header order.
+    pub fn version_info(&self) -> Result<Vec<VersionSection<'data>>, ParseError> {
+        let sections = self.sections()?.unwrap_or_default();
+        let endian = self.file.ehdr.endianness;
+        // Each section names its string table (or symbol table, for VERSYM) via sh_link
+        let linked =
+            |shdr: &SectionHeader| -> Result<(&'data str, StringTable<'data>), ParseError> {
+                let link = sections
+                    .get(shdr.sh_link as usize)
+                    .ok_or(ParseError::BadOffset(shdr.sh_link as u64))?;
+                let strtab = match link.shdr.sh_type {
+                    abi::SHT_STRTAB => self.file.section_data_as_strtab(&link.shdr)?,
+                    _ => StringTable::default(),
+                };
+                Ok((link.name, strtab))
+            };
+
+        let mut result = Vec::new();
+        for &section in &sections {
+            let shdr = &section.shdr;
+            let (data, _) = match shdr.sh_type {
+                abi::SHT_GNU_VERSYM | abi::SHT_GNU_VERNEED | abi::SHT_GNU_VERDEF => {
+                    self.file.section_data(shdr)?
+                }
+                _ => continue,
+            };
+            let (link, strtab) = linked(shdr)?;
+            result.push(match shdr.sh_type {
+                abi::SHT_GNU_VERNEED => VersionSection::Needs {
+                    section,
+                    link,
+                    needs: symver::parse_verneed(endian, data, shdr.sh_info, &strtab)?,
+                },
+                abi::SHT_GNU_VERDEF => VersionSection::Definitions {
+                    section,
+                    link,
+                    defs: symver::parse_verdef(endian, data, shdr.sh_info, &strtab)?,
+                },
+                // Resolved below, once every definition and need is known
+                _ => VersionSection::Symbols {
+                    section,
+                    link,
+                    versions: symver::parse_versym(endian, data)?
+                        .into_iter()
+                        .map(version_index)
+                        .collect(),
+                },
+            });
+        }
+
+        // Version indexes referenced by .gnu.version, from both definitions and needs
+        let mut names = HashMap::new();
+        for version_section in &result {
+            match version_section {
+                VersionSection::Definitions { defs, .. } => {
+                    add_version_names(&mut names, defs, &[])
+                }
+                VersionSection::Needs { needs, .. } => add_version_names(&mut names, &[], needs),
+                VersionSection::Symbols { .. } => (),
+            }
+        }
+        for version_section in &mut result {
+            if let VersionSection::Symbols { versions, .. } = version_section {
+                name_versions(versions, &names);
+            }
+        }
+        Ok(result)
+    }
+
+    /// The vendor subsections of the processor build attribute sections;
+    /// empty on machines that have none.
+    pub fn arch_attributes(&self) -> Result<Vec<attributes::Subsection<'data>>, ParseError> {
+        let (sh_type, vendor, value_kind): (_, _, fn(u64) -> attributes::ValueKind) =
+            match self.file.ehdr.e_machine {
+                abi::EM_ARM => (abi::SHT_ARM_ATTRIBUTES, "aeabi", attributes::arm_value_kind),
+                abi::EM_RISCV => (
+                    abi::SHT_RISCV_ATTRIBUTES,
+                    "riscv",
+                    attributes::riscv_value_kind,
+                ),
+                _ => return Ok(Vec::new()),
+            };
+        let mut subsections = Vec::new();
+        for section in self.sections_of_type(sh_type)? {
+            let (data, _) = self.file.section_data(&section.shdr)?;
+            subsections.extend(attributes::parse_attributes(
+                self.file.ehdr.endianness,
+                data,
+                vendor,
+                value_kind,
+            )?);
+        }
+        Ok(subsections)
+    }
+
+    /// The file offset a virtual address is loaded from, through PT_LOAD.
+    fn vaddr_to_offset(&self, vaddr: u64) -> Result<u64, ParseError> {
+        let phdr = self.load_segment(vaddr)?;
+        phdr.p_offset
+            .checked_add(vaddr - phdr.p_vaddr)
+            .ok_or(ParseError::IntegerOverflow)
+    }
+
+    /// The PT_LOAD whose file data covers `vaddr`.
+    fn load_segment(&self, vaddr: u64) -> Result<ProgramHeader, ParseError> {
+        self.segments()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|phdr| {
+                phdr.p_type == abi::PT_LOAD
+                    && vaddr >= phdr.p_vaddr
+                    && vaddr - phdr.p_vaddr < phdr.p_filesz
+            })
+            .ok_or(ParseError::BadOffset(vaddr))
+    }
+
+    /// The SHT_SYMTAB_SHNDX section linked to the symbol table at section
+    /// index `symtab`, if the table needed one.
+    fn extended_indices(
+        &self,
+        symtab: usize,
+    ) -> Result<Option<ExtendedIndices<'data>>, ParseError> {
+        let Some(section) = self
+            .sections_of_type(abi::SHT_SYMTAB_SHNDX)?
+            .into_iter()
+            .find(|section| section.shdr.sh_link as usize == symtab)
+        else {
+            return Ok(None);
+        };
+        let (data, _) = self.file.section_data(&section.shdr)?;
+        Ok(Some(ExtendedIndices {
+            endian: self.file.ehdr.endianness,
+            data,
+        }))
+    }
+
+    /// The string table at DT_STRTAB's `strtab`, cut to DT_STRSZ if given.
+    fn dynamic_strings(
+        &self,
+        dynamic: &Dynamic,
+        strtab: u64,
+    ) -> Result<StringTable<'data>, ParseError> {
+        let strings = self.data_at_vaddr(strtab)?;
+        let strings = match dynamic_value(dynamic, abi::DT_STRSZ) {
+            Some(strsz) => strings
+                .get(..strsz as usize)
+                .ok_or(ParseError::BadOffset(strtab))?,
+            None => strings,
+        };
+        Ok(StringTable::new(strings))
+    }
+
+    /// The file bytes loaded from `vaddr` to the end of its segment's data.
+    fn data_at_vaddr(&self, vaddr: u64) -> Result<&'data [u8], ParseError> {
+        let phdr = self.load_segment(vaddr)?;
+        // Both fields come straight from the file, so their sums can wrap
+        let start = self.vaddr_to_offset(vaddr)?;
+        let end = phdr
+            .p_offset
+            .checked_add(phdr.p_filesz)
+            .ok_or(ParseError::IntegerOverflow)?;
+        self.data
+            .get(start as usize..end as usize)
+            .ok_or(ParseError::BadOffset(start))
+    }
+
+    fn sections_of_type(&self, sh_type: u32) -> Result<Vec<Section<'data>>, ParseError> {
+        Ok(self
+            .sections()?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|section| section.shdr.sh_type == sh_type)
+            .collect())
+    }
+}
+
+/// A VERSYM entry's version index and hidden bit, its name still unknown.
+fn version_index(versym: u16) -> VersionIndex<'static> {
+    VersionIndex {
+        index: versym & abi::VER_NDX_VERSION,
+        hidden: versym & abi::VER_NDX_HIDDEN != 0,
+        name: None,
+        needed: false,
+    }
+}
+
+/// The version names VERSYM indexes refer to, by index, with whether
+/// each is needed from another object rather than defined here.
+type VersionNames<'data> = HashMap<u16, (&'data str, bool)>;
+
+fn add_version_names<'data>(
+    names: &mut VersionNames<'data>,
+    defs: &[symver::VerDef<'data>],
+    needs: &[symver::VerNeed<'data>],
+) {
+    for def in defs {
+        if let Some(aux) = def.aux.first() {
+            names.insert(def.vd_ndx, (aux.name, false));
+        }
+    }
+    for aux in needs.iter().flat_map(|need| &need.aux) {
+        names.insert(aux.vna_other, (aux.name, true));
+    }
+}
+
+/// Names each of `versions` but VER_NDX_LOCAL and VER_NDX_GLOBAL.
+fn name_versions<'data>(versions: &mut [VersionIndex<'data>], names: &VersionNames<'data>) {
+    for version in versions {
+        if !matches!(version.index, abi::VER_NDX_LOCAL | abi::VER_NDX_GLOBAL) {
+            if let Some(&(name, needed)) = names.get(&version.index) {
+                version.name = Some(name);
+                version.needed = needed;
+            }
+        }
+    }
+}
+
+/// Each of `symtab`'s symbols with its name from `strtab`, and its
+/// SHT_SYMTAB_SHNDX entry if it's SHN_XINDEX.
+fn named_entries<'data>(
+    symtab: &elf::symbol::SymbolTable<'data, AnyEndian>,
+    strtab: &StringTable<'data>,
+    xindices: Option<&ExtendedIndices>,
+) -> Vec<NamedSymbol<'data>> {
+    symtab
+        .iter()
+        .enumerate()
+        .map(|(index, symbol)| NamedSymbol {
+            name: strtab.get(symbol.st_name as usize).unwrap_or(CORRUPT_NAME),
+            version: None,
+            xindex: xindices
+                .filter(|_| symbol.st_shndx == abi::SHN_XINDEX)
+                .and_then(|xindices| xindices.get(index)),
+            symbol,
+        })
+        .collect()
+}
+
+/// [`ElfReport::header_counts`] for the ELF header `ehdr` of `data`.
+fn header_counts(ehdr: &FileHeader<AnyEndian>, data: &[u8]) -> HeaderCounts {
+    let mut counts = HeaderCounts {
+        phnum: ehdr.e_phnum as u32,
+        shnum: ehdr.e_shnum as u64,
+        shstrndx: ehdr.e_shstrndx as u32,
+    };
+    let mut offset = ehdr.e_shoff as usize;
+    let shdr0 = match ehdr.e_shoff {
+        0 => None,
+        _ => SectionHeader::parse_at(ehdr.endianness, ehdr.class, &mut offset, data).ok(),
+    };
+    if let Some(shdr0) = shdr0 {
+        if ehdr.e_phnum == abi::PN_XNUM {
+            counts.phnum = shdr0.sh_info;
+        }
+        if ehdr.e_shnum == 0 {
+            counts.shnum = shdr0.sh_size;
+        }
+        if ehdr.e_shstrndx == abi::SHN_XINDEX {
+            counts.shstrndx = shdr0.sh_link;
+        }
+    }
+    counts
+}
+
+/// The program and section header tables the ELF header places in the
+/// file, as (offset, size).
+fn header_tables(
+    ehdr: &FileHeader<AnyEndian>,
+    counts: &HeaderCounts,
+) -> Vec<(u64, u64, Extent<'static>)> {
+    let mut tables = Vec::new();
+    if ehdr.e_phoff != 0 {
+        let size = counts.phnum as u64 * ehdr.e_phentsize as u64;
+        tables.push((ehdr.e_phoff, size, Extent::ProgramHeaders));
+    }
+    if ehdr.e_shoff != 0 {
+        // sh_size of section 0 can hold any count
+        let size = counts.shnum.saturating_mul(ehdr.e_shentsize as u64);
+        tables.push((ehdr.e_shoff, size, Extent::SectionHeaders));
+    }
+    tables
+}
+
+/// An OutOfBounds anomaly if [offset, offset + size) doesn't fit in a file
+/// of `file_len` bytes.
+fn out_of_bounds(extent: Extent, offset: u64, size: u64, file_len: u64) -> Option<Anomaly> {
+    let fits = offset.checked_add(size).is_some_and(|end| end <= file_len);
+    (!fits).then_some(Anomaly::OutOfBounds {
+        extent,
+        offset,
+        size,
+    })
+}
+
+/// What --check can still say about a file [`ElfReport::parse`] turns
+/// away because a header table runs past its end: which of them do, going
+/// on the ELF header `ehdr` from [`parse_header`] alone.
+pub fn check_header_tables(ehdr: &FileHeader<AnyEndian>, data: &[u8]) -> Vec<Anomaly<'static>> {
+    let counts = header_counts(ehdr, data);
+    header_tables(ehdr, &counts)
+        .into_iter()
+        .filter_map(|(offset, size, extent)| out_of_bounds(extent, offset, size, data.len() as u64))
+        .collect()
+}
+
+/// The ELF header by itself, without the tables it points at.
+pub fn parse_header(data: &[u8]) -> Result<FileHeader<AnyEndian>, ParseError> {
+    let ident = elf::file::parse_ident(
+        data.get(..abi::EI_NIDENT)
+            .ok_or(ParseError::SliceReadError((0, abi::EI_NIDENT)))?,
+    )?;
+    FileHeader::parse_tail(ident, &data[abi::EI_NIDENT..])
+}
+
+/// An SHT_SYMTAB_SHNDX section: a u32 per symbol of its table, holding
+/// the section index of those whose st_shndx is SHN_XINDEX.
+struct ExtendedIndices<'data> {
+    endian: AnyEndian,
+    data: &'data [u8],
+}
+
+impl ExtendedIndices<'_> {
+    fn get(&self, symbol: usize) -> Option<u32> {
+        let mut offset = symbol.checked_mul(4)?;
+        self.endian.parse_u32_at(&mut offset, self.data).ok()
+    }
+}
+
+/// The section `symbol` is defined in, given its SHT_SYMTAB_SHNDX entry.
+/// Indexes from 0xff00 (SHN_LORESERVE) on are reserved, like SHN_ABS.
+fn section_index(symbol: &Symbol, xindex: Option<u32>) -> Option<usize> {
+    match symbol.st_shndx {
+        abi::SHN_UNDEF => None,
+        abi::SHN_XINDEX => xindex.map(|index| index as usize),
+        shndx if shndx < 0xff00 => Some(shndx as usize),
+        _ => None,
+    }
+}
+
+/// Whether glibc's loader maps the stack executable for a file without
+/// PT_GNU_STACK (its DEFAULT_STACK_PERMS). Only the newer ports default
+/// to a non-executable one.
+fn stack_executable_by_default(e_machine: u16) -> bool {
+    !matches!(e_machine, abi::EM_AARCH64 | abi::EM_RISCV | abi::EM_IA_64)
+}
+
+const PT_GNU_SFRAME: u32 = 0x6474_e554;
+const PT_GNU_MBIND_LO: u32 = 0x6474_e555;
+const PT_GNU_MBIND_HI: u32 = PT_GNU_MBIND_LO + 0xfff;
+
+/// binutils' ELF_SECTION_IN_SEGMENT_STRICT, which readelf -l maps sections
+/// with: a section belongs to a segment when its file bytes (unless
+/// SHT_NOBITS) and its addresses (if SHF_ALLOC) lie inside the segment's,
+/// TLS sections only go in the segments that can hold them, and empty
+/// sections don't count at the edges of PT_DYNAMIC or PT_NOTE.
+fn section_in_segment(shdr: &SectionHeader, phdr: &ProgramHeader) -> bool {
+    let tls = shdr.sh_flags & abi::SHF_TLS as u64 != 0;
+    let alloc = shdr.sh_flags & abi::SHF_ALLOC as u64 != 0;
+    let nobits = shdr.sh_type == abi::SHT_NOBITS;
+    // .tbss takes no room in the segments after PT_TLS
+    let tbss = tls && nobits && phdr.p_type != abi::PT_TLS;
+    if tbss {
+        return false;
+    }
+    let size = shdr.sh_size;
+
+    let type_fits = if tls {
+        matches!(phdr.p_type, abi::PT_TLS | abi::PT_GNU_RELRO | abi::PT_LOAD)
+    } else {
+        !matches!(phdr.p_type, abi::PT_TLS | abi::PT_PHDR)
+    };
+    let alloc_only = matches!(
+        phdr.p_type,
+        abi::PT_LOAD
+            | abi::PT_DYNAMIC
+            | abi::PT_GNU_EH_FRAME
+            | abi::PT_GNU_STACK
+            | abi::PT_GNU_RELRO
+            | PT_GNU_SFRAME
+            | PT_GNU_MBIND_LO..=PT_GNU_MBIND_HI
+    );
+    // The differences wrap as binutils' unsigned arithmetic does, so an
+    // empty segment holds nothing
+    let in_file = nobits
+        || (shdr.sh_offset >= phdr.p_offset
+            && shdr.sh_offset - phdr.p_offset <= phdr.p_filesz.wrapping_sub(1)
+            && (shdr.sh_offset - phdr.p_offset).saturating_add(size) <= phdr.p_filesz);
+    let in_memory = !alloc
+        || (shdr.sh_addr >= phdr.p_vaddr
+            && shdr.sh_addr - phdr.p_vaddr <= phdr.p_memsz.wrapping_sub(1)
+            && (shdr.sh_addr - phdr.p_vaddr).saturating_add(size) <= phdr.p_memsz);
+    let inside_edges = !matches!(phdr.p_type, abi::PT_DYNAMIC | abi::PT_NOTE)
+        || size != 0
+        || phdr.p_memsz == 0
+        || ((nobits
+            || (shdr.sh_offset > phdr.p_offset && shdr.sh_offset - phdr.p_offset < phdr.p_filesz))
+            && (!alloc
+                || (shdr.sh_addr > phdr.p_vaddr && shdr.sh_addr - phdr.p_vaddr < phdr.p_memsz)));
+
+    type_fits && (alloc || !alloc_only) && in_file && in_memory && inside_edges
+}
+
+/// The notes in `data`, up to the first that doesn't parse, and that
+/// one's offset if there is one.
+///
+/// The elf crate's iterator drops the name's NUL before padding, which
+/// misreads every note after one whose namesz is 1 mod 4, such as the
+/// "CORE" notes of a core dump, so the padding is worked out here.
+fn parse_notes(endian: AnyEndian, align: u64, data: &[u8]) -> (Vec<Note<'_>>, Option<usize>) {
+    // Producers emit 4-byte aligned notes with a 0 or 1 alignment too
+    let align = align.max(4) as usize;
+    let padded = |offset: usize| offset.checked_next_multiple_of(align);
+    let mut notes = Vec::new();
+    let mut offset = 0;
+    let next = |offset: &mut usize| -> Result<Note<'_>, ParseError> {
+        // Note headers are 32-bit words in both classes
+        let namesz = endian.parse_u32_at(offset, data)? as usize;
+        let descsz = endian.parse_u32_at(offset, data)? as usize;
+        let n_type = endian.parse_u32_at(offset, data)? as u64;
+        let name_end = offset
+            .checked_add(namesz)
+            .ok_or(ParseError::IntegerOverflow)?;
+        let name = data
+            .get(*offset..name_end)
+            .ok_or(ParseError::BadOffset(*offset as u64))?;
+        let name = std::str::from_utf8(name.strip_suffix(&[0]).unwrap_or(name))
+            .map_err(ParseError::Utf8Error)?;
+        let desc_start = padded(name_end).ok_or(ParseError::IntegerOverflow)?;
+        let desc_end = desc_start
+            .checked_add(descsz)
+            .ok_or(ParseError::IntegerOverflow)?;
+        let desc = data
+            .get(desc_start..desc_end)
+            .ok_or(ParseError::BadOffset(desc_start as u64))?;
+        *offset = padded(desc_end).ok_or(ParseError::IntegerOverflow)?;
+        Ok(match (name, n_type) {
+            (abi::ELF_NOTE_GNU, abi::NT_GNU_ABI_TAG) => {
+                let mut at = 0;
+                Note::GnuAbiTag(NoteGnuAbiTag {
+                    os: endian.parse_u32_at(&mut at, desc)?,
+                    major: endian.parse_u32_at(&mut at, desc)?,
+                    minor: endian.parse_u32_at(&mut at, desc)?,
+                    subminor: endian.parse_u32_at(&mut at, desc)?,
+                })
+            }
+            (abi::ELF_NOTE_GNU, abi::NT_GNU_BUILD_ID) => Note::GnuBuildId(NoteGnuBuildId(desc)),
+            _ => Note::Unknown(NoteAny { n_type, name, desc }),
+        })
+    };
+    while offset < data.len() {
+        let start = offset;
+        match next(&mut offset) {
+            Ok(note) => notes.push(note),
+            Err(_) => return (notes, Some(start)),
+        }
+    }
+    (notes, None)
+}
+
+/// The value of the first entry with `d_tag`, as an address or integer.
+fn dynamic_value(dynamic: &Dynamic, d_tag: i64) -> Option<u64> {
+    dynamic
+        .entries
+        .iter()
+        .find(|entry| entry.d_tag == d_tag)
+        .map(|entry| entry.clone().d_val())
+}
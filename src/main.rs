@@ -1,306 +1,3080 @@
-use clap::Parser;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
 use elf::abi;
-use elf::dynamic;
 use elf::endian::AnyEndian;
-use elf::hash::GnuHashHeader;
-use elf::relocation::Rela;
-use elf::section::SectionHeader;
+use elf::file::{Class, FileHeader};
+use elf::note::Note;
+use elf::parse::ParseError;
 use elf::segment::ProgramHeader;
 use elf::string_table::StringTable;
-use elf::symbol::Symbol;
-use elf::to_str;
-use elf::ElfBytes;
-use std::collections::HashMap;
+use hrelf::{
+    archive, attributes, demangle, dwarf, r_type, symver, to_str, AddressLookup, Anomaly, Dynamic,
+    ElfReport, Error, ExecutableStack, Extent, Function, HashHistogram, HeaderCounts, InitFini,
+    Libraries, NamedSymbol, NoteSource, RelocationSection, Section, SectionGroup,
+    SectionSymbolCount, SymbolMatch, Symbols, UnwindTable, VersionIndex, VersionSection,
+    CORRUPT_NAME, GRP_COMDAT,
+};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+mod color;
+mod diff;
+mod json;
+mod mmap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+/// A DWARF section --debug-dump can decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum DebugDump {
+    /// The line number programs in .debug_line
+    Line,
+    /// A summary of each compile unit in .debug_info
+    Info,
+    /// The strings of .debug_str and .debug_line_str, as -p shows them
+    Str,
+}
+
+/// When --color styles the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum ColorWhen {
+    /// Only when stdout is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+/// The order --sort lists symbol table rows in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum SymbolSort {
+    /// Lowest st_value first
+    Value,
+    /// Biggest st_size first
+    Size,
+    /// Alphabetically by name
+    Name,
+}
+
+/// A symbol type --symbol-type can select, by its readelf name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum SymbolType {
+    Notype,
+    Object,
+    Func,
+    Section,
+    File,
+    Common,
+    Tls,
+    Ifunc,
+}
+
+impl SymbolType {
+    fn st_type(self) -> u8 {
+        match self {
+            SymbolType::Notype => abi::STT_NOTYPE,
+            SymbolType::Object => abi::STT_OBJECT,
+            SymbolType::Func => abi::STT_FUNC,
+            SymbolType::Section => abi::STT_SECTION,
+            SymbolType::File => abi::STT_FILE,
+            SymbolType::Common => abi::STT_COMMON,
+            SymbolType::Tls => abi::STT_TLS,
+            SymbolType::Ifunc => abi::STT_GNU_IFUNC,
+        }
+    }
+}
+
+/// A symbol binding --symbol-bind can select, by its readelf name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum SymbolBind {
+    Local,
+    Global,
+    Weak,
+    Unique,
+}
+
+impl SymbolBind {
+    fn st_bind(self) -> u8 {
+        match self {
+            SymbolBind::Local => abi::STB_LOCAL,
+            SymbolBind::Global => abi::STB_GLOBAL,
+            SymbolBind::Weak => abi::STB_WEAK,
+            SymbolBind::Unique => abi::STB_GNU_UNIQUE,
+        }
+    }
+}
+
+impl DebugDump {
+    /// The sections the dump can't do without.
+    fn sections(self) -> &'static [&'static str] {
+        match self {
+            DebugDump::Line => &[".debug_line"],
+            DebugDump::Info => &[".debug_info", ".debug_abbrev"],
+            // Reports a missing .debug_str itself
+            DebugDump::Str => &[],
+        }
+    }
+}
+
+/// Sections DWARF strings may live in, read by every --debug-dump.
+const DWARF_STRING_SECTIONS: [&str; 3] = [".debug_str", ".debug_line_str", ".debug_str_offsets"];
+
+/// A -x or -p request, kept in command-line order.
+#[derive(Clone, Debug)]
+enum Dump {
+    Hex(String),
+    Strings(String),
+}
+
+impl Dump {
+    fn selector(&self) -> &str {
+        match self {
+            Dump::Hex(selector) | Dump::Strings(selector) => selector,
+        }
+    }
+}
 
 #[derive(Parser, Debug)]
 #[command(
     author,
     about,
     version,
-    long_about = "This is a tool for manipulating ELF files."
+    long_about = "This is a tool for manipulating ELF files.",
+    disable_help_flag = true,
+    disable_version_flag = true
 )]
 struct Args {
+    /// An ELF file or archive to read, as many times as needed; standard
+    /// input if there are none or for "-"
+    #[arg(short, long)]
+    file: Vec<PathBuf>,
+
+    /// More files to read, after any -f ones
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Equivalent to: -h -S -g -l -d -r -s -V -n -A and a summary of -I; takes precedence over
+    /// any narrower display option given alongside it
+    #[arg(short, long)]
+    all: bool,
+
+    /// Display the ELF file header
+    #[arg(short = 'h', long)]
+    file_header: bool,
+
+    /// Display a one-line identification of each file, prefixed by its name
+    #[arg(long)]
+    summary: bool,
+
+    /// Compare two files instead of displaying them: header fields, sections,
+    /// segments and symbols that differ. Exits with 1 if they do
+    #[arg(long)]
+    diff: bool,
+
+    /// Warn about sections and segments that point outside the file, sections
+    /// or loadable segments that overlap, and empty tables; exit with failure
+    /// if any are found
+    #[arg(long, visible_alias = "validate")]
+    check: bool,
+
+    /// Display the sections' headers
+    #[arg(short = 'S', long, visible_alias = "sections")]
+    section_headers: bool,
+
+    /// Display the section details
+    #[arg(short = 't', long)]
+    section_details: bool,
+
+    /// Display the section groups
+    #[arg(short = 'g', long)]
+    section_groups: bool,
+
+    /// Display the program headers
+    #[arg(short = 'l', long, visible_alias = "program-headers")]
+    segments: bool,
+
+    /// Display the dynamic section (if present)
+    #[arg(short, long)]
+    dynamic: bool,
+
+    /// Display just the DT_NEEDED libraries, one per line, then any DT_SONAME and search paths
+    #[arg(long)]
+    needed: bool,
+
+    /// Display the constructors and destructors the dynamic section names,
+    /// DT_INIT, DT_FINI and their arrays, in the order they run
+    #[arg(long)]
+    init_fini: bool,
+
+    /// Display the relocations (if present)
+    #[arg(short, long)]
+    relocs: bool,
+
+    /// Display the symbol table, .symtab
+    #[arg(short, long, visible_alias = "symbols")]
+    syms: bool,
+
+    /// Display the dynamic symbol table, .dynsym
+    #[arg(long)]
+    dyn_syms: bool,
+
+    /// Display the .symtab and .dynsym entries named <NAME>; fail if there are none
+    #[arg(long, value_name = "NAME")]
+    symbol: Option<String>,
+
+    /// Display the symbol, or else the section, that the address <HEX> falls inside
+    #[arg(long, value_name = "HEX", value_parser = parse_address)]
+    addr: Option<u64>,
+
+    /// Display the entry point address and the symbol it's in
+    #[arg(long)]
+    entry_point: bool,
+
+    /// Use the dynamic section info when displaying symbols and relocations,
+    /// ignoring the section headers
+    #[arg(short = 'D', long)]
+    use_dynamic: bool,
+
+    /// Allow output width to exceed 80 characters: one line per section and segment
+    #[arg(short = 'W', long)]
+    wide: bool,
+
+    /// Spell out section and segment alignments as powers of two, e.g. "4096 (2^12)"
+    #[arg(long)]
+    verbose: bool,
+
+    /// Display how many symbols, and how many bytes of them, each allocated section holds
+    #[arg(long)]
+    section_symbol_counts: bool,
+
+    /// List -s and --dyn-syms rows by value, size or name instead of table order
+    #[arg(long, value_enum, value_name = "KEY")]
+    sort: Option<SymbolSort>,
+
+    /// Only list -s and --dyn-syms rows of this type, e.g. FUNC
+    #[arg(long, value_enum, ignore_case = true, value_name = "TYPE")]
+    symbol_type: Option<SymbolType>,
+
+    /// Only list -s and --dyn-syms rows with this binding, e.g. GLOBAL
+    #[arg(long, value_enum, ignore_case = true, value_name = "BIND")]
+    symbol_bind: Option<SymbolBind>,
+
+    /// Only list -s and --dyn-syms rows the file defines
+    #[arg(long, conflicts_with = "undefined_only")]
+    defined_only: bool,
+
+    /// Only list -s and --dyn-syms rows that are SHN_UNDEF, the symbols the file imports
+    #[arg(long)]
+    undefined_only: bool,
+
+    /// Decode mangled C++ and Rust symbol names
+    #[arg(short = 'C', long)]
+    demangle: bool,
+
+    /// Display a histogram of bucket list lengths
+    #[arg(short = 'I', long)]
+    histogram: bool,
+
+    /// What -a shows of the histogram unless -I is given too: one line per table
+    #[arg(skip)]
+    histogram_summary: bool,
+
+    /// Display the version sections (if present)
+    #[arg(short = 'V', long)]
+    version_info: bool,
+
+    /// Dump the contents of section <number|name> as bytes
+    #[arg(short = 'x', long, value_name = "number|name")]
+    hex_dump: Vec<String>,
+
+    /// Dump the contents of section <number|name> as strings
+    #[arg(short = 'p', long, value_name = "number|name")]
+    string_dump: Vec<String>,
+
+    /// Dump the file bytes of each PT_LOAD segment at their load addresses
+    #[arg(long)]
+    segment_contents: bool,
+
+    /// -x and -p merged back into the order they were given in
+    #[arg(skip)]
+    dumps: Vec<Dump>,
+
+    /// Display the contents of DWARF debug sections
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "SECTION")]
+    debug_dump: Vec<DebugDump>,
+
+    /// Display the .eh_frame_hdr unwind lookup table (if present)
+    #[arg(short = 'u', long)]
+    unwind: bool,
+
+    /// Display the symbol/file index in an archive
+    #[arg(short = 'c', long)]
+    archive_index: bool,
+
+    /// Display the core notes (if present)
     #[arg(short, long)]
-    file: std::path::PathBuf,
+    notes: bool,
+
+    /// Display architecture specific information (if any)
+    #[arg(short = 'A', long)]
+    arch_specific: bool,
+
+    /// Map the file into memory instead of reading it, the default past 64 MiB
+    #[arg(long)]
+    mmap: bool,
+
+    /// Color section and symbol types and flags: executable in red, writable in yellow
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorWhen::Auto)]
+    color: ColorWhen,
+
+    /// Output format; json prints everything unless display options narrow it
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+
+    /// Print help
+    #[arg(long, action = clap::ArgAction::Help)]
+    help: Option<bool>,
+
+    /// Print version
+    #[arg(long, action = clap::ArgAction::Version)]
+    version: Option<bool>,
+}
+
+impl Args {
+    fn any_selected(&self) -> bool {
+        self.archive_index || self.diff || self.any_report_selected()
+    }
+
+    /// Whether anything is selected that's shown per ELF file, which for
+    /// an archive means per member.
+    fn any_report_selected(&self) -> bool {
+        self.file_header
+            || self.summary
+            || self.check
+            || self.section_headers
+            || self.section_details
+            || self.section_groups
+            || self.segments
+            || self.dynamic
+            || self.needed
+            || self.init_fini
+            || self.relocs
+            || self.syms
+            || self.dyn_syms
+            || self.symbol.is_some()
+            || self.addr.is_some()
+            || self.entry_point
+            || self.section_symbol_counts
+            || self.histogram
+            || self.histogram_summary
+            || self.version_info
+            || !self.dumps.is_empty()
+            || self.segment_contents
+            || self.unwind
+            || !self.debug_dump.is_empty()
+            || self.notes
+            || self.arch_specific
+    }
+
+    fn select_all(&mut self) {
+        self.file_header = true;
+        self.section_headers = true;
+        self.section_groups = true;
+        self.segments = true;
+        self.dynamic = true;
+        self.relocs = true;
+        self.syms = true;
+        self.dyn_syms = true;
+        self.histogram_summary = !self.histogram;
+        self.version_info = true;
+        self.notes = true;
+        self.arch_specific = true;
+    }
+}
+
+/// Column widths that follow the ELF class: ELF32 files get the narrower
+/// address and r_info fields readelf prints for them.
+#[derive(Clone, Copy)]
+struct Widths {
+    /// Hex digits in an address, offset or size.
+    addr: usize,
+    /// Hex digits in a relocation's r_info.
+    info: usize,
+}
+
+impl Widths {
+    fn of(report: &ElfReport) -> Self {
+        match report.header().class {
+            Class::ELF32 => Widths { addr: 8, info: 8 },
+            Class::ELF64 => Widths { addr: 16, info: 12 },
+        }
+    }
+
+    fn is_elf32(self) -> bool {
+        self.addr == 8
+    }
+
+    /// r_info rebuilt from its parts: ELF32 packs the type into the low
+    /// byte, ELF64 into the low 32 bits.
+    fn r_info(self, r_sym: u32, r_type: u32) -> String {
+        if self.is_elf32() {
+            format!("{:08x}", r_sym << 8 | r_type & 0xff)
+        } else {
+            format!("{:04x}{:08x}", r_sym, r_type)
+        }
+    }
 }
 
 #[rustfmt::skip]
-fn parse_elf_header(ehdr: elf::file::FileHeader<AnyEndian>, ident: &[u8]) {
+fn parse_elf_header(ehdr: &FileHeader<AnyEndian>, ident: &[u8], counts: HeaderCounts) {
+    // Escaped counts show the sentinel and then the real value, as readelf does
+    let count = |field: u64, real: u64| match field == real {
+        true => field.to_string(),
+        false => format!("{} ({})", field, real),
+    };
     println!("ELF Header:");
     println!("  Magic:    {:02x?}", ident);
     println!("  Class:                      {:?}", ehdr.class);
-    println!("  Data:                       {:?}", ehdr.endianness);
+    println!("  Data:                       {}", to_str::ei_data_to_string(ident[abi::EI_DATA]));
     println!("  Version:                    {:?} (current)", ident[abi::EI_VERSION]);
     println!("  OS/ABI:                     {}", to_str::e_osabi_to_string(ehdr.osabi));
     println!("  ABI Version:                {:?}", ehdr.abiversion);
-    println!("  Type:                       {}", to_str::e_type_to_human_str(ehdr.e_type).unwrap());
-    println!("  Machine:                    {}", to_str::e_machine_to_human_str(ehdr.e_machine).unwrap());
+    println!("  Type:                       {}", to_str::e_type_to_human_string(ehdr.e_type));
+    println!("  Machine:                    {}", to_str::e_machine_to_human_string(ehdr.e_machine));
     println!("  Version:                    0x{:x}", ehdr.version);
     println!("  Entry point address:        0x{:x}", ehdr.e_entry);
     println!("  Start of program headers:   {:?} (bytes into file)", ehdr.e_phoff);
     println!("  Start of section headers:   {:?} (bytes into file)", ehdr.e_shoff);
-    println!("  Flags:                      0x{:x}", ehdr.e_flags);
+    println!("  Flags:                      {}", to_str::e_flags_to_string(ehdr.e_machine, ehdr.e_flags));
     println!("  Size of this header:        {:?} (bytes into file)", ehdr.e_ehsize);
     println!("  Size of program headers:    {:?} (bytes into file)", ehdr.e_phentsize);
-    println!("  Number of program headers:  {:?}", ehdr.e_phnum);
+    println!("  Number of program headers:  {}", count(ehdr.e_phnum as u64, counts.phnum as u64));
     println!("  Size of section headers:    {:?} (bytes into file)", ehdr.e_shentsize);
-    println!("  Number of section headers:  {:?}", ehdr.e_shnum);
-    println!("  Section header string table index: {:?}", ehdr.e_shstrndx);
-    println!("");
+    println!("  Number of section headers:  {}", count(ehdr.e_shnum as u64, counts.shnum));
+    println!("  Section header string table index: {}", count(ehdr.e_shstrndx as u64, counts.shstrndx as u64));
+    println!();
+}
+
+/// A file(1) style line: class, byte order, kind, machine, then how it's
+/// linked, whether .symtab survives and the build ID, where they apply.
+fn parse_summary(report: &ElfReport) -> Result<String, ParseError> {
+    let ehdr = report.header();
+    let segments = report.segments().unwrap_or_default();
+    let has_segment = |p_type| segments.iter().any(|phdr| phdr.p_type == p_type);
+    let kind = match ehdr.e_type {
+        abi::ET_REL => "relocatable",
+        abi::ET_EXEC => "executable",
+        // Position independent executables are ET_DYN too, but ask for a loader
+        abi::ET_DYN if has_segment(abi::PT_INTERP) => "pie executable",
+        abi::ET_DYN => "shared object",
+        abi::ET_CORE => "core file",
+        _ => "unknown type",
+    };
+    let mut words = vec![
+        format!(
+            "{:?} {} {}",
+            ehdr.class,
+            match ehdr.endianness {
+                AnyEndian::Little => "LSB",
+                AnyEndian::Big => "MSB",
+            },
+            kind
+        ),
+        to_str::e_machine_to_short_string(ehdr.e_machine),
+    ];
+    if matches!(ehdr.e_type, abi::ET_EXEC | abi::ET_DYN) {
+        words.push(match has_segment(abi::PT_DYNAMIC) {
+            true => "dynamically linked".to_string(),
+            false => "statically linked".to_string(),
+        });
+    }
+    if ehdr.e_type != abi::ET_CORE {
+        words.push(match report.symbols()? {
+            Some(_) => "not stripped".to_string(),
+            None => "stripped".to_string(),
+        });
+    }
+    let build_id = report
+        .notes()?
+        .into_iter()
+        .flat_map(|group| group.notes)
+        .find_map(|note| match note {
+            Note::GnuBuildId(build_id) => Some(build_id.0),
+            _ => None,
+        });
+    if let Some(build_id) = build_id {
+        let digest: String = build_id.iter().map(|b| format!("{:02x}", b)).collect();
+        words.push(format!("build-id={}", digest));
+    }
+    Ok(words.join(", "))
+}
+
+/// One "<file>: warning: ..." line per anomaly, so a clean file prints
+/// nothing.
+fn parse_check(path: &Path, anomalies: &[Anomaly], e_machine: u16) {
+    let describe = |extent: &Extent| match *extent {
+        Extent::ElfHeader => "the ELF header".to_string(),
+        Extent::ProgramHeaders => "the program header table".to_string(),
+        Extent::SectionHeaders => "the section header table".to_string(),
+        Extent::Section(index, name) => format!("section [{}] '{}'", index, name),
+        Extent::Segment(index) => format!("program header {}", index),
+    };
+    for anomaly in anomalies {
+        let message = match anomaly {
+            Anomaly::OutOfBounds {
+                extent,
+                offset,
+                size,
+            } => format!(
+                "{} claims 0x{:x} bytes at offset 0x{:x}, past the end of the file",
+                describe(extent),
+                size,
+                offset
+            ),
+            Anomaly::Overlap(first, second) => {
+                format!("{} overlaps {}", describe(second), describe(first))
+            }
+            Anomaly::Empty(extent) => format!("{} is an empty table", describe(extent)),
+            Anomaly::LoadOverlap(first, second) => format!(
+                "loadable segments {} and {} overlap in memory",
+                first, second
+            ),
+            Anomaly::FileSizeExceedsMemSize(index) => format!(
+                "loadable segment {} has a larger file size than memory size",
+                index
+            ),
+            Anomaly::ExecutableStack(stack) => executable_stack_warning(*stack, e_machine),
+        };
+        eprintln!("{}: warning: {}", path.display(), message);
+    }
+}
+
+/// Why the stack will be executable, naming the machine's default when
+/// that's the reason.
+fn executable_stack_warning(stack: ExecutableStack, e_machine: u16) -> String {
+    match stack {
+        ExecutableStack::Requested(index) => format!(
+            "program header {} (PT_GNU_STACK) asks for an executable stack",
+            index
+        ),
+        ExecutableStack::ByDefault => format!(
+            "there is no PT_GNU_STACK, so the stack is executable, the default on {}",
+            to_str::e_machine_to_short_string(e_machine)
+        ),
+    }
 }
 
-fn parse_section_headers(shdrs: &Vec<SectionHeader>, strtab: &StringTable) {
+fn parse_section_headers(sections: &[Section], widths: Widths, wide: bool, verbose: bool) {
+    let w = widths.addr;
     println!("Section Headers:");
-    println!("  [Nr] Name               Type              Address            Offset");
-    println!("       Size               EntSize           Flags  Link  Info  Align");
-    for (i, shdr) in shdrs.iter().enumerate() {
+    // ELF32 rows fit on one line even without -W, as in readelf
+    let one_line = wide || widths.is_elf32();
+    if one_line {
         println!(
-            "  [{:>2}] {:<19}{:<15}   {:016x}   {:08x}",
-            i,
-            strtab.get(shdr.sh_name as usize).unwrap(),
-            to_str::sh_type_to_string(shdr.sh_type),
-            shdr.sh_addr,
-            shdr.sh_offset
+            "  [Nr] {:<19}{:<18}{:<a$}{:<11}{:<a$}{:<e$}Flags  Link  Info  Align",
+            "Name",
+            "Type",
+            "Address",
+            "Offset",
+            "Size",
+            "EntSize",
+            a = w + 3,
+            e = w + 2,
+        );
+    } else {
+        println!("  [Nr] Name               Type              Address            Offset");
+        println!("       Size               EntSize           Flags  Link  Info  Align");
+    }
+    for (i, Section { name, shdr }) in sections.iter().enumerate() {
+        let style = color::permissions(
+            shdr.sh_flags & abi::SHF_EXECINSTR as u64 != 0,
+            shdr.sh_flags & abi::SHF_WRITE as u64 != 0,
+        );
+        // Padded before painting, so the escapes don't count toward the width
+        // A name too long for its column still gets a space after it
+        let name = color::paint(style, &format!("{:<18} ", shown_name(name, shdr.sh_name)));
+        let sh_type = color::paint(
+            color::SECTION_TYPE,
+            &format!("{:<15}", to_str::sh_type_to_string(shdr.sh_type)),
         );
+        let flags = color::paint(
+            style,
+            // Right-aligned under the heading, as readelf does
+            &format!("{:>5} ", to_str::sh_flags_to_string(shdr.sh_flags)),
+        );
+        if one_line {
+            println!(
+                "  [{:>2}] {}{}   {:0w$x}   {:08x}   {:0w$x}   {:0w$x}  {} {:<5} {:<5} {}",
+                i,
+                name,
+                sh_type,
+                shdr.sh_addr,
+                shdr.sh_offset,
+                shdr.sh_size,
+                shdr.sh_entsize,
+                flags,
+                shdr.sh_link,
+                shdr.sh_info,
+                alignment(shdr.sh_addralign, verbose),
+            );
+        } else {
+            println!(
+                "  [{:>2}] {}{}   {:016x}   {:08x}",
+                i, name, sh_type, shdr.sh_addr, shdr.sh_offset
+            );
+            println!(
+                "       {:016x}   {:016x}  {} {:<5} {:<5} {:<5}",
+                shdr.sh_size,
+                shdr.sh_entsize,
+                flags,
+                shdr.sh_link,
+                shdr.sh_info,
+                alignment(shdr.sh_addralign, verbose),
+            );
+        }
+        // The o, p and x letters don't say which bits they stand for
+        if let Some(note) = to_str::sh_flags_note(shdr.sh_flags) {
+            println!("      [Other flags: {}]", note);
+        }
+    }
+    println!("{}", to_str::SH_FLAGS_KEY);
+    println!();
+}
+
+/// An alignment, followed under --verbose by the power of two it is.
+fn alignment(align: u64, verbose: bool) -> String {
+    if verbose && align.is_power_of_two() {
+        format!("{} (2^{})", align, align.trailing_zeros())
+    } else {
+        align.to_string()
+    }
+}
+
+fn parse_section_details(report: &ElfReport, sections: &[Section]) -> Result<(), ParseError> {
+    // ELF32 fields fit one row, laid out as readelf does
+    let widths = Widths::of(report);
+    let w = widths.addr;
+    println!("Section Headers:");
+    println!("  [Nr] Name");
+    if widths.is_elf32() {
+        println!("       Type            Addr     Off    Size   ES   Lk Inf Al");
+    } else {
+        println!("       Type              Address          Offset            Link");
+        println!("       Size              EntSize          Info              Align");
+    }
+    println!("       Flags");
+    for (i, section) in sections.iter().enumerate() {
+        let shdr = &section.shdr;
+        println!("  [{:>2}] {}", i, shown_name(section.name, shdr.sh_name));
+        if widths.is_elf32() {
+            println!(
+                "       {:<15} {:08x} {:06x} {:06x} {:02x} {:>3} {:>3} {:>2}",
+                to_str::sh_type_to_string(shdr.sh_type),
+                shdr.sh_addr,
+                shdr.sh_offset,
+                shdr.sh_size,
+                shdr.sh_entsize,
+                shdr.sh_link,
+                shdr.sh_info,
+                shdr.sh_addralign
+            );
+        } else {
+            println!(
+                "       {:<16} {:016x}  {:016x}  {}",
+                to_str::sh_type_to_string(shdr.sh_type),
+                shdr.sh_addr,
+                shdr.sh_offset,
+                shdr.sh_link
+            );
+            println!(
+                "       {:016x} {:016x}  {:<16}  {}",
+                shdr.sh_size, shdr.sh_entsize, shdr.sh_info, shdr.sh_addralign
+            );
+        }
         println!(
-            "       {:016x}   {:016x}  {:<6} {:<5} {:<5} {:<5}",
-            shdr.sh_size,
-            shdr.sh_entsize,
+            "       [{:0w$x}]: {}",
             shdr.sh_flags,
-            shdr.sh_link,
-            shdr.sh_info,
-            shdr.sh_addralign,
+            to_str::sh_flags_to_words(shdr.sh_flags)
         );
+        if let Some(chdr) = report.compression_header(section)? {
+            println!(
+                "       {}, {:0w$x}, {}",
+                to_str::ch_type_to_string(chdr.ch_type),
+                chdr.ch_size,
+                chdr.ch_addralign
+            );
+        }
     }
-    println!("");
+    println!();
+    Ok(())
 }
 
-fn parse_program_headers(phdrs: &Vec<ProgramHeader>) {
-    println!("Program Headers:");
-    println!("  Type            Offset           VirtAddr         PhysAddr");
-    println!("                  FileSiz          MemSiz           Flags  Align");
-    for phdr in phdrs {
+fn parse_section_groups(groups: &[SectionGroup]) {
+    for group in groups {
+        let kind = match group.flags {
+            GRP_COMDAT => "COMDAT ".to_string(),
+            0 => String::new(),
+            flags => format!("[0x{:x}] ", flags),
+        };
+        println!();
         println!(
-            "  {:<15} {:016x} {:016x} {:016x}",
-            to_str::p_type_to_string(phdr.p_type),
-            phdr.p_offset,
-            phdr.p_vaddr,
-            phdr.p_paddr
+            "{}group section [{:5}] `{}' [{}] contains {} sections:",
+            kind,
+            group.index,
+            group.name,
+            group.signature,
+            group.members.len()
         );
+        println!("   [Index]    Name");
+        for (index, name) in &group.members {
+            println!("   [{:5}]   {}", index, name);
+        }
+    }
+    println!();
+}
+
+fn parse_program_headers(report: &ElfReport, phdrs: &[ProgramHeader], wide: bool, verbose: bool) {
+    let widths = Widths::of(report);
+    let w = widths.addr;
+    println!("Program Headers:");
+    let one_line = wide || widths.is_elf32();
+    if one_line {
         println!(
-            "                  {:016x} {:016x} {:<6} {:<5}",
-            phdr.p_filesz,
-            phdr.p_memsz,
-            to_str::p_flags_to_string(phdr.p_flags),
-            phdr.p_align,
+            "  {:<16}{:<c$}{:<c$}{:<c$}{:<c$}{:<c$}Flags  Align",
+            "Type",
+            "Offset",
+            "VirtAddr",
+            "PhysAddr",
+            "FileSiz",
+            "MemSiz",
+            c = w + 1,
         );
+    } else {
+        println!("  Type            Offset           VirtAddr         PhysAddr");
+        println!("                  FileSiz          MemSiz           Flags  Align");
     }
-    println!("");
+    for phdr in phdrs {
+        let flags = color::paint(
+            color::permissions(phdr.p_flags & abi::PF_X != 0, phdr.p_flags & abi::PF_W != 0),
+            &to_str::p_flags_to_string(phdr.p_flags),
+        );
+        if one_line {
+            println!(
+                "  {:<15} {:0w$x} {:0w$x} {:0w$x} {:0w$x} {:0w$x} {}    {}",
+                to_str::p_type_to_string(phdr.p_type),
+                phdr.p_offset,
+                phdr.p_vaddr,
+                phdr.p_paddr,
+                phdr.p_filesz,
+                phdr.p_memsz,
+                flags,
+                alignment(phdr.p_align, verbose),
+            );
+        } else {
+            println!(
+                "  {:<15} {:016x} {:016x} {:016x}",
+                to_str::p_type_to_string(phdr.p_type),
+                phdr.p_offset,
+                phdr.p_vaddr,
+                phdr.p_paddr
+            );
+            // Three flag columns then four spaces keep Align under its heading
+            println!(
+                "                  {:016x} {:016x} {}    {}",
+                phdr.p_filesz,
+                phdr.p_memsz,
+                flags,
+                alignment(phdr.p_align, verbose),
+            );
+        }
+        if phdr.p_type == abi::PT_INTERP {
+            println!(
+                "      [Requesting program interpreter: {}]",
+                interpreter(report, phdr).unwrap_or(CORRUPT_NAME)
+            );
+        }
+        if let Some(note) = to_str::p_flags_note(phdr.p_flags) {
+            println!("      [Other flags: {}]", note);
+        }
+        // The loader maps whole pages, so a segment only lands where it
+        // should when its offset and address agree within the alignment
+        if phdr.p_align > 1 && phdr.p_offset % phdr.p_align != phdr.p_vaddr % phdr.p_align {
+            println!("      [Misaligned: offset and address differ modulo the alignment]");
+        }
+    }
+    println!();
 }
 
-fn section_to_segment_mapping(
-    shdrs: &Vec<SectionHeader>,
-    phdrs: &Vec<ProgramHeader>,
-    strtab: &StringTable,
-) {
+fn section_to_segment_mapping(mapping: &[Vec<Section>]) {
     println!(" Section to Segment mapping:");
     println!("  Segment Sections...");
-    for (i, phdr) in phdrs.iter().enumerate() {
-        let mut sections: Vec<String> = Vec::new();
-        for shdr in shdrs {
-            if shdr.sh_addr >= phdr.p_vaddr
-                && shdr.sh_addr + shdr.sh_size <= phdr.p_vaddr + phdr.p_memsz
-            {
-                sections.push(strtab.get(shdr.sh_name as usize).unwrap().to_string());
-            }
+    for (i, sections) in mapping.iter().enumerate() {
+        println!("  {:02}      {}", i, section_names(sections));
+    }
+    println!();
+}
+
+/// The names of `sections`, space-separated.
+fn section_names(sections: &[Section]) -> String {
+    let names: Vec<_> = sections
+        .iter()
+        .map(|section| shown_name(section.name, section.shdr.sh_name))
+        .collect();
+    names.join(" ")
+}
+
+/// The path in a PT_INTERP segment, which must be a single NUL-terminated
+/// string that fits in the file.
+fn interpreter<'data>(report: &ElfReport<'data>, phdr: &ProgramHeader) -> Option<&'data str> {
+    let path = report.segment_data(phdr).ok()?.strip_suffix(b"\0")?;
+    if path.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(path).ok()
+}
+
+/// Spells out a PT_TLS segment: each thread's block starts as a copy of
+/// the p_filesz byte template (.tdata) and is zero-filled up to p_memsz
+/// (.tbss).
+fn parse_tls_segment(index: usize, phdr: &ProgramHeader, sections: Option<&[Section]>) {
+    println!(" Thread-local storage segment {:02}:", index);
+    println!("  Template size: 0x{:x}", phdr.p_filesz);
+    println!("  Block size:    0x{:x}", phdr.p_memsz);
+    println!("  Alignment:     0x{:x}", phdr.p_align);
+    if let Some(sections) = sections {
+        println!("  Sections:      {}", section_names(sections));
+    }
+    println!();
+}
+
+/// Lists the init functions in the order the loader calls them, pre-init
+/// array, DT_INIT then the init array, and the fini functions likewise: the
+/// fini array backwards, then DT_FINI.
+fn parse_init_fini<'data>(init_fini: &InitFini<'data>, widths: Widths, demangle: bool) {
+    let w = widths.addr;
+    let labelled = |tag: &str, functions: &[Function<'data>]| {
+        let entries = functions.iter().enumerate();
+        entries
+            .map(|(i, function)| (format!("{}[{}]", tag, i), *function))
+            .collect::<Vec<_>>()
+    };
+    let mut init = labelled("DT_PREINIT_ARRAY", &init_fini.preinit_array);
+    init.extend(init_fini.init.map(|init| ("DT_INIT".to_string(), init)));
+    init.extend(labelled("DT_INIT_ARRAY", &init_fini.init_array));
+    let mut fini = labelled("DT_FINI_ARRAY", &init_fini.fini_array);
+    fini.reverse();
+    fini.extend(init_fini.fini.map(|fini| ("DT_FINI".to_string(), fini)));
+
+    for (heading, functions) in [("Init", init), ("Fini", fini)] {
+        if functions.is_empty() {
+            println!(
+                "There are no {} functions in this file.",
+                heading.to_lowercase()
+            );
+            println!();
+            continue;
         }
-        sections.retain(|s| !s.is_empty());
-        println!("  {:02}      {}", i, sections.join(" "));
+        println!("{} functions, in the order they run:", heading);
+        for (source, function) in functions {
+            let name = function.name.unwrap_or("");
+            let name = match demangle {
+                true => demangle::demangle(name).unwrap_or_else(|| name.to_string()),
+                false => name.to_string(),
+            };
+            println!("  0x{:0w$x} {:<20} {}", function.address, source, name);
+        }
+        println!();
+    }
+}
+
+/// How readelf -d renders a dynamic entry's value.
+enum DynamicValue {
+    /// A string table offset, shown as the string after this label.
+    String(&'static str),
+    /// A size in bytes.
+    Size,
+    /// A number of entries.
+    Count,
+    /// DT_FLAGS or DT_FLAGS_1 bits.
+    Flags,
+    Flags1,
+    /// DT_PLTREL, naming the tag of the PLT relocations' kind.
+    Tag,
+    /// Addresses and anything else, in hex.
+    Hex,
+}
+
+fn dynamic_value_kind(d_tag: i64) -> DynamicValue {
+    match d_tag {
+        abi::DT_NEEDED => DynamicValue::String("Shared library"),
+        abi::DT_SONAME => DynamicValue::String("Library soname"),
+        abi::DT_RPATH => DynamicValue::String("Library rpath"),
+        abi::DT_RUNPATH => DynamicValue::String("Library runpath"),
+        to_str::DT_AUXILIARY => DynamicValue::String("Auxiliary library"),
+        to_str::DT_FILTER => DynamicValue::String("Filter library"),
+        abi::DT_CONFIG => DynamicValue::String("Configuration file"),
+        abi::DT_DEPAUDIT => DynamicValue::String("Dependency audit library"),
+        abi::DT_AUDIT => DynamicValue::String("Audit library"),
+        abi::DT_PLTRELSZ
+        | abi::DT_RELASZ
+        | abi::DT_RELAENT
+        | abi::DT_STRSZ
+        | abi::DT_SYMENT
+        | abi::DT_RELSZ
+        | abi::DT_RELENT
+        | abi::DT_INIT_ARRAYSZ
+        | abi::DT_FINI_ARRAYSZ
+        | abi::DT_PREINIT_ARRAYSZ
+        | to_str::DT_RELRSZ
+        | to_str::DT_RELRENT
+        | abi::DT_SYMINSZ
+        | abi::DT_SYMINENT
+        | abi::DT_MOVEENT
+        | abi::DT_MOVESZ
+        | abi::DT_PLTPADSZ
+        | abi::DT_GNU_CONFLICTSZ
+        | abi::DT_GNU_LIBLISTSZ => DynamicValue::Size,
+        abi::DT_VERDEFNUM | abi::DT_VERNEEDNUM | abi::DT_RELACOUNT | abi::DT_RELCOUNT => {
+            DynamicValue::Count
+        }
+        abi::DT_FLAGS => DynamicValue::Flags,
+        abi::DT_FLAGS_1 => DynamicValue::Flags1,
+        abi::DT_PLTREL => DynamicValue::Tag,
+        _ => DynamicValue::Hex,
+    }
+}
+
+/// A d_tag as readelf names it, without the DT_ prefix, or the range an
+/// unnamed one falls in.
+fn dynamic_type_name(d_tag: i64) -> String {
+    match to_str::d_tag_to_str(d_tag) {
+        Some(name) => name.trim_start_matches("DT_").to_string(),
+        None => match d_tag {
+            abi::DT_LOOS..=abi::DT_HIOS => format!("Operating System specific: {:x}", d_tag),
+            abi::DT_LOPROC..=abi::DT_HIPROC => format!("Processor Specific: {:x}", d_tag),
+            _ => format!("<unknown>: {:x}", d_tag),
+        },
     }
-    println!("");
 }
 
-fn parse_dynamic_section(dynamics: &Vec<dynamic::Dyn>, offset: u64) {
+/// Prints the dynamic entries as readelf -d does, each value by its kind:
+/// strings from `strings` where it has them, sizes, counts, flags or hex.
+fn parse_dynamic_section(dynamic: &Dynamic, strings: Option<&StringTable>, widths: Widths) {
+    // The count runs through the first DT_NULL, which ElfReport::dynamic stops at
+    let count = dynamic.entries.len();
     println!(
-        "Dynamic section at offset 0x{:x} contains {} entries:",
-        offset,
-        dynamics.len()
+        "Dynamic section at offset 0x{:x} contains {} {}:",
+        dynamic.offset,
+        count,
+        if count == 1 { "entry" } else { "entries" }
     );
-    println!("  Tag        Type               Value");
-    for dynamic in dynamics {
+    println!("  Tag        Type                         Name/Value");
+    // The type column ends at the same place for either width of tag
+    let type_width = if widths.is_elf32() { 28 } else { 20 };
+    for entry in &dynamic.entries {
+        let d_val = entry.clone().d_val();
+        let value = match dynamic_value_kind(entry.d_tag) {
+            // An offset past DT_STRSZ is shown, not mistaken for a number
+            DynamicValue::String(label) => match strings.map(|strings| strings.get(d_val as usize))
+            {
+                Some(Ok(string)) => format!("{}: [{}]", label, string),
+                Some(Err(_)) => format!("{}: [<corrupt: {:#x}>]", label, d_val),
+                None => format!("0x{:x}", d_val),
+            },
+            DynamicValue::Size => format!("{} (bytes)", d_val),
+            DynamicValue::Count => d_val.to_string(),
+            DynamicValue::Flags => to_str::df_flags_to_string(d_val),
+            DynamicValue::Flags1 => format!("Flags: {}", to_str::df_1_flags_to_string(d_val)),
+            DynamicValue::Tag => dynamic_type_name(d_val as i64),
+            DynamicValue::Hex => format!("0x{:x}", d_val),
+        };
         println!(
-            "  0x{:08x} {:<18} 0x{:x}",
-            dynamic.d_tag,
-            to_str::d_tag_to_str(dynamic.d_tag).unwrap(),
-            dynamic.clone().d_val(),
+            " 0x{:0w$x} {:<t$} {}",
+            entry.d_tag,
+            format!("({})", dynamic_type_name(entry.d_tag)),
+            value,
+            w = widths.addr,
+            t = type_width,
         );
     }
-    println!("");
+    println!();
+}
+
+/// Prints the needed libraries bare, so the list pipes straight into other
+/// tools, and the rest in readelf -d's words.
+fn parse_libraries(libraries: &Libraries) {
+    for name in &libraries.needed {
+        println!("{}", name);
+    }
+    let labelled = [
+        ("soname", libraries.soname),
+        ("rpath", libraries.rpath),
+        ("runpath", libraries.runpath),
+    ];
+    for (label, value) in labelled {
+        if let Some(value) = value {
+            println!("Library {}: [{}]", label, value);
+        }
+    }
 }
 
-fn parse_reloacation_dynamic_section(rels: &Vec<Rela>, offset: u64) {
+/// Prints one SHT_REL or SHT_RELA section; only the latter has addends.
+fn parse_relocation_section(section: &RelocationSection, e_machine: u16, widths: Widths) {
+    let w = widths.addr;
+    let count = section.relocations.len();
     println!(
-        "Relocation section '.rela.dyn' at offset 0x{:x} contains {} entry:",
-        offset,
-        rels.len()
+        "Relocation section '{}' at offset 0x{:x} contains {} {}:",
+        section.name,
+        section.shdr.sh_offset,
+        count,
+        if count == 1 { "entry" } else { "entries" }
     );
-    println!("  Offset          Info                   Sym. Value    Sym. Name + Addend");
-    for rel in rels {
-        println!(
-            "  {:016x} {:04x}{:08x} {:016x}",
-            rel.r_offset, rel.r_sym, rel.r_type, rel.r_addend,
+    let rela = section.shdr.sh_type == abi::SHT_RELA;
+    // ELF32 values are narrower than their heading, so they're padded out to it
+    const SYM_VALUE_WIDTH: usize = "Sym. Value".len();
+    println!(
+        "  {:<c$}{:<i$}{:<19}{:<v$}{}",
+        "Offset",
+        "Info",
+        "Type",
+        "Sym. Value",
+        if rela {
+            "Sym. Name + Addend"
+        } else {
+            "Sym. Name"
+        },
+        c = w + 1,
+        i = widths.info + 1,
+        v = w.max(SYM_VALUE_WIDTH) + 1,
+    );
+    let value = |value: u64| format!("{:<SYM_VALUE_WIDTH$}", format!("{:0w$x}", value));
+    for rel in &section.relocations {
+        let r_type = r_type::r_type_to_string(e_machine, rel.r_type);
+        let prefix = format!(
+            "  {:0w$x} {}",
+            rel.r_offset,
+            widths.r_info(rel.r_sym, rel.r_type)
         );
+        match (rel.symbol, rel.r_addend) {
+            // RELA shows the addend with every symbol, even a zero one
+            (Some(sym), Some(r_addend)) => println!(
+                "{} {:<18} {} {} {} {:x}",
+                prefix,
+                r_type,
+                value(sym.value),
+                sym.name,
+                if r_addend < 0 { '-' } else { '+' },
+                r_addend.unsigned_abs(),
+            ),
+            (Some(sym), None) => println!(
+                "{} {:<18} {} {}",
+                prefix,
+                r_type,
+                value(sym.value),
+                sym.name
+            ),
+            // Without a symbol, RELA shows just the addend, in the name column
+            (None, Some(r_addend)) => println!(
+                "{} {:<18} {:v$} {}{:x}",
+                prefix,
+                r_type,
+                "",
+                if r_addend < 0 { "-" } else { "" },
+                r_addend.unsigned_abs(),
+                v = w.max(SYM_VALUE_WIDTH),
+            ),
+            (None, None) => println!("{} {}", prefix, r_type),
+        }
     }
-    println!("");
+    println!();
 }
 
-fn parse_reloacation_plt_section(rels: &Vec<Rela>, offset: u64) {
-    println!(
-        "Relocation section '.rela.plt' at offset 0x{:x} contains {} entry:",
-        offset,
-        rels.len()
-    );
-    println!("  Offset           Info         Addend");
-    for rel in rels {
+fn parse_section_symbol_counts(counts: &[SectionSymbolCount]) {
+    println!("Symbols defined in each allocated section (.symtab and .dynsym):");
+    for count in counts {
         println!(
-            "  {:016x} {:04x}{:08x} {:016x}",
-            rel.r_offset, rel.r_sym, rel.r_type, rel.r_addend,
+            "  [{:>2}] {}: {} {}, total size {}",
+            count.index,
+            count.section.name,
+            count.symbols,
+            if count.symbols == 1 {
+                "symbol"
+            } else {
+                "symbols"
+            },
+            count.total_size
         );
     }
-    println!("");
+    println!();
+}
+
+/// Which rows of a symbol table -s and --dyn-syms list, and how.
+#[derive(Clone, Copy)]
+struct SymbolListing {
+    sort: Option<SymbolSort>,
+    symbol_type: Option<SymbolType>,
+    symbol_bind: Option<SymbolBind>,
+    /// Some(true) keeps only defined symbols, Some(false) only undefined ones.
+    defined: Option<bool>,
+    demangle: bool,
+}
+
+impl SymbolListing {
+    fn of(args: &Args) -> Self {
+        SymbolListing {
+            sort: args.sort,
+            symbol_type: args.symbol_type,
+            symbol_bind: args.symbol_bind,
+            defined: match (args.defined_only, args.undefined_only) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            },
+            demangle: args.demangle,
+        }
+    }
+
+    /// Whether the filters let `symbol` through; they combine with AND.
+    fn shows(&self, symbol: &elf::symbol::Symbol) -> bool {
+        self.symbol_type
+            .is_none_or(|symbol_type| symbol.st_symtype() == symbol_type.st_type())
+            && self
+                .symbol_bind
+                .is_none_or(|symbol_bind| symbol.st_bind() == symbol_bind.st_bind())
+            && self
+                .defined
+                .is_none_or(|defined| symbol.is_undefined() != defined)
+    }
 }
 
-fn parse_dynsym_table(dynsyms: &Vec<Symbol>, strtab: &StringTable) {
-    println!("Symbol table '.dynsym' contains {} entries:", dynsyms.len());
-    println!("   Num: Value            Size  Type       Bind       Vis         Ndx    Name");
-    for (i, dynsym) in dynsyms.iter().enumerate() {
+fn parse_symbol_table(symbols: &Symbols, widths: Widths, listing: SymbolListing) {
+    let w = widths.addr;
+    println!(
+        "Symbol table '{}' contains {} entries:",
+        symbols.name,
+        symbols.entries.len()
+    );
+    println!(
+        "   Num: {:<v$}Size  Type       Bind       Vis         Ndx    Name",
+        "Value",
+        v = w + 1
+    );
+    let mut rows: Vec<_> = symbols
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| listing.shows(&entry.symbol))
+        .collect();
+    // Stable sorts, so ties stay in index order; Num still gives the index
+    match listing.sort {
+        Some(SymbolSort::Value) => rows.sort_by_key(|(_, entry)| entry.symbol.st_value),
+        Some(SymbolSort::Size) => {
+            rows.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.symbol.st_size))
+        }
+        Some(SymbolSort::Name) => rows.sort_by_key(|(_, entry)| entry.name),
+        None => (),
+    }
+    for (i, entry) in rows {
+        let symbol = &entry.symbol;
         println!(
-            "   {:<3}: {:016x} {:<5} {:<10} {:<10} {:<11} {:<6} {}",
+            "   {:<3}: {:0w$x} {:<5} {} {:<10} {:<11} {:<6} {}",
             i,
-            dynsym.st_value,
-            dynsym.st_size,
-            to_str::st_symtype_to_string(dynsym.st_symtype()),
-            to_str::st_bind_to_string(dynsym.st_bind()),
-            to_str::st_vis_to_string(dynsym.st_vis()),
-            dynsym.st_shndx,
-            strtab.get(dynsym.st_name as usize).unwrap()
+            symbol.st_value,
+            symbol.st_size,
+            color::paint(
+                color::symbol_type(symbol.st_symtype()),
+                &format!("{:<10}", to_str::st_symtype_to_string(symbol.st_symtype()))
+            ),
+            to_str::st_bind_to_string(symbol.st_bind()),
+            to_str::st_vis_to_string(symbol.st_vis()),
+            entry.xindex.map_or_else(
+                || to_str::st_shndx_to_string(symbol.st_shndx),
+                |xindex| xindex.to_string()
+            ),
+            versioned_name(entry, listing.demangle)
         );
     }
-    println!("");
+    println!();
 }
 
-fn parse_symbol_table(symtabs: &Vec<Symbol>, strtab: &StringTable) {
-    println!("Symbol table '.symtab' contains {} entries:", symtabs.len());
-    println!("   Num: Value            Size  Type       Bind       Vis         Ndx    Name");
-    for (i, symtab) in symtabs.iter().enumerate() {
+fn parse_symbol_matches(
+    name: &str,
+    matches: &[SymbolMatch],
+    sections: &[Section],
+    widths: Widths,
+    demangle: bool,
+) {
+    let w = widths.addr;
+    println!(
+        "Symbol '{}' has {} matching {}:",
+        name,
+        matches.len(),
+        if matches.len() == 1 {
+            "entry"
+        } else {
+            "entries"
+        }
+    );
+    println!(
+        "  Table      Num: {:<v$}Size  Type       Bind       Section",
+        "Value",
+        v = w + 1
+    );
+    for SymbolMatch {
+        table,
+        index,
+        entry,
+    } in matches
+    {
+        let symbol = &entry.symbol;
+        let section = match entry.section_index() {
+            Some(index) => sections
+                .get(index)
+                .map_or_else(|| index.to_string(), |section| section.name.to_string()),
+            None => to_str::st_shndx_to_string(symbol.st_shndx),
+        };
         println!(
-            "   {:<3}: {:016x} {:<5} {:<10} {:<10} {:<11} {:<6} {}",
-            i,
-            symtab.st_value,
-            symtab.st_size,
-            to_str::st_symtype_to_string(symtab.st_symtype()),
-            to_str::st_bind_to_string(symtab.st_bind()),
-            to_str::st_vis_to_string(symtab.st_vis()),
-            symtab.st_shndx,
-            strtab.get(symtab.st_name as usize).unwrap(),
+            "  {:<8} {:>6}: {:0w$x} {:<5} {:<10} {:<10} {}",
+            table,
+            index,
+            symbol.st_value,
+            symbol.st_size,
+            to_str::st_symtype_to_string(symbol.st_symtype()),
+            to_str::st_bind_to_string(symbol.st_bind()),
+            section
         );
+        if demangle {
+            if let Some(demangled) = demangle::demangle(entry.name) {
+                println!("           {demangled}");
+            }
+        }
+    }
+    println!();
+}
+
+fn parse_address_lookup(address: u64, lookup: &AddressLookup, demangle: bool) {
+    match lookup {
+        AddressLookup::Symbol { entry, section } => {
+            let name = match demangle {
+                true => demangle::demangle(entry.name),
+                false => None,
+            };
+            print!(
+                "0x{:x} is {}+0x{:x}",
+                address,
+                name.as_deref().unwrap_or(entry.name),
+                address - entry.symbol.st_value
+            );
+            match section {
+                Some(section) => println!(" in section {}", section.name),
+                None => println!(),
+            }
+        }
+        AddressLookup::Section(section) => println!(
+            "0x{:x} is in no symbol, at {}+0x{:x}",
+            address,
+            section.name,
+            address - section.shdr.sh_addr
+        ),
+        AddressLookup::Nowhere => println!("0x{:x} is in no symbol or section", address),
     }
-    println!("");
+    println!();
 }
 
-fn parse_gnu_hash(gnu_hash_header: &GnuHashHeader, gnu_hash_section: &SectionHeader, gnu_hash_table: &[u8], strtab: &StringTable, symtabs: &Vec<Symbol>) {
-    todo!("parse_gnu_hash");
+/// "Entry point: 0x1040 <_start>", with an offset when e_entry isn't at the
+/// start of its symbol. A shared object or PIE is loaded at an address of
+/// the loader's choosing, so there e_entry is only relative to that.
+fn parse_entry_point(address: u64, lookup: &AddressLookup, relative: bool, demangle: bool) {
+    let location = match lookup {
+        AddressLookup::Symbol { entry, .. } => {
+            let name = match demangle {
+                true => demangle::demangle(entry.name),
+                false => None,
+            };
+            let name = name.as_deref().unwrap_or(entry.name);
+            match address - entry.symbol.st_value {
+                0 => format!("<{}>", name),
+                offset => format!("<{}+0x{:x}>", name, offset),
+            }
+        }
+        AddressLookup::Section(section) => match address - section.shdr.sh_addr {
+            0 => format!("<{}>, in no symbol", section.name),
+            offset => format!("<{}+0x{:x}>, in no symbol", section.name, offset),
+        },
+        AddressLookup::Nowhere => "in no symbol or section".to_string(),
+    };
+    println!("Entry point: 0x{:x} {}", address, location);
+    if relative {
+        println!("  (position-independent: relative to the load address)");
+    }
+    println!();
 }
 
-fn parse_gnu_version(gnu_version: &SectionHeader) {
-    todo!("parse_gnu_version");
+/// A section or symbol name, except that a [`CORRUPT_NAME`] one shows the
+/// string table offset that didn't resolve, as readelf's "<corrupt: 0x..>".
+fn shown_name(name: &str, offset: u32) -> Cow<'_, str> {
+    match name == CORRUPT_NAME {
+        true => Cow::Owned(format!("<corrupt: {:#x}>", offset)),
+        false => Cow::Borrowed(name),
+    }
 }
 
-fn parse_gnu_version_r(gnu_version_r: &SectionHeader) {
-    todo!("parse_gnu_version_r");
+/// The name with readelf's version suffix: "@VER (n)" for a version needed
+/// from another object, "@@VER" for the default one defined here and "@VER"
+/// for a hidden one.
+fn versioned_name(entry: &NamedSymbol, demangle: bool) -> String {
+    let name = match demangle {
+        true => demangle::demangle(entry.name).unwrap_or_else(|| entry.name.to_string()),
+        false => shown_name(entry.name, entry.symbol.st_name).into_owned(),
+    };
+    let Some(VersionIndex {
+        index,
+        hidden,
+        name: Some(version),
+        needed,
+    }) = entry.version
+    else {
+        return name;
+    };
+    // The linker's marker symbols for defined versions are named after them
+    if version == entry.name {
+        name
+    } else if needed {
+        format!("{}@{} ({})", name, version, index)
+    } else if hidden {
+        format!("{}@{}", name, version)
+    } else {
+        format!("{}@@{}", name, version)
+    }
 }
 
-fn parse_note_gnu_property(note_gnu_property: &SectionHeader) {
-    todo!("parse_note_gnu_property");
+fn parse_histogram(histogram: &HashHistogram) {
+    let lengths = &histogram.chain_lengths;
+    let nbucket = lengths.len();
+    // readelf only names the section for .gnu.hash
+    let table = match histogram.section.shdr.sh_type {
+        abi::SHT_GNU_HASH => format!("`{}' ", histogram.section.name),
+        _ => String::new(),
+    };
+    println!(
+        "Histogram for {}bucket list length (total of {} {}):",
+        table,
+        nbucket,
+        if nbucket == 1 { "bucket" } else { "buckets" }
+    );
+    let max = lengths.iter().copied().max().unwrap_or_default() as usize;
+    let mut counts = vec![0usize; max + 1];
+    for &length in lengths {
+        counts[length as usize] += 1;
+    }
+    let nsyms: usize = lengths.iter().map(|&length| length as usize).sum();
+    let percent = |n: usize, total: usize| n as f64 * 100.0 / total as f64;
+    println!(" Length  Number     % of total  Coverage");
+    println!(
+        "      0  {:<10} ({:5.1}%)",
+        counts[0],
+        percent(counts[0], nbucket)
+    );
+    let mut covered = 0;
+    for (length, &count) in counts.iter().enumerate().skip(1) {
+        covered += length * count;
+        println!(
+            "{:7}  {:<10} ({:5.1}%)    {:5.1}%",
+            length,
+            count,
+            percent(count, nbucket),
+            percent(covered, nsyms)
+        );
+    }
+    println!();
 }
 
-fn parse_note_gnu_build_id(note_gnu_build_id: &SectionHeader) {
-    todo!("parse_note_gnu_build_id");
+fn parse_histogram_summary(histogram: &HashHistogram) {
+    let lengths = &histogram.chain_lengths;
+    let nsyms: u32 = lengths.iter().sum();
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    println!(
+        "Hash table `{}': {} bucket{}, {} symbol{}, longest chain {} (-I for the histogram)",
+        histogram.section.name,
+        lengths.len(),
+        plural(lengths.len()),
+        nsyms,
+        plural(nsyms as usize),
+        lengths.iter().max().unwrap_or(&0)
+    );
+    println!();
 }
 
-fn parse_note_gnu_abi_tag(note_gnu_abi_tag: &SectionHeader) {
-    todo!("parse_note_gnu_abi_tag");
+/// readelf prints entry offsets with C's "%#06x", which drops the 0x for zero.
+fn c_alt_hex(offset: usize) -> String {
+    match offset {
+        0 => "000000".to_string(),
+        _ => format!("{:#06x}", offset),
+    }
 }
 
-fn main() {
-    let args = Args::parse();
-    let file_data = std::fs::read(args.file).expect("Could not read file.");
-    let slice = file_data.as_slice();
-    let file = ElfBytes::<AnyEndian>::minimal_parse(slice).unwrap();
-    let ident = slice.get(0..16).unwrap();
-    let phdr: Vec<ProgramHeader> = file.segments().unwrap().iter().collect();
-    // Get the section header table alongside its string table
-    let (shdrs_opt, strtab_opt) = file
-        .section_headers_with_strtab()
-        .expect("shdrs offsets should be valid");
-    let (shdrs, strtab) = (
-        shdrs_opt.expect("Should have shdrs"),
-        strtab_opt.expect("Should have strtab"),
+fn version_section_banner(kind: &str, section: &Section, count: usize, link: &str) {
+    println!(
+        "{} section '{}' contains {} {}:",
+        kind,
+        section.name,
+        count,
+        if count == 1 { "entry" } else { "entries" }
     );
-    let shdr = shdrs.iter().collect();
-    let mut dynamic: Vec<dynamic::Dyn> = file
-        .dynamic()
-        .unwrap()
-        .expect("Should have dynamic section")
-        .iter()
-        .collect();
-    dynamic.truncate(dynamic.len() - 4);
+    println!(
+        " Addr: 0x{:016x}  Offset: 0x{:08x}  Link: {} ({})",
+        section.shdr.sh_addr, section.shdr.sh_offset, section.shdr.sh_link, link
+    );
+}
 
-    let dynamic_offset = shdrs
-        .iter()
-        .find(|shdr| shdr.sh_type == abi::SHT_DYNAMIC)
-        .unwrap()
-        .sh_offset;
+fn parse_gnu_version(section: &Section, link: &str, versions: &[VersionIndex]) {
+    version_section_banner("Version symbols", section, versions.len(), link);
+    for (row, chunk) in versions.chunks(4).enumerate() {
+        let mut line = format!("  {:03x}:", row * 4);
+        for version in chunk {
+            let name = match version.index {
+                abi::VER_NDX_LOCAL => "*local*",
+                abi::VER_NDX_GLOBAL => "*global*",
+                _ => version.name.unwrap_or("???"),
+            };
+            let hidden = if version.hidden { 'h' } else { ' ' };
+            let cell = format!("{:4x}{}({})", version.index, hidden, name);
+            line.push_str(&format!("{:<18}", cell));
+        }
+        println!("{}", line.trim_end());
+    }
+    println!();
+}
 
-    let rel_section = shdrs
-        .iter()
-        .filter(|shdr| shdr.sh_type == abi::SHT_RELA)
-        .collect::<Vec<_>>();
-    let rel = rel_section
-        .iter()
-        .map(|shdr| {
-            let rels = file
-                .section_data_as_relas(shdr)
-                .expect("Should have relocations");
-            rels.collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    let rel_offset = shdrs
-        .iter()
-        .filter(|shdr| shdr.sh_type == abi::SHT_RELA)
-        .collect::<Vec<_>>();
-    let common_data = file.find_common_data().unwrap();
-    let symtab = common_data.symtab.unwrap();
-    let symtab_strs = common_data.symtab_strs.unwrap();
-    let dynsyms = common_data.dynsyms.unwrap();
-    let dynsyms_strs = common_data.dynsyms_strs.unwrap();
-    let gnu_hash_header = common_data.gnu_hash.unwrap();
-    let gnu_hash_section = file.section_header_by_name(".gnu.hash").unwrap().unwrap();
-    let (gnu_hash_table, _) = file.section_data(&gnu_hash_section).unwrap();
-
-    parse_elf_header(file.ehdr, ident);
-    parse_section_headers(&shdr, &strtab);
-    parse_program_headers(&phdr);
-    section_to_segment_mapping(&shdr, &phdr, &strtab);
-    parse_dynamic_section(&dynamic, dynamic_offset);
-    parse_reloacation_dynamic_section(&rel[0], rel_offset[0].sh_offset);
-    parse_reloacation_plt_section(&rel[1], rel_offset[1].sh_offset);
-    parse_dynsym_table(&dynsyms.iter().collect(), &dynsyms_strs);
-    parse_symbol_table(&symtab.iter().collect(), &symtab_strs);
-    parse_gnu_hash(&gnu_hash_header.hdr, &gnu_hash_section, &gnu_hash_table, &symtab_strs, &symtab.iter().collect());
-    parse_gnu_version(&file.section_header_by_name(".gnu.version").unwrap().unwrap());
-    parse_gnu_version_r(&file.section_header_by_name(".gnu.version_r").unwrap().unwrap());
-    parse_note_gnu_property(&file.section_header_by_name(".note.gnu.property").unwrap().unwrap());
-    parse_note_gnu_build_id(&file.section_header_by_name(".note.gnu.build-id").unwrap().unwrap());
-    parse_note_gnu_abi_tag(&file.section_header_by_name(".note.gnu.abi-tag").unwrap().unwrap());
+fn parse_gnu_version_r(section: &Section, link: &str, needs: &[symver::VerNeed]) {
+    version_section_banner("Version needs", section, needs.len(), link);
+    for need in needs {
+        println!(
+            "  {}: Version: {}  File: {}  Cnt: {}",
+            c_alt_hex(need.offset),
+            need.vn_version,
+            need.file,
+            need.aux.len()
+        );
+        for aux in &need.aux {
+            println!(
+                "  {}:   Name: {}  Flags: {}  Version: {}",
+                c_alt_hex(aux.offset),
+                aux.name,
+                to_str::ver_flags_to_string(aux.vna_flags),
+                aux.vna_other
+            );
+        }
+    }
+    println!();
+}
+
+fn parse_gnu_version_d(section: &Section, link: &str, defs: &[symver::VerDef]) {
+    version_section_banner("Version definition", section, defs.len(), link);
+    for def in defs {
+        println!(
+            "  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  Name: {}",
+            c_alt_hex(def.offset),
+            def.vd_version,
+            to_str::ver_flags_to_string(def.vd_flags),
+            def.vd_ndx,
+            def.aux.len(),
+            def.aux.first().map_or("", |aux| aux.name)
+        );
+        for (i, aux) in def.aux.iter().enumerate().skip(1) {
+            println!("  {}: Parent {}: {}", c_alt_hex(aux.offset), i, aux.name);
+        }
+    }
+    println!();
+}
+
+fn parse_hex_dump(section: &Section, data: &[u8], relocated: bool) {
+    println!();
+    println!("Hex dump of section '{}':", section.name);
+    if relocated {
+        println!(
+            " NOTE: This section has relocations against it, but these have NOT been applied to this dump."
+        );
+    }
+    if section.shdr.sh_flags & abi::SHF_COMPRESSED as u64 != 0 {
+        println!(" NOTE: This section is compressed; the dump shows its bytes as stored.");
+    }
+    parse_hex_rows(section.shdr.sh_addr, data, 16);
+    println!();
+}
+
+/// Prints segment `index`'s file bytes at the addresses they're loaded at,
+/// padding between its sections included.
+fn parse_segment_dump(index: usize, phdr: &ProgramHeader, data: &[u8], wide: bool) {
+    println!();
+    println!(
+        "Hex dump of segment {} ({}, {}):",
+        index,
+        to_str::p_type_to_string(phdr.p_type),
+        to_str::p_flags_to_string(phdr.p_flags).trim_end()
+    );
+    if phdr.p_memsz > phdr.p_filesz {
+        println!(
+            " NOTE: The last 0x{:x} bytes are zero-filled at load time and aren't shown.",
+            phdr.p_memsz - phdr.p_filesz
+        );
+    }
+    parse_hex_rows(phdr.p_vaddr, data, if wide { 32 } else { 16 });
+    println!();
+}
+
+/// readelf's hex dump rows: the address, `per_row` bytes in groups of four,
+/// then the same bytes as ASCII. Addresses past the top of the address
+/// space wrap around to 0, as in readelf.
+fn parse_hex_rows(address: u64, data: &[u8], per_row: usize) {
+    for (row, chunk) in data.chunks(per_row).enumerate() {
+        let row_address = address.wrapping_add((row * per_row) as u64);
+        let mut line = format!("  0x{:08x} ", row_address);
+        for i in 0..per_row {
+            match chunk.get(i) {
+                Some(byte) => line.push_str(&format!("{:02x}", byte)),
+                None => line.push_str("  "),
+            }
+            if i % 4 == 3 {
+                line.push(' ');
+            }
+        }
+        for &byte in chunk {
+            line.push(match byte {
+                0x20..=0x7e => byte as char,
+                _ => '.',
+            });
+        }
+        println!("{}", line);
+    }
+}
+
+fn parse_string_dump(section: &Section, data: &[u8]) {
+    println!();
+    println!("String dump of section '{}':", section.name);
+    let mut offset = 0;
+    while offset < data.len() {
+        // Like readelf, a string only starts at a printable character
+        if !(0x20..=0x7e).contains(&data[offset]) {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        let mut line = String::new();
+        while let Some(&byte) = data.get(offset) {
+            offset += 1;
+            match byte {
+                0 => break,
+                // A newline ends the line; anything after it is a new string
+                b'\n' => {
+                    line.push_str("\\n");
+                    break;
+                }
+                0x20..=0x7e => line.push(byte as char),
+                0x01..=0x1f => {
+                    line.push('^');
+                    line.push((byte + 0x40) as char);
+                }
+                _ => (),
+            }
+        }
+        println!("  [{:6x}]  {}", start, line);
+    }
+    println!();
+}
+
+fn parse_line_program(program: &dwarf::LineProgram, widths: Widths) {
+    let w = widths.addr;
+    println!(
+        "Decoded line number program at offset 0x{:x} of .debug_line:",
+        program.offset
+    );
+    println!("  DWARF Version:               {}", program.version);
+    println!(
+        "  Format:                      {}",
+        if program.dwarf64 {
+            "DWARF64"
+        } else {
+            "DWARF32"
+        }
+    );
+    println!("  Address size (bytes):        {}", program.address_size);
+    println!("  Minimum Instruction Length:  {}", program.min_inst_length);
+    println!(
+        "  Maximum Ops per Instruction: {}",
+        program.max_ops_per_inst
+    );
+    println!(
+        "  Initial value of 'is_stmt':  {}",
+        program.default_is_stmt as u8
+    );
+    println!("  Line Base:                   {}", program.line_base);
+    println!("  Line Range:                  {}", program.line_range);
+    println!("  Opcode Base:                 {}", program.opcode_base);
+    println!();
+
+    // Entries are numbered from 0 in DWARF 5 and from 1 before it
+    let first = if program.version >= 5 { 0 } else { 1 };
+    if program.directories.is_empty() {
+        println!(" The Directory Table is empty.");
+    } else {
+        println!(" The Directory Table:");
+        println!("  Entry Name");
+        for (index, directory) in program.directories.iter().enumerate() {
+            println!("  {:<5} {}", index + first, directory);
+        }
+    }
+    println!();
+    if program.files.is_empty() {
+        println!(" The File Name Table is empty.");
+    } else {
+        println!(" The File Name Table:");
+        println!("  Entry Dir   Name");
+        for (index, file) in program.files.iter().enumerate() {
+            println!("  {:<5} {:<5} {}", index + first, file.directory, file.name);
+        }
+    }
+    println!();
+
+    if program.rows.is_empty() {
+        println!(" No line number rows.");
+    } else {
+        println!(
+            " {:<a$}File  Line    Column  Stmt  End",
+            "Address",
+            a = w + 3
+        );
+        for row in &program.rows {
+            let line = format!(
+                " 0x{:0w$x} {:<5} {:<7} {:<7} {:<5} {}",
+                row.address,
+                row.file,
+                row.line,
+                row.column,
+                if row.is_stmt { "yes" } else { "no" },
+                if row.end_sequence { "end" } else { "" }
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+    println!();
+}
+
+fn parse_compile_unit(unit: &dwarf::CompileUnit) {
+    println!("  Compilation Unit @ offset 0x{:x}:", unit.offset);
+    println!(
+        "   Length:        0x{:x} ({})",
+        unit.length,
+        if unit.dwarf64 { "64-bit" } else { "32-bit" }
+    );
+    println!("   Version:       {}", unit.version);
+    if let Some(unit_type) = unit.unit_type {
+        println!(
+            "   Unit Type:     {} ({})",
+            to_str::dw_ut_to_string(unit_type),
+            unit_type
+        );
+    }
+    println!("   Abbrev Offset: 0x{:x}", unit.abbrev_offset);
+    println!("   Pointer Size:  {}", unit.address_size);
+    for (attribute, value) in [
+        ("DW_AT_producer", unit.producer),
+        ("DW_AT_name", unit.name),
+        ("DW_AT_comp_dir", unit.comp_dir),
+    ] {
+        if let Some(value) = value {
+            println!("   {:<15} {}", format!("{attribute}:"), value);
+        }
+    }
+    if let Some(language) = unit.language {
+        println!(
+            "   DW_AT_language: {} ({})",
+            language,
+            to_str::dw_lang_to_string(language)
+        );
+    }
+    println!();
+}
+
+fn parse_unwind_table(table: &UnwindTable, widths: Widths) {
+    let w = widths.addr;
+    let hdr = &table.hdr;
+    println!(
+        "Unwind table section '{}' at offset 0x{:x} contains {} {}:",
+        table.section.name,
+        table.section.shdr.sh_offset,
+        hdr.table.len(),
+        if hdr.table.len() == 1 {
+            "entry"
+        } else {
+            "entries"
+        }
+    );
+    println!("  Version:               {}", hdr.version);
+    for (field, encoding) in [
+        ("eh_frame_ptr", hdr.eh_frame_ptr_enc),
+        ("fde_count", hdr.fde_count_enc),
+        ("table", hdr.table_enc),
+    ] {
+        println!(
+            "  {:<22} 0x{:02x} ({})",
+            format!("{field} encoding:"),
+            encoding,
+            to_str::eh_pe_to_string(encoding)
+        );
+    }
+    println!("  eh_frame_ptr:          0x{:x}", hdr.eh_frame_ptr);
+    println!("  FDE count:             {}", hdr.fde_count);
+    if !hdr.table.is_empty() {
+        println!();
+        println!("  {:<a$}FDE address", "Initial PC", a = w + 3);
+        for (pc, fde) in &hdr.table {
+            println!("  {:0w$x}   {:0w$x}", pc, fde);
+        }
+    }
+    println!();
+}
+
+fn parse_notes(banner: &str, notes: &[Note]) {
+    println!("{}", banner);
+    println!("  Owner                Data size \tDescription");
+    for note in notes {
+        match note {
+            Note::GnuAbiTag(abi_tag) => {
+                println!(
+                    "  {:<20} 0x{:08x}\t{}",
+                    abi::ELF_NOTE_GNU,
+                    16,
+                    to_str::note_type_to_string(abi::ELF_NOTE_GNU, abi::NT_GNU_ABI_TAG)
+                );
+                println!(
+                    "    OS: {}, ABI: {}.{}.{}",
+                    to_str::note_abi_tag_os_to_str(abi_tag.os).unwrap_or("Unknown"),
+                    abi_tag.major,
+                    abi_tag.minor,
+                    abi_tag.subminor
+                );
+            }
+            Note::GnuBuildId(build_id) => {
+                println!(
+                    "  {:<20} 0x{:08x}\t{}",
+                    abi::ELF_NOTE_GNU,
+                    build_id.0.len(),
+                    to_str::note_type_to_string(abi::ELF_NOTE_GNU, abi::NT_GNU_BUILD_ID)
+                );
+                let digest: String = build_id.0.iter().map(|b| format!("{:02x}", b)).collect();
+                println!("    Build ID: {}", digest);
+            }
+            Note::Unknown(any) => {
+                println!(
+                    "  {:<20} 0x{:08x}\t{}",
+                    any.name,
+                    any.desc.len(),
+                    to_str::note_type_to_string(any.name, any.n_type)
+                );
+                if any.name == abi::ELF_NOTE_GNU && any.n_type == abi::NT_GNU_GOLD_VERSION {
+                    let version = any.desc.split(|&b| b == 0).next().unwrap_or_default();
+                    println!("    Version: {}", String::from_utf8_lossy(version));
+                } else if matches!(any.name, "CORE" | "LINUX") {
+                    // Register dumps and the like, too big to be worth a hex dump
+                } else if !any.desc.is_empty() {
+                    let bytes: Vec<String> =
+                        any.desc.iter().map(|b| format!("{:02x}", b)).collect();
+                    println!("   description data: {} ", bytes.join(" "));
+                }
+            }
+        }
+    }
+    println!();
+}
+
+fn parse_archive_index(path: &Path, archive: &archive::Archive) {
+    let plural = |n: usize, one: &'static str, many: &'static str| if n == 1 { one } else { many };
+    println!(
+        "Index of archive {}: ({} {}, 0x{:x} bytes in the symbol table)",
+        path.display(),
+        archive.index.len(),
+        plural(archive.index.len(), "entry", "entries"),
+        archive.index_names_size
+    );
+    // The index lists each member's symbols together, in member order
+    let mut index = archive.index.iter().peekable();
+    while let Some(&&(_, offset)) = index.peek() {
+        match archive.member_at(offset) {
+            Some(member) => println!(
+                "Contents of binary {}({}) at offset 0x{:x}",
+                path.display(),
+                member.name,
+                offset
+            ),
+            None => println!("Contents of binary at unknown offset 0x{:x}", offset),
+        }
+        while let Some((name, _)) = index.next_if(|&&(_, next)| next == offset) {
+            println!("\t{}", name);
+        }
+    }
+    println!();
+}
+
+fn parse_arch_attributes(
+    subsections: &[attributes::Subsection],
+    tag_name: fn(u64) -> Option<&'static str>,
+    value_name: fn(u64, u64) -> String,
+) {
+    for subsection in subsections {
+        println!("Attribute Section: {}", subsection.vendor);
+        for group in &subsection.groups {
+            match group.scope {
+                attributes::TAG_FILE => println!("File Attributes"),
+                attributes::TAG_SECTION => print!("Section Attributes:"),
+                attributes::TAG_SYMBOL => print!("Symbol Attributes:"),
+                scope => print!("Unknown tag {}:", scope),
+            }
+            if group.scope != attributes::TAG_FILE {
+                for index in &group.indexes {
+                    print!(" {}", index);
+                }
+                println!();
+            }
+            for attribute in &group.attributes {
+                let tag = match tag_name(attribute.tag) {
+                    Some(name) => name.to_string(),
+                    None => format!("Tag_unknown_{}", attribute.tag),
+                };
+                match attribute.value {
+                    attributes::Value::Int(value) => {
+                        println!("  {}: {}", tag, value_name(attribute.tag, value))
+                    }
+                    attributes::Value::Str(value) => println!("  {}: \"{}\"", tag, value),
+                    attributes::Value::IntStr(flag, vendor) => {
+                        println!("  {}: flag = {}, vendor = {}", tag, flag, vendor)
+                    }
+                }
+            }
+        }
+    }
+    println!();
+}
+
+type Report = fn(&ElfReport, &Args) -> Result<(), ParseError>;
+
+/// The table a report parses, which is what names it in errors and says
+/// whether it applies to a core dump or object file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Table {
+    Summary,
+    ElfHeader,
+    SectionHeaders,
+    SectionGroups,
+    ProgramHeaders,
+    DynamicSection,
+    Relocations,
+    DynamicSymbols,
+    Symbols,
+    AllSymbols,
+    Hash,
+    VersionSections,
+    SectionContents,
+    SegmentContents,
+    Unwind,
+    DebugSections,
+    Notes,
+    ArchSpecific,
+}
+
+impl Table {
+    fn name(self) -> &'static str {
+        match self {
+            Table::Summary => "summary",
+            Table::ElfHeader => "ELF header",
+            Table::SectionHeaders => "section headers",
+            Table::SectionGroups => "section groups",
+            Table::ProgramHeaders => "program headers",
+            Table::DynamicSection => "dynamic section",
+            Table::Relocations => "relocations",
+            Table::DynamicSymbols => "dynamic symbol table",
+            Table::Symbols => "symbol table",
+            Table::AllSymbols => "symbol tables",
+            Table::Hash => "hash table",
+            Table::VersionSections => "version sections",
+            Table::SectionContents => "section contents",
+            Table::SegmentContents => "segment contents",
+            Table::Unwind => "unwind table",
+            Table::DebugSections => "debug sections",
+            Table::Notes => "notes",
+            Table::ArchSpecific => "arch-specific attributes",
+        }
+    }
+}
+
+fn report_file_header(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    parse_elf_header(report.header(), report.ident(), report.header_counts());
+    Ok(())
+}
+
+fn report_section_headers(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    match report.sections()? {
+        // -t is the long form of -S, so it replaces rather than adds to it
+        Some(sections) if args.section_details => parse_section_details(report, &sections)?,
+        Some(sections) => {
+            parse_section_headers(&sections, Widths::of(report), args.wide, args.verbose)
+        }
+        None => {
+            println!("There are no sections in this file.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_section_groups(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    let groups = report.section_groups()?;
+    if groups.is_empty() {
+        println!("There are no section groups in this file.");
+        println!();
+        return Ok(());
+    }
+    parse_section_groups(&groups);
+    Ok(())
+}
+
+fn report_segments(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    match report.segments() {
+        Some(phdrs) => {
+            parse_program_headers(report, &phdrs, args.wide, args.verbose);
+            if let Some(stack) = report.executable_stack() {
+                let e_machine = report.header().e_machine;
+                println!("Warning: {}", executable_stack_warning(stack, e_machine));
+                println!();
+            }
+            let mapping = report.section_to_segment_mapping()?;
+            if let Some(mapping) = &mapping {
+                section_to_segment_mapping(mapping);
+            }
+            for (index, phdr) in phdrs.iter().enumerate() {
+                if phdr.p_type == abi::PT_TLS {
+                    let sections = mapping.as_ref().map(|mapping| mapping[index].as_slice());
+                    parse_tls_segment(index, phdr, sections);
+                }
+            }
+        }
+        None => {
+            println!("There are no program headers in this file.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_dynamic(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    match report.dynamic()? {
+        Some(dynamic) => {
+            let strings = report.dynamic_string_table(&dynamic)?;
+            parse_dynamic_section(&dynamic, strings.as_ref(), Widths::of(report))
+        }
+        None => {
+            println!("There is no dynamic section in this file.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_init_fini(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    match report.init_fini()? {
+        Some(init_fini) => parse_init_fini(&init_fini, Widths::of(report), args.demangle),
+        None => {
+            println!("There is no dynamic section in this file.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_needed(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    match report.libraries()? {
+        Some(libraries) => parse_libraries(&libraries),
+        None => {
+            println!("There is no dynamic section in this file.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_relocs(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let sections = if args.use_dynamic {
+        report.relocations_from_dynamic()?
+    } else {
+        report.relocations()?
+    };
+    if sections.is_empty() {
+        // Static binaries and objects have no PT_DYNAMIC for -D to go on
+        if args.use_dynamic {
+            println!("There are no dynamic relocations in this file.");
+        } else {
+            println!("There are no relocations in this file.");
+        }
+        println!();
+    }
+    let e_machine = report.header().e_machine;
+    for section in &sections {
+        parse_relocation_section(section, e_machine, Widths::of(report));
+    }
+    Ok(())
+}
+
+fn report_dyn_syms(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let symbols = if args.use_dynamic {
+        report.dynamic_symbols_from_dynamic()?
+    } else {
+        report.dynamic_symbols()?
+    };
+    match symbols {
+        Some(symbols) => parse_symbol_table(&symbols, Widths::of(report), SymbolListing::of(args)),
+        // -a quietly skips the table, like readelf, only --dyn-syms alone
+        // (or -s under -D) asks for it
+        None if !args.syms || args.use_dynamic => {
+            println!("Dynamic symbol information is not available for displaying symbols.");
+            println!();
+        }
+        None => (),
+    }
+    Ok(())
+}
+
+fn report_syms(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    // Only the section headers lead to .symtab, so like readelf -D -s, -s
+    // under -D lists the dynamic symbols instead
+    if args.use_dynamic {
+        if !args.dyn_syms {
+            report_dyn_syms(report, args)?;
+        }
+        return Ok(());
+    }
+    match report.symbols()? {
+        Some(symbols) => parse_symbol_table(&symbols, Widths::of(report), SymbolListing::of(args)),
+        // Stripped binaries keep .dynsym, and when that's just been shown
+        // the missing .symtab goes unmentioned, as under readelf -a
+        None if args.dyn_syms && report.dynamic_symbols()?.is_some() => (),
+        None => {
+            println!("No symbol table found.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_symbol(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let Some(name) = &args.symbol else {
+        return Ok(());
+    };
+    let matches = report.find_symbols(name)?;
+    let sections = report.sections()?.unwrap_or_default();
+    parse_symbol_matches(name, &matches, &sections, Widths::of(report), args.demangle);
+    Ok(())
+}
+
+fn report_addr(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let Some(address) = args.addr else {
+        return Ok(());
+    };
+    parse_address_lookup(address, &report.lookup_address(address)?, args.demangle);
+    Ok(())
+}
+
+fn report_entry_point(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let ehdr = report.header();
+    if ehdr.e_entry == 0 {
+        println!("There is no entry point in this file.");
+        println!();
+        return Ok(());
+    }
+    parse_entry_point(
+        ehdr.e_entry,
+        &report.lookup_address(ehdr.e_entry)?,
+        ehdr.e_type == abi::ET_DYN,
+        args.demangle,
+    );
+    Ok(())
+}
+
+fn report_section_symbol_counts(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    let counts = report.section_symbol_counts()?;
+    if counts.is_empty() {
+        println!("There are no allocated sections in this file.");
+        println!();
+        return Ok(());
+    }
+    parse_section_symbol_counts(&counts);
+    Ok(())
+}
+
+fn report_histogram(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let histograms = report.hash_histograms()?;
+    // Only an explicit -I explains what's missing; -a stays quiet like readelf
+    let has_gnu_hash = histograms
+        .iter()
+        .any(|histogram| histogram.section.shdr.sh_type == abi::SHT_GNU_HASH);
+    if args.histogram && !has_gnu_hash {
+        if histograms.is_empty() {
+            println!("There are no hash tables in this file.");
+        } else {
+            println!("There is no .gnu.hash section in this file, only a SysV .hash.");
+        }
+        println!();
+    }
+    for histogram in histograms {
+        if !args.histogram {
+            parse_histogram_summary(&histogram);
+        } else if !histogram.chain_lengths.is_empty() {
+            // A table without buckets has nothing to chart
+            parse_histogram(&histogram);
+        }
+    }
+    Ok(())
+}
+
+fn report_version_info(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    let version_sections = report.version_info()?;
+    if version_sections.is_empty() {
+        println!("No version information found in this file.");
+        println!();
+    }
+    for version_section in &version_sections {
+        match version_section {
+            VersionSection::Symbols {
+                section,
+                link,
+                versions,
+            } => parse_gnu_version(section, link, versions),
+            VersionSection::Needs {
+                section,
+                link,
+                needs,
+            } => parse_gnu_version_r(section, link, needs),
+            VersionSection::Definitions {
+                section,
+                link,
+                defs,
+            } => parse_gnu_version_d(section, link, defs),
+        }
+    }
+    Ok(())
+}
+
+fn report_section_contents(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let sections = report.sections()?.unwrap_or_default();
+    for dump in &args.dumps {
+        let Some((index, section)) = report.find_section(dump.selector())? else {
+            continue;
+        };
+        // Only -x gets this far with one; run() refuses it for -p
+        if section.shdr.sh_type == abi::SHT_NOBITS {
+            println!("Section '{}' has no data to dump.", section.name);
+            continue;
+        }
+        let data = report.section_data(&section)?;
+        match dump {
+            Dump::Hex(_) => {
+                let relocated = sections.iter().any(|rel| {
+                    matches!(rel.shdr.sh_type, abi::SHT_REL | abi::SHT_RELA)
+                        && rel.shdr.sh_info as usize == index
+                });
+                parse_hex_dump(&section, data, relocated);
+            }
+            Dump::Strings(_) => parse_string_dump(&section, data),
+        }
+    }
+    Ok(())
+}
+
+fn report_segment_contents(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    let Some(phdrs) = report.segments() else {
+        println!("There are no program headers in this file.");
+        println!();
+        return Ok(());
+    };
+    for (index, phdr) in phdrs.iter().enumerate() {
+        if phdr.p_type == abi::PT_LOAD {
+            parse_segment_dump(index, phdr, report.segment_data(phdr)?, args.wide);
+        }
+    }
+    Ok(())
+}
+
+fn report_unwind(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    match report.unwind_table()? {
+        Some(table) => parse_unwind_table(&table, Widths::of(report)),
+        None => {
+            println!("There is no .eh_frame_hdr section in this file.");
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn report_debug_dump(report: &ElfReport, args: &Args) -> Result<(), ParseError> {
+    for dump in &args.debug_dump {
+        match dump {
+            DebugDump::Line => {
+                for program in report.line_programs()? {
+                    parse_line_program(&program, Widths::of(report));
+                }
+            }
+            DebugDump::Info => {
+                println!("Contents of the .debug_info section:");
+                println!();
+                for (offset, unit) in report.compile_units()? {
+                    match unit {
+                        Ok(unit) => parse_compile_unit(&unit),
+                        Err(err) => eprintln!(
+                            "hrelf: Warning: skipping the unit at offset 0x{:x} of .debug_info: {}",
+                            offset, err
+                        ),
+                    }
+                }
+            }
+            DebugDump::Str => {
+                for name in [".debug_str", ".debug_line_str"] {
+                    match report.find_section(name)? {
+                        Some((_, section))
+                            if section.shdr.sh_size == 0
+                                || section.shdr.sh_type == abi::SHT_NOBITS =>
+                        {
+                            println!("Section '{}' has no data to dump.", name);
+                        }
+                        Some((_, section)) => {
+                            parse_string_dump(&section, report.section_data(&section)?)
+                        }
+                        // .debug_line_str only exists from DWARF 5 on
+                        None if name == ".debug_str" => {
+                            println!("There is no .debug_str section in this file.");
+                            println!();
+                        }
+                        None => (),
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn report_notes(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    let groups = report.notes()?;
+    if groups.is_empty() {
+        println!("There are no notes in this file.");
+        println!();
+    }
+    for group in &groups {
+        let banner = match &group.source {
+            NoteSource::Section(name) => format!("Displaying notes found in: {}", name),
+            NoteSource::Segment(phdr) => format!(
+                "Displaying notes found at file offset 0x{:08x} with length 0x{:08x}:",
+                phdr.p_offset, phdr.p_filesz
+            ),
+        };
+        parse_notes(&banner, &group.notes);
+        // readelf goes on to the next group too
+        if let Some(offset) = group.corrupt {
+            eprintln!(
+                "hrelf: Warning: corrupt note found at offset 0x{:x}",
+                offset
+            );
+        }
+    }
+    Ok(())
+}
+
+fn report_arch_specific(report: &ElfReport, _args: &Args) -> Result<(), ParseError> {
+    let subsections = report.arch_attributes()?;
+    if subsections.is_empty() {
+        println!("There are no arch-specific attributes in this file.");
+        println!();
+        return Ok(());
+    }
+    match report.header().e_machine {
+        abi::EM_RISCV => parse_arch_attributes(
+            &subsections,
+            to_str::riscv_attr_tag_to_str,
+            to_str::riscv_attr_value_to_string,
+        ),
+        _ => parse_arch_attributes(
+            &subsections,
+            to_str::arm_attr_tag_to_str,
+            to_str::arm_attr_value_to_string,
+        ),
+    }
+    Ok(())
+}
+
+type JsonReport = fn(&ElfReport) -> Result<json::Value, ParseError>;
+
+fn summary_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    parse_summary(report).map(json::Value::from)
+}
+
+fn header_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let ehdr = report.header();
+    Ok(json::Value::Object(vec![
+        ("class", format!("{:?}", ehdr.class).into()),
+        ("endianness", format!("{:?}", ehdr.endianness).into()),
+        ("version", ehdr.version.into()),
+        (
+            "osabi",
+            json::Value::named(ehdr.osabi, to_str::e_osabi_to_str(ehdr.osabi)),
+        ),
+        ("abiversion", ehdr.abiversion.into()),
+        (
+            "e_type",
+            json::Value::named(ehdr.e_type, to_str::e_type_to_str(ehdr.e_type)),
+        ),
+        (
+            "e_machine",
+            json::Value::named(ehdr.e_machine, to_str::e_machine_to_str(ehdr.e_machine)),
+        ),
+        ("e_entry", ehdr.e_entry.into()),
+        ("e_phoff", ehdr.e_phoff.into()),
+        ("e_shoff", ehdr.e_shoff.into()),
+        ("e_flags", ehdr.e_flags.into()),
+        ("e_ehsize", ehdr.e_ehsize.into()),
+        ("e_phentsize", ehdr.e_phentsize.into()),
+        ("e_phnum", ehdr.e_phnum.into()),
+        ("e_shentsize", ehdr.e_shentsize.into()),
+        ("e_shnum", ehdr.e_shnum.into()),
+        ("e_shstrndx", ehdr.e_shstrndx.into()),
+    ]))
+}
+
+fn sections_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let sections: Vec<json::Value> = report
+        .sections()?
+        .unwrap_or_default()
+        .iter()
+        .map(|Section { name, shdr }| {
+            json::Value::Object(vec![
+                ("name", (*name).into()),
+                ("sh_name", shdr.sh_name.into()),
+                (
+                    "sh_type",
+                    json::Value::named(shdr.sh_type, to_str::sh_type_to_str(shdr.sh_type)),
+                ),
+                ("sh_flags", shdr.sh_flags.into()),
+                ("sh_addr", shdr.sh_addr.into()),
+                ("sh_offset", shdr.sh_offset.into()),
+                ("sh_size", shdr.sh_size.into()),
+                ("sh_link", shdr.sh_link.into()),
+                ("sh_info", shdr.sh_info.into()),
+                ("sh_addralign", shdr.sh_addralign.into()),
+                ("sh_entsize", shdr.sh_entsize.into()),
+            ])
+        })
+        .collect();
+    Ok(sections.into())
+}
+
+fn segments_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let segments: Vec<json::Value> = report
+        .segments()
+        .unwrap_or_default()
+        .iter()
+        .map(|phdr| {
+            json::Value::Object(vec![
+                (
+                    "p_type",
+                    json::Value::named(phdr.p_type, to_str::p_type_to_str(phdr.p_type)),
+                ),
+                ("p_flags", phdr.p_flags.into()),
+                ("p_offset", phdr.p_offset.into()),
+                ("p_vaddr", phdr.p_vaddr.into()),
+                ("p_paddr", phdr.p_paddr.into()),
+                ("p_filesz", phdr.p_filesz.into()),
+                ("p_memsz", phdr.p_memsz.into()),
+                ("p_align", phdr.p_align.into()),
+            ])
+        })
+        .collect();
+    Ok(segments.into())
+}
+
+fn dynamic_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let Some(dynamic) = report.dynamic()? else {
+        return Ok(json::Value::Null);
+    };
+    let entries: Vec<json::Value> = dynamic
+        .entries
+        .iter()
+        .map(|entry| {
+            json::Value::Object(vec![
+                (
+                    "d_tag",
+                    json::Value::named(entry.d_tag, to_str::d_tag_to_str(entry.d_tag)),
+                ),
+                ("d_val", entry.clone().d_val().into()),
+            ])
+        })
+        .collect();
+    Ok(entries.into())
+}
+
+fn relocations_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let e_machine = report.header().e_machine;
+    let sections: Vec<json::Value> = report
+        .relocations()?
+        .iter()
+        .map(|section| {
+            let entries: Vec<json::Value> = section
+                .relocations
+                .iter()
+                .map(|rel| {
+                    json::Value::Object(vec![
+                        ("r_offset", rel.r_offset.into()),
+                        ("r_sym", rel.r_sym.into()),
+                        (
+                            "r_type",
+                            json::Value::named(
+                                rel.r_type,
+                                r_type::r_type_to_str(e_machine, rel.r_type),
+                            ),
+                        ),
+                        ("r_addend", rel.r_addend.into()),
+                    ])
+                })
+                .collect();
+            json::Value::Object(vec![
+                ("section", section.name.into()),
+                ("sh_offset", section.shdr.sh_offset.into()),
+                ("entries", entries.into()),
+            ])
+        })
+        .collect();
+    Ok(sections.into())
+}
+
+fn symbols_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let table = |symbols: Option<Symbols>| {
+        let entries: Vec<json::Value> = symbols?
+            .entries
+            .iter()
+            .map(|entry| {
+                let symbol = &entry.symbol;
+                json::Value::Object(vec![
+                    ("name", entry.name.into()),
+                    ("st_name", symbol.st_name.into()),
+                    ("st_value", symbol.st_value.into()),
+                    ("st_size", symbol.st_size.into()),
+                    (
+                        "st_symtype",
+                        json::Value::named(
+                            symbol.st_symtype(),
+                            to_str::st_symtype_to_str(symbol.st_symtype()),
+                        ),
+                    ),
+                    (
+                        "st_bind",
+                        json::Value::named(
+                            symbol.st_bind(),
+                            to_str::st_bind_to_str(symbol.st_bind()),
+                        ),
+                    ),
+                    (
+                        "st_vis",
+                        json::Value::named(symbol.st_vis(), to_str::st_vis_to_str(symbol.st_vis())),
+                    ),
+                    ("st_shndx", symbol.st_shndx.into()),
+                    (
+                        "version",
+                        entry.version.and_then(|version| version.name).into(),
+                    ),
+                ])
+            })
+            .collect();
+        Some(json::Value::from(entries))
+    };
+    Ok(json::Value::Object(vec![
+        ("dynsym", table(report.dynamic_symbols()?).into()),
+        ("symtab", table(report.symbols()?).into()),
+    ]))
+}
+
+fn notes_json(report: &ElfReport) -> Result<json::Value, ParseError> {
+    let note = |note: &Note| {
+        let (owner, n_type, desc_size, decoded) = match note {
+            Note::GnuAbiTag(abi_tag) => (
+                abi::ELF_NOTE_GNU,
+                abi::NT_GNU_ABI_TAG,
+                16,
+                (
+                    "abi_tag",
+                    format!(
+                        "{} {}.{}.{}",
+                        to_str::note_abi_tag_os_to_str(abi_tag.os).unwrap_or("Unknown"),
+                        abi_tag.major,
+                        abi_tag.minor,
+                        abi_tag.subminor
+                    ),
+                ),
+            ),
+            Note::GnuBuildId(build_id) => (
+                abi::ELF_NOTE_GNU,
+                abi::NT_GNU_BUILD_ID,
+                build_id.0.len(),
+                (
+                    "build_id",
+                    build_id.0.iter().map(|b| format!("{:02x}", b)).collect(),
+                ),
+            ),
+            Note::Unknown(any) => (
+                any.name,
+                any.n_type,
+                any.desc.len(),
+                (
+                    "desc",
+                    any.desc.iter().map(|b| format!("{:02x}", b)).collect(),
+                ),
+            ),
+        };
+        json::Value::Object(vec![
+            ("owner", owner.into()),
+            (
+                "n_type",
+                json::Value::named(n_type, to_str::note_type_to_str(owner, n_type)),
+            ),
+            ("desc_size", desc_size.into()),
+            (decoded.0, decoded.1.into()),
+        ])
+    };
+    let groups: Vec<json::Value> = report
+        .notes()?
+        .iter()
+        .map(|group| {
+            let (section, offset) = match &group.source {
+                NoteSource::Section(name) => (Some(*name), None),
+                NoteSource::Segment(phdr) => (None, Some(phdr.p_offset)),
+            };
+            json::Value::Object(vec![
+                ("section", section.into()),
+                ("p_offset", offset.into()),
+                (
+                    "notes",
+                    group.notes.iter().map(note).collect::<Vec<_>>().into(),
+                ),
+            ])
+        })
+        .collect();
+    Ok(groups.into())
+}
+
+/// The input's bytes and the name to report it by. The elf crate parses
+/// from one slice, so stdin is buffered whole.
+/// Files are mapped rather than read with --mmap or past [`mmap::THRESHOLD`].
+fn read_input(args: &Args, file: Option<&Path>) -> Result<(PathBuf, mmap::Bytes), Error> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => {
+            let io_error = |err| Error::Io(path.into(), err);
+            let mut file = std::fs::File::open(path).map_err(io_error)?;
+            let len = file.metadata().map_err(io_error)?.len();
+            let data = if args.mmap || len >= mmap::THRESHOLD {
+                mmap::map_or_read(file)
+            } else {
+                let mut data = Vec::new();
+                file.read_to_end(&mut data).map(|_| mmap::Bytes::Read(data))
+            };
+            Ok((path.into(), data.map_err(io_error)?))
+        }
+        _ => {
+            let path = PathBuf::from("<stdin>");
+            let mut data = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|err| Error::Io(path.clone(), err))?;
+            Ok((path, mmap::Bytes::Read(data)))
+        }
+    }
+}
+
+/// Prints a failure the one way hrelf reports them, "hrelf: <file>: <message>".
+fn report_error(err: &impl fmt::Display) {
+    eprintln!("hrelf: {}", err);
+}
+
+/// The failures of a run: the file not reading or parsing, or the options
+/// asking for something the file doesn't have.
+#[derive(Debug)]
+enum CliError {
+    /// The file couldn't be read or parsed.
+    File(Error),
+    /// A section selector (-x) matched neither an index nor a name; carries
+    /// the names that would have.
+    NoSuchSection(PathBuf, String, Vec<String>),
+    /// A -p selector named an SHT_NOBITS section.
+    NoData(PathBuf, String),
+    /// A section --debug-dump needs isn't in the file.
+    MissingSection(PathBuf, &'static str),
+    /// A section --debug-dump needs is SHF_COMPRESSED, which isn't decoded.
+    CompressedSection(PathBuf, &'static str),
+    /// No symbol table has an entry by the name --symbol asked for.
+    NoSuchSymbol(PathBuf, String),
+}
+
+impl From<Error> for CliError {
+    fn from(err: Error) -> Self {
+        CliError::File(err)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Every message leads with the file, as in "<file>: <message>"
+        match self {
+            CliError::File(err) => err.fmt(f),
+            CliError::NoSuchSection(path, selector, names) => write!(
+                f,
+                "{}: no section '{}'; available sections: {}",
+                path.display(),
+                selector,
+                names.join(", ")
+            ),
+            CliError::NoData(path, name) => write!(
+                f,
+                "{}: section '{}' is SHT_NOBITS and has no data to dump",
+                path.display(),
+                name
+            ),
+            CliError::MissingSection(path, name) => {
+                write!(f, "{}: no {} section", path.display(), name)
+            }
+            CliError::CompressedSection(path, name) => write!(
+                f,
+                "{}: the {} section is compressed, which hrelf can't decode",
+                path.display(),
+                name
+            ),
+            CliError::NoSuchSymbol(path, name) => {
+                write!(f, "{}: no symbol '{}'", path.display(), name)
+            }
+        }
+    }
+}
+
+/// Runs the selected reports on one input. Failures of single reports
+/// are printed as they happen and the rest still run, so Ok(false) means
+/// the output is incomplete; Err means nothing could be shown.
+fn run(args: &Args, file: Option<&Path>) -> Result<bool, CliError> {
+    let (path, file_data) = read_input(args, file)?;
+    if file_data.starts_with(archive::THINMAG) {
+        return Err(Error::ThinArchive(path).into());
+    }
+    if file_data.starts_with(archive::ARMAG) {
+        return run_archive(args, &path, &file_data);
+    }
+    // Checked up front so that text files and the like don't surface as parse errors
+    if !file_data.starts_with(&abi::ELFMAGIC) {
+        return Err(Error::NotElf(path).into());
+    }
+    if args.archive_index {
+        eprintln!(
+            "hrelf: Warning: {}: not an archive, so there's no index to display",
+            path.display()
+        );
+    }
+    if !args.any_report_selected() {
+        return Ok(true);
+    }
+    run_elf(args, &path, &file_data)
+}
+
+/// Prints the differences between two ELF files, and whether they matched.
+fn run_diff(args: &Args, old: &Path, new: &Path) -> Result<bool, Error> {
+    let (old_path, old_data) = read_input(args, Some(old))?;
+    let (new_path, new_data) = read_input(args, Some(new))?;
+    fn parse<'data>(path: &Path, data: &'data [u8]) -> Result<ElfReport<'data>, Error> {
+        if !data.starts_with(&abi::ELFMAGIC) {
+            return Err(Error::NotElf(path.into()));
+        }
+        ElfReport::parse(data).map_err(|err| Error::Header(path.into(), err))
+    }
+    let old_report = parse(&old_path, &old_data)?;
+    let new_report = parse(&new_path, &new_data)?;
+    let lines = diff::diff(&old_report, &new_report)
+        .map_err(|err| Error::Table(new_path.clone(), Table::SectionHeaders.name(), err))?;
+    if !lines.is_empty() {
+        println!("--- {}", old_path.display());
+        println!("+++ {}", new_path.display());
+        for line in &lines {
+            println!("{}", line);
+        }
+    }
+    Ok(lines.is_empty())
+}
+
+/// Runs the selected reports on each object in an archive, after its
+/// symbol index if -c asked for it.
+fn run_archive(args: &Args, path: &Path, data: &[u8]) -> Result<bool, CliError> {
+    let archive = archive::parse_archive(data).map_err(|err| Error::Archive(path.into(), err))?;
+    if args.archive_index {
+        parse_archive_index(path, &archive);
+    }
+    if !args.any_report_selected() {
+        return Ok(true);
+    }
+    let mut complete = true;
+    for member in &archive.members {
+        let path = PathBuf::from(format!("{}({})", path.display(), member.name));
+        if !member.data.starts_with(&abi::ELFMAGIC) {
+            // One stray member shouldn't hide the rest of the library
+            eprintln!("hrelf: Warning: {}", Error::NotElf(path));
+            continue;
+        }
+        if args.format == Format::Human && !args.summary {
+            println!();
+            println!("File: {}", path.display());
+        }
+        // Nor should one that doesn't parse
+        match run_elf(args, &path, member.data) {
+            Ok(member_complete) => complete &= member_complete,
+            Err(err) => {
+                report_error(&err);
+                complete = false;
+            }
+        }
+    }
+    Ok(complete)
+}
+
+/// Runs the selected reports on one ELF image, naming it `path` in errors.
+fn run_elf(args: &Args, path: &Path, file_data: &[u8]) -> Result<bool, CliError> {
+    let report = match ElfReport::parse(file_data) {
+        Ok(report) => report,
+        Err(err) => {
+            // A header table that runs off the end stops the parse, but
+            // --check can still say which one it was
+            if args.check {
+                if let Ok(ehdr) = hrelf::parse_header(file_data) {
+                    let anomalies = hrelf::check_header_tables(&ehdr, file_data);
+                    parse_check(path, &anomalies, ehdr.e_machine);
+                }
+            }
+            return Err(Error::Header(path.into(), err).into());
+        }
+    };
+    let table_error = |table: Table| move |err| Error::Table(path.into(), table.name(), err);
+
+    if args.format == Format::Json {
+        let reports: [(bool, &'static str, Table, JsonReport); 8] = [
+            (args.summary, "summary", Table::Summary, summary_json),
+            (args.file_header, "header", Table::ElfHeader, header_json),
+            (
+                args.section_headers,
+                "sections",
+                Table::SectionHeaders,
+                sections_json,
+            ),
+            (
+                args.segments,
+                "segments",
+                Table::ProgramHeaders,
+                segments_json,
+            ),
+            (args.dynamic, "dynamic", Table::DynamicSection, dynamic_json),
+            (
+                args.relocs,
+                "relocations",
+                Table::Relocations,
+                relocations_json,
+            ),
+            (
+                args.syms || args.dyn_syms,
+                "symbols",
+                Table::AllSymbols,
+                symbols_json,
+            ),
+            (args.notes, "notes", Table::Notes, notes_json),
+        ];
+        // A table that doesn't parse is left out of the document
+        let mut fields = Vec::new();
+        let mut complete = true;
+        for (selected, key, table, build) in reports {
+            if !selected {
+                continue;
+            }
+            match build(&report) {
+                Ok(value) => fields.push((key, value)),
+                Err(err) => {
+                    report_error(&table_error(table)(err));
+                    complete = false;
+                }
+            }
+        }
+        println!("{}", json::Value::Object(fields));
+        return Ok(complete);
+    }
+
+    // Catch mistyped selectors and absent symbols before any output
+    for dump in &args.dumps {
+        let found = report
+            .find_section(dump.selector())
+            .map_err(table_error(Table::SectionHeaders))?;
+        if let (Dump::Strings(_), Some((_, section))) = (dump, found) {
+            // There are no strings to look for in a section without file data
+            if section.shdr.sh_type == abi::SHT_NOBITS {
+                return Err(CliError::NoData(path.into(), section.name.to_string()));
+            }
+        }
+        if found.is_none() {
+            let names = report
+                .sections()
+                .map_err(table_error(Table::SectionHeaders))?
+                .unwrap_or_default()
+                .iter()
+                .map(|section| section.name.to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+            return Err(CliError::NoSuchSection(
+                path.into(),
+                dump.selector().to_string(),
+                names,
+            ));
+        }
+    }
+
+    if let Some(name) = &args.symbol {
+        if report
+            .find_symbols(name)
+            .map_err(table_error(Table::AllSymbols))?
+            .is_empty()
+        {
+            return Err(CliError::NoSuchSymbol(path.into(), name.clone()));
+        }
+    }
+
+    if !args.debug_dump.is_empty() {
+        let needed = args
+            .debug_dump
+            .iter()
+            .flat_map(|dump| dump.sections())
+            .map(|&name| (name, true));
+        let strings = DWARF_STRING_SECTIONS.iter().map(|&name| (name, false));
+        for (name, required) in needed.chain(strings) {
+            let section = report
+                .find_section(name)
+                .map_err(table_error(Table::SectionHeaders))?;
+            match section {
+                None if required => return Err(CliError::MissingSection(path.into(), name)),
+                Some((_, section)) if section.shdr.sh_flags & abi::SHF_COMPRESSED as u64 != 0 => {
+                    return Err(CliError::CompressedSection(path.into(), name))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // The summary names its file itself, to stay one line per file
+    let mut complete = true;
+    if args.summary {
+        match parse_summary(&report) {
+            Ok(summary) => println!("{}: {}", path.display(), summary),
+            Err(err) => {
+                report_error(&table_error(Table::Summary)(err));
+                complete = false;
+            }
+        }
+    }
+
+    if args.check {
+        match report.check() {
+            Ok(anomalies) => {
+                parse_check(path, &anomalies, report.header().e_machine);
+                complete &= anomalies.is_empty();
+            }
+            Err(err) => {
+                report_error(&table_error(Table::SectionHeaders)(err));
+                complete = false;
+            }
+        }
+    }
+
+    // In readelf's canonical order; each report only parses what it prints
+    let reports: [(bool, Table, Report); 22] = [
+        (args.file_header, Table::ElfHeader, report_file_header),
+        (
+            args.section_headers || args.section_details,
+            Table::SectionHeaders,
+            report_section_headers,
+        ),
+        (
+            args.section_groups,
+            Table::SectionGroups,
+            report_section_groups,
+        ),
+        (args.segments, Table::ProgramHeaders, report_segments),
+        (args.dynamic, Table::DynamicSection, report_dynamic),
+        (args.needed, Table::DynamicSection, report_needed),
+        (args.init_fini, Table::DynamicSection, report_init_fini),
+        (args.relocs, Table::Relocations, report_relocs),
+        (args.dyn_syms, Table::DynamicSymbols, report_dyn_syms),
+        (args.syms, Table::Symbols, report_syms),
+        (args.symbol.is_some(), Table::AllSymbols, report_symbol),
+        (args.addr.is_some(), Table::AllSymbols, report_addr),
+        (args.entry_point, Table::AllSymbols, report_entry_point),
+        (
+            args.section_symbol_counts,
+            Table::AllSymbols,
+            report_section_symbol_counts,
+        ),
+        (
+            args.histogram || args.histogram_summary,
+            Table::Hash,
+            report_histogram,
+        ),
+        (
+            args.version_info,
+            Table::VersionSections,
+            report_version_info,
+        ),
+        (
+            !args.dumps.is_empty(),
+            Table::SectionContents,
+            report_section_contents,
+        ),
+        (
+            args.segment_contents,
+            Table::SegmentContents,
+            report_segment_contents,
+        ),
+        (args.unwind, Table::Unwind, report_unwind),
+        (
+            !args.debug_dump.is_empty(),
+            Table::DebugSections,
+            report_debug_dump,
+        ),
+        (args.notes, Table::Notes, report_notes),
+        (
+            args.arch_specific,
+            Table::ArchSpecific,
+            report_arch_specific,
+        ),
+    ];
+    // A core dump has only its program headers and the notes they point to,
+    // and an object file isn't linked yet, so has nothing for the loader.
+    // The hash table report stays: it's quiet under -a, and -I should
+    // still say why there's no histogram
+    let skipped = |table| match report.file().ehdr.e_type {
+        abi::ET_CORE => !matches!(
+            table,
+            Table::ElfHeader | Table::ProgramHeaders | Table::SegmentContents | Table::Notes
+        ),
+        abi::ET_REL => matches!(table, Table::DynamicSection | Table::DynamicSymbols),
+        _ => false,
+    };
+    // One table that doesn't parse doesn't keep the others from printing
+    for (selected, table, print) in reports {
+        if selected && !skipped(table) {
+            if let Err(err) = print(&report, args) {
+                report_error(&table_error(table)(err));
+                complete = false;
+            }
+        }
+    }
+    Ok(complete)
+}
+
+/// A --addr value: hexadecimal, with or without 0x.
+fn parse_address(value: &str) -> Result<u64, String> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    u64::from_str_radix(digits, 16).map_err(|err| format!("not a hex address: {err}"))
+}
+
+/// The -x and -p selectors interleaved as given, which clap keeps apart.
+fn section_dumps(matches: &ArgMatches) -> Vec<Dump> {
+    let mut dumps = Vec::new();
+    let kinds = [
+        ("hex_dump", Dump::Hex as fn(String) -> Dump),
+        ("string_dump", Dump::Strings),
+    ];
+    for (id, dump) in kinds {
+        if let (Some(indices), Some(selectors)) =
+            (matches.indices_of(id), matches.get_many::<String>(id))
+        {
+            dumps.extend(indices.zip(selectors.cloned().map(dump)));
+        }
+    }
+    dumps.sort_by_key(|(index, _)| *index);
+    dumps.into_iter().map(|(_, dump)| dump).collect()
+}
+
+fn main() -> ExitCode {
+    // Die quietly of SIGPIPE like readelf when piped into head, rather than
+    // panicking on the failed write
+    #[cfg(unix)]
+    // SAFETY: nothing else is running yet to race on the signal disposition.
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    args.dumps = section_dumps(&matches);
+    color::enable(
+        args.color == ColorWhen::Always,
+        args.color == ColorWhen::Auto,
+    );
+    if args.all || (args.format == Format::Json && !args.any_selected()) {
+        args.select_all();
+    }
+    if !args.any_selected() {
+        eprintln!("hrelf: Warning: Nothing to do.");
+        eprintln!("Pass -a to display everything, or see --help for the individual options.");
+        return ExitCode::FAILURE;
+    }
+    args.file.append(&mut args.files);
+    if args.diff {
+        let [old, new] = args.file.as_slice() else {
+            eprintln!("hrelf: --diff compares exactly two files");
+            return ExitCode::from(2);
+        };
+        return match run_diff(&args, old, new) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(err) => {
+                report_error(&err);
+                ExitCode::from(2)
+            }
+        };
+    }
+    if args.file.is_empty() {
+        return match run(&args, None) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(err) => {
+                report_error(&err);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    // Like readelf, a file that fails doesn't stop the rest, only the exit status
+    let mut status = ExitCode::SUCCESS;
+    for file in &args.file {
+        if args.file.len() > 1 && args.format == Format::Human && !args.summary {
+            println!();
+            println!("File: {}", file.display());
+        }
+        match run(&args, Some(file)) {
+            Ok(true) => (),
+            Ok(false) => status = ExitCode::FAILURE,
+            Err(err) => {
+                report_error(&err);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
 }
@@ -0,0 +1,94 @@
+//! Read-only mappings of input files, so a large binary is paged in as the
+//! reports touch it instead of being copied onto the heap up front.
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+
+/// Inputs at least this big are mapped even without --mmap.
+pub const THRESHOLD: u64 = 64 << 20;
+
+/// An input file's bytes, however they were loaded.
+pub enum Bytes {
+    Read(Vec<u8>),
+    #[cfg(unix)]
+    Mapped(Mapping),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Read(data) => data,
+            #[cfg(unix)]
+            Bytes::Mapped(mapping) => mapping,
+        }
+    }
+}
+
+/// Maps `file` where the platform allows it, and reads it otherwise.
+pub fn map_or_read(mut file: File) -> io::Result<Bytes> {
+    #[cfg(unix)]
+    {
+        let len = file.metadata()?.len();
+        // mmap rejects empty lengths, and an empty file gains nothing anyway
+        if len > 0 {
+            let len = usize::try_from(len).map_err(|_| io::ErrorKind::OutOfMemory)?;
+            return Mapping::new(&file, len).map(Bytes::Mapped);
+        }
+    }
+    let mut data = Vec::new();
+    io::Read::read_to_end(&mut file, &mut data)?;
+    Ok(Bytes::Read(data))
+}
+
+/// A private, read-only mapping of a whole file.
+#[cfg(unix)]
+pub struct Mapping {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+#[cfg(unix)]
+impl Mapping {
+    fn new(file: &File, len: usize) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: a fresh mapping aliases no Rust memory. Like every mmap
+        // reader, this trusts nothing truncates the file while it's mapped.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mapping { ptr, len })
+    }
+}
+
+#[cfg(unix)]
+impl Deref for Mapping {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the mapping is `len` readable bytes until it's dropped.
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: ptr and len are exactly what mmap returned.
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
@@ -0,0 +1,629 @@
+//! Relocation type names by target machine, as readelf spells them.
+
+use crate::to_str;
+use elf::abi;
+
+pub fn r_type_to_str(e_machine: u16, r_type: u32) -> Option<&'static str> {
+    match e_machine {
+        abi::EM_386 => r_386_to_str(r_type),
+        abi::EM_X86_64 => r_x86_64_to_str(r_type),
+        abi::EM_ARM => r_arm_to_str(r_type),
+        abi::EM_AARCH64 => r_aarch64_to_str(r_type),
+        abi::EM_PPC64 => r_ppc64_to_str(r_type),
+        abi::EM_RISCV => r_riscv_to_str(r_type),
+        _ => None,
+    }
+}
+
+/// The type's name, or else its number and the machine it's unknown for.
+pub fn r_type_to_string(e_machine: u16, r_type: u32) -> String {
+    match r_type_to_str(e_machine, r_type) {
+        Some(s) => s.to_string(),
+        None => format!(
+            "{:#x} ({})",
+            r_type,
+            to_str::e_machine_to_short_string(e_machine)
+        ),
+    }
+}
+
+fn r_386_to_str(r_type: u32) -> Option<&'static str> {
+    // elf::abi has no R_386_* constants, so the psABI numbers are spelled out
+    match r_type {
+        0 => Some("R_386_NONE"),
+        1 => Some("R_386_32"),
+        2 => Some("R_386_PC32"),
+        3 => Some("R_386_GOT32"),
+        4 => Some("R_386_PLT32"),
+        5 => Some("R_386_COPY"),
+        6 => Some("R_386_GLOB_DAT"),
+        7 => Some("R_386_JUMP_SLOT"),
+        8 => Some("R_386_RELATIVE"),
+        9 => Some("R_386_GOTOFF"),
+        10 => Some("R_386_GOTPC"),
+        11 => Some("R_386_32PLT"),
+        14 => Some("R_386_TLS_TPOFF"),
+        15 => Some("R_386_TLS_IE"),
+        16 => Some("R_386_TLS_GOTIE"),
+        17 => Some("R_386_TLS_LE"),
+        18 => Some("R_386_TLS_GD"),
+        19 => Some("R_386_TLS_LDM"),
+        20 => Some("R_386_16"),
+        21 => Some("R_386_PC16"),
+        22 => Some("R_386_8"),
+        23 => Some("R_386_PC8"),
+        24 => Some("R_386_TLS_GD_32"),
+        25 => Some("R_386_TLS_GD_PUSH"),
+        26 => Some("R_386_TLS_GD_CALL"),
+        27 => Some("R_386_TLS_GD_POP"),
+        28 => Some("R_386_TLS_LDM_32"),
+        29 => Some("R_386_TLS_LDM_PUSH"),
+        30 => Some("R_386_TLS_LDM_CALL"),
+        31 => Some("R_386_TLS_LDM_POP"),
+        32 => Some("R_386_TLS_LDO_32"),
+        33 => Some("R_386_TLS_IE_32"),
+        34 => Some("R_386_TLS_LE_32"),
+        35 => Some("R_386_TLS_DTPMOD32"),
+        36 => Some("R_386_TLS_DTPOFF32"),
+        37 => Some("R_386_TLS_TPOFF32"),
+        38 => Some("R_386_SIZE32"),
+        39 => Some("R_386_TLS_GOTDESC"),
+        40 => Some("R_386_TLS_DESC_CALL"),
+        41 => Some("R_386_TLS_DESC"),
+        42 => Some("R_386_IRELATIVE"),
+        43 => Some("R_386_GOT32X"),
+        _ => None,
+    }
+}
+
+fn r_x86_64_to_str(r_type: u32) -> Option<&'static str> {
+    match r_type {
+        abi::R_X86_64_NONE => Some("R_X86_64_NONE"),
+        abi::R_X86_64_64 => Some("R_X86_64_64"),
+        abi::R_X86_64_PC32 => Some("R_X86_64_PC32"),
+        abi::R_X86_64_GOT32 => Some("R_X86_64_GOT32"),
+        abi::R_X86_64_PLT32 => Some("R_X86_64_PLT32"),
+        abi::R_X86_64_COPY => Some("R_X86_64_COPY"),
+        abi::R_X86_64_GLOB_DAT => Some("R_X86_64_GLOB_DAT"),
+        abi::R_X86_64_JUMP_SLOT => Some("R_X86_64_JUMP_SLOT"),
+        abi::R_X86_64_RELATIVE => Some("R_X86_64_RELATIVE"),
+        abi::R_X86_64_GOTPCREL => Some("R_X86_64_GOTPCREL"),
+        abi::R_X86_64_32 => Some("R_X86_64_32"),
+        abi::R_X86_64_32S => Some("R_X86_64_32S"),
+        abi::R_X86_64_16 => Some("R_X86_64_16"),
+        abi::R_X86_64_PC16 => Some("R_X86_64_PC16"),
+        abi::R_X86_64_8 => Some("R_X86_64_8"),
+        abi::R_X86_64_PC8 => Some("R_X86_64_PC8"),
+        abi::R_X86_64_DTPMOD64 => Some("R_X86_64_DTPMOD64"),
+        abi::R_X86_64_DTPOFF64 => Some("R_X86_64_DTPOFF64"),
+        abi::R_X86_64_TPOFF64 => Some("R_X86_64_TPOFF64"),
+        abi::R_X86_64_TLSGD => Some("R_X86_64_TLSGD"),
+        abi::R_X86_64_TLSLD => Some("R_X86_64_TLSLD"),
+        abi::R_X86_64_DTPOFF32 => Some("R_X86_64_DTPOFF32"),
+        abi::R_X86_64_GOTTPOFF => Some("R_X86_64_GOTTPOFF"),
+        abi::R_X86_64_TPOFF32 => Some("R_X86_64_TPOFF32"),
+        abi::R_X86_64_PC64 => Some("R_X86_64_PC64"),
+        abi::R_X86_64_GOTOFF64 => Some("R_X86_64_GOTOFF64"),
+        abi::R_X86_64_GOTPC32 => Some("R_X86_64_GOTPC32"),
+        abi::R_X86_64_GOT64 => Some("R_X86_64_GOT64"),
+        abi::R_X86_64_GOTPCREL64 => Some("R_X86_64_GOTPCREL64"),
+        abi::R_X86_64_GOTPC64 => Some("R_X86_64_GOTPC64"),
+        abi::R_X86_64_PLTOFF64 => Some("R_X86_64_PLTOFF64"),
+        abi::R_X86_64_SIZE32 => Some("R_X86_64_SIZE32"),
+        abi::R_X86_64_SIZE64 => Some("R_X86_64_SIZE64"),
+        abi::R_X86_64_GOTPC32_TLSDESC => Some("R_X86_64_GOTPC32_TLSDESC"),
+        abi::R_X86_64_TLSDESC_CALL => Some("R_X86_64_TLSDESC_CALL"),
+        abi::R_X86_64_TLSDESC => Some("R_X86_64_TLSDESC"),
+        abi::R_X86_64_IRELATIVE => Some("R_X86_64_IRELATIVE"),
+        abi::R_X86_64_RELATIVE64 => Some("R_X86_64_RELATIVE64"),
+        abi::R_X86_64_GOTPCRELX => Some("R_X86_64_GOTPCRELX"),
+        abi::R_X86_64_REX_GOTPCRELX => Some("R_X86_64_REX_GOTPCRELX"),
+        _ => None,
+    }
+}
+
+fn r_arm_to_str(r_type: u32) -> Option<&'static str> {
+    match r_type {
+        abi::R_ARM_NONE => Some("R_ARM_NONE"),
+        abi::R_ARM_PC24 => Some("R_ARM_PC24"),
+        abi::R_ARM_ABS32 => Some("R_ARM_ABS32"),
+        abi::R_ARM_REL32 => Some("R_ARM_REL32"),
+        abi::R_ARM_LDR_PC_G0 => Some("R_ARM_LDR_PC_G0"),
+        abi::R_ARM_ABS16 => Some("R_ARM_ABS16"),
+        abi::R_ARM_ABS12 => Some("R_ARM_ABS12"),
+        abi::R_ARM_THM_ABS5 => Some("R_ARM_THM_ABS5"),
+        abi::R_ARM_ABS8 => Some("R_ARM_ABS8"),
+        abi::R_ARM_SBREL32 => Some("R_ARM_ABS12"),
+        abi::R_ARM_THM_CALL => Some("R_ARM_THM_CALL"),
+        abi::R_ARM_THM_PC8 => Some("R_ARM_THM_PC8"),
+        abi::R_ARM_BREL_ADJ => Some("R_ARM_BREL_ADJ"),
+        abi::R_ARM_TLS_DESC => Some("R_ARM_TLS_DESC"),
+        abi::R_ARM_THM_SWI8 => Some("R_ARM_THM_SWI8"),
+        abi::R_ARM_XPC25 => Some("R_ARM_XPC25"),
+        abi::R_ARM_THM_XPC22 => Some("R_ARM_THM_XPC22"),
+        abi::R_ARM_TLS_DTPMOD32 => Some("R_ARM_TLS_DTPMOD32"),
+        abi::R_ARM_TLS_DTPOFF32 => Some("R_ARM_TLS_DTPOFF32"),
+        abi::R_ARM_TLS_TPOFF32 => Some("R_ARM_TLS_TPOFF32"),
+        abi::R_ARM_COPY => Some("R_ARM_COPY"),
+        abi::R_ARM_GLOB_DAT => Some("R_ARM_GLOB_DAT"),
+        abi::R_ARM_JUMP_SLOT => Some("R_ARM_JUMP_SLOT"),
+        abi::R_ARM_RELATIVE => Some("R_ARM_RELATIVE"),
+        abi::R_ARM_GOTOFF32 => Some("R_ARM_GOTOFF32"),
+        abi::R_ARM_BASE_PREL => Some("R_ARM_BASE_PREL"),
+        abi::R_ARM_BASE_BREL => Some("R_ARM_BASE_BREL"),
+        abi::R_ARM_PLT32 => Some("R_ARM_PLT32"),
+        abi::R_ARM_CALL => Some("R_ARM_CALL"),
+        abi::R_ARM_JUMP24 => Some("R_ARM_JUMP24"),
+        abi::R_ARM_THM_JUMP24 => Some("R_ARM_THM_JUMP24"),
+        abi::R_ARM_BASE_ABS => Some("R_ARM_BASE_ABS"),
+        abi::R_ARM_ALU_PCREL_7_0 => Some("R_ARM_ALU_PCREL_7_0"),
+        abi::R_ARM_ALU_PCREL_15_8 => Some("R_ARM_ALU_PCREL_15_8"),
+        abi::R_ARM_ALU_PCREL_23_15 => Some("R_ARM_ALU_PCREL_23_15"),
+        abi::R_ARM_LDR_SBREL_11_0 => Some("R_ARM_LDR_SBREL_11_0"),
+        abi::R_ARM_ALU_SBREL_19_12 => Some("R_ARM_ALU_SBREL_19_12"),
+        abi::R_ARM_ALU_SBREL_27_20 => Some("R_ARM_ALU_SBREL_27_20"),
+        abi::R_ARM_TARGET1 => Some("R_ARM_TARGET1"),
+        abi::R_ARM_SBREL31 => Some("R_ARM_SBREL31"),
+        abi::R_ARM_V4BX => Some("R_ARM_V4BX"),
+        abi::R_ARM_TARGET2 => Some("R_ARM_TARGET2"),
+        abi::R_ARM_PREL31 => Some("R_ARM_PREL31"),
+        abi::R_ARM_MOVW_ABS_NC => Some("R_ARM_MOVW_ABS_NC"),
+        abi::R_ARM_MOVT_ABS => Some("R_ARM_MOVT_ABS"),
+        abi::R_ARM_MOVW_PREL_NC => Some("R_ARM_MOVW_PREL_NC"),
+        abi::R_ARM_MOVT_PREL => Some("R_ARM_MOVT_PREL"),
+        abi::R_ARM_THM_MOVW_ABS_NC => Some("R_ARM_THM_MOVW_ABS_NC"),
+        abi::R_ARM_THM_MOVT_ABS => Some("R_ARM_THM_MOVT_ABS"),
+        abi::R_ARM_THM_MOVW_PREL_NC => Some("R_ARM_THM_MOVW_PREL_NC"),
+        abi::R_ARM_THM_MOVT_PREL => Some("R_ARM_THM_MOVT_PREL"),
+        abi::R_ARM_THM_JUMP19 => Some("R_ARM_THM_JUMP19"),
+        abi::R_ARM_THM_JUMP6 => Some("R_ARM_THM_JUMP6"),
+        abi::R_ARM_THM_ALU_PREL_11_0 => Some("R_ARM_THM_ALU_PREL_11_0"),
+        abi::R_ARM_THM_PC12 => Some("R_ARM_THM_PC12"),
+        abi::R_ARM_ABS32_NOI => Some("R_ARM_ABS32_NOI"),
+        abi::R_ARM_REL32_NOI => Some("R_ARM_REL32_NOI"),
+        abi::R_ARM_ALU_PC_G0_NC => Some("R_ARM_ALU_PC_G0_NC"),
+        abi::R_ARM_ALU_PC_G0 => Some("R_ARM_ALU_PC_G0"),
+        abi::R_ARM_ALU_PC_G1_NC => Some("R_ARM_ALU_PC_G1_NC"),
+        abi::R_ARM_ALU_PC_G1 => Some("R_ARM_ALU_PC_G1"),
+        abi::R_ARM_ALU_PC_G2 => Some("R_ARM_ALU_PC_G2"),
+        abi::R_ARM_LDR_PC_G1 => Some("R_ARM_LDR_PC_G1"),
+        abi::R_ARM_LDR_PC_G2 => Some("R_ARM_LDR_PC_G2"),
+        abi::R_ARM_LDRS_PC_G0 => Some("R_ARM_LDRS_PC_G0"),
+        abi::R_ARM_LDRS_PC_G1 => Some("R_ARM_LDRS_PC_G1"),
+        abi::R_ARM_LDRS_PC_G2 => Some("R_ARM_LDRS_PC_G2"),
+        abi::R_ARM_LDC_PC_G0 => Some("R_ARM_LDC_PC_G0"),
+        abi::R_ARM_LDC_PC_G1 => Some("R_ARM_LDC_PC_G1"),
+        abi::R_ARM_LDC_PC_G2 => Some("R_ARM_LDC_PC_G2"),
+        abi::R_ARM_ALU_SB_G0_NC => Some("R_ARM_ALU_SB_G0_NC"),
+        abi::R_ARM_ALU_SB_G0 => Some("R_ARM_ALU_SB_G0"),
+        abi::R_ARM_ALU_SB_G1_NC => Some("R_ARM_ALU_SB_G1_NC"),
+        abi::R_ARM_ALU_SB_G1 => Some("R_ARM_ALU_SB_G1"),
+        abi::R_ARM_ALU_SB_G2 => Some("R_ARM_ALU_SB_G2"),
+        abi::R_ARM_LDR_SB_G0 => Some("R_ARM_LDR_SB_G0"),
+        abi::R_ARM_LDR_SB_G1 => Some("R_ARM_LDR_SB_G1"),
+        abi::R_ARM_LDR_SB_G2 => Some("R_ARM_LDR_SB_G2"),
+        abi::R_ARM_LDRS_SB_G0 => Some("R_ARM_LDRS_SB_G0"),
+        abi::R_ARM_LDRS_SB_G1 => Some("R_ARM_LDRS_SB_G1"),
+        abi::R_ARM_LDRS_SB_G2 => Some("R_ARM_LDRS_SB_G2"),
+        abi::R_ARM_LDC_SB_G0 => Some("R_ARM_LDC_SB_G0"),
+        abi::R_ARM_LDC_SB_G1 => Some("R_ARM_LDC_SB_G1"),
+        abi::R_ARM_LDC_SB_G2 => Some("R_ARM_LDC_SB_G2"),
+        abi::R_ARM_MOVW_BREL_NC => Some("R_ARM_MOVW_BREL_NC"),
+        abi::R_ARM_MOVT_BREL => Some("R_ARM_MOVT_BREL"),
+        abi::R_ARM_MOVW_BREL => Some("R_ARM_MOVW_BREL"),
+        abi::R_ARM_THM_MOVW_BREL_NC => Some("R_ARM_THM_MOVW_BREL_NC"),
+        abi::R_ARM_THM_MOVT_BREL => Some("R_ARM_THM_MOVT_BREL"),
+        abi::R_ARM_THM_MOVW_BREL => Some("R_ARM_THM_MOVW_BREL"),
+        abi::R_ARM_TLS_GOTDESC => Some("R_ARM_TLS_GOTDESC"),
+        abi::R_ARM_TLS_CALL => Some("R_ARM_TLS_CALL"),
+        abi::R_ARM_TLS_DESCSEQ => Some("R_ARM_TLS_DESCSEQ"),
+        abi::R_ARM_THM_TLS_CALL => Some("R_ARM_THM_TLS_CALL"),
+        abi::R_ARM_PLT32_ABS => Some("R_ARM_PLT32_ABS"),
+        abi::R_ARM_GOT_ABS => Some("R_ARM_GOT_ABS"),
+        abi::R_ARM_GOT_PREL => Some("R_ARM_GOT_PREL"),
+        abi::R_ARM_GOT_BREL12 => Some("R_ARM_GOT_BREL12"),
+        abi::R_ARM_GOTOFF12 => Some("R_ARM_GOTOFF12"),
+        abi::R_ARM_GOTRELAX => Some("R_ARM_GOTRELAX"),
+        abi::R_ARM_GNU_VTENTRY => Some("R_ARM_GNU_VTENTRY"),
+        abi::R_ARM_GNU_VTINHERIT => Some("R_ARM_GNU_VTINHERIT"),
+        abi::R_ARM_THM_JUMP11 => Some("R_ARM_THM_JUMP11"),
+        abi::R_ARM_THM_JUMP8 => Some("R_ARM_THM_JUMP8"),
+        abi::R_ARM_TLS_GD32 => Some("R_ARM_TLS_GD32"),
+        abi::R_ARM_TLS_LDM32 => Some("R_ARM_TLS_LDM32"),
+        abi::R_ARM_TLS_LDO32 => Some("R_ARM_TLS_LDO32"),
+        abi::R_ARM_TLS_IE32 => Some("R_ARM_TLS_IE32"),
+        abi::R_ARM_TLS_LE32 => Some("R_ARM_TLS_LE32"),
+        abi::R_ARM_TLS_LDO12 => Some("R_ARM_TLS_LDO12"),
+        abi::R_ARM_TLS_LE12 => Some("R_ARM_TLS_LE12"),
+        abi::R_ARM_TLS_IE12GP => Some("R_ARM_TLS_IE12GP"),
+        abi::R_ARM_ME_TOO => Some("R_ARM_ME_TOO"),
+        abi::R_ARM_THM_TLS_DESCSEQ16 => Some("R_ARM_THM_TLS_DESCSEQ16"),
+        abi::R_ARM_THM_TLS_DESCSEQ32 => Some("R_ARM_THM_TLS_DESCSEQ32"),
+        abi::R_ARM_THM_GOT_BREL12 => Some("R_ARM_THM_GOT_BREL12"),
+        abi::R_ARM_THM_ALU_ABS_G0_NC => Some("R_ARM_THM_ALU_ABS_G0_NC"),
+        abi::R_ARM_THM_ALU_ABS_G1_NC => Some("R_ARM_THM_ALU_ABS_G1_NC"),
+        abi::R_ARM_THM_ALU_ABS_G2_NC => Some("R_ARM_THM_ALU_ABS_G2_NC"),
+        abi::R_ARM_THM_ALU_ABS_G3 => Some("R_ARM_THM_ALU_ABS_G3"),
+        abi::R_ARM_THM_BF16 => Some("R_ARM_THM_BF16"),
+        abi::R_ARM_THM_BF12 => Some("R_ARM_THM_BF12"),
+        abi::R_ARM_THM_BF18 => Some("R_ARM_THM_BF18"),
+        abi::R_ARM_IRELATIVE => Some("R_ARM_IRELATIVE"),
+        _ => None,
+    }
+}
+
+fn r_aarch64_to_str(r_type: u32) -> Option<&'static str> {
+    match r_type {
+        abi::R_AARCH64_NONE => Some("R_AARCH64_NONE"),
+        abi::R_AARCH64_P32_ABS32 => Some("R_AARCH64_P32_ABS32"),
+        abi::R_AARCH64_P32_COPY => Some("R_AARCH64_P32_COPY"),
+        abi::R_AARCH64_P32_GLOB_DAT => Some("R_AARCH64_P32_GLOB_DAT"),
+        abi::R_AARCH64_P32_JUMP_SLOT => Some("R_AARCH64_P32_JUMP_SLOT"),
+        abi::R_AARCH64_P32_RELATIVE => Some("R_AARCH64_P32_RELATIVE"),
+        abi::R_AARCH64_P32_TLS_DTPMOD => Some("R_AARCH64_P32_TLS_DTPMOD"),
+        abi::R_AARCH64_P32_TLS_DTPREL => Some("R_AARCH64_P32_TLS_DTPREL"),
+        abi::R_AARCH64_P32_TLS_TPREL => Some("R_AARCH64_P32_TLS_TPREL"),
+        abi::R_AARCH64_P32_TLSDESC => Some("R_AARCH64_P32_TLSDESC"),
+        abi::R_AARCH64_P32_IRELATIVE => Some("R_AARCH64_P32_IRELATIVE"),
+        abi::R_AARCH64_ABS64 => Some("R_AARCH64_ABS64"),
+        abi::R_AARCH64_ABS32 => Some("R_AARCH64_ABS32"),
+        abi::R_AARCH64_ABS16 => Some("R_AARCH64_ABS16"),
+        abi::R_AARCH64_PREL64 => Some("R_AARCH64_PREL64"),
+        abi::R_AARCH64_PREL32 => Some("R_AARCH64_PREL32"),
+        abi::R_AARCH64_PREL16 => Some("R_AARCH64_PREL16"),
+        abi::R_AARCH64_MOVW_UABS_G0 => Some("R_AARCH64_MOVW_UABS_G0"),
+        abi::R_AARCH64_MOVW_UABS_G0_NC => Some("R_AARCH64_MOVW_UABS_G0_NC"),
+        abi::R_AARCH64_MOVW_UABS_G1 => Some("R_AARCH64_MOVW_UABS_G1"),
+        abi::R_AARCH64_MOVW_UABS_G1_NC => Some("R_AARCH64_MOVW_UABS_G1_NC"),
+        abi::R_AARCH64_MOVW_UABS_G2 => Some("R_AARCH64_MOVW_UABS_G2"),
+        abi::R_AARCH64_MOVW_UABS_G2_NC => Some("R_AARCH64_MOVW_UABS_G2_NC"),
+        abi::R_AARCH64_MOVW_UABS_G3 => Some("R_AARCH64_MOVW_UABS_G3"),
+        abi::R_AARCH64_MOVW_SABS_G0 => Some("R_AARCH64_MOVW_SABS_G0"),
+        abi::R_AARCH64_MOVW_SABS_G1 => Some("R_AARCH64_MOVW_SABS_G1"),
+        abi::R_AARCH64_MOVW_SABS_G2 => Some("R_AARCH64_MOVW_SABS_G2"),
+        abi::R_AARCH64_LD_PREL_LO19 => Some("R_AARCH64_LD_PREL_LO19"),
+        abi::R_AARCH64_ADR_PREL_LO21 => Some("R_AARCH64_ADR_PREL_LO21"),
+        abi::R_AARCH64_ADR_PREL_PG_HI21 => Some("R_AARCH64_ADR_PREL_PG_HI21"),
+        abi::R_AARCH64_ADR_PREL_PG_HI21_NC => Some("R_AARCH64_ADR_PREL_PG_HI21_NC"),
+        abi::R_AARCH64_ADD_ABS_LO12_NC => Some("R_AARCH64_ADD_ABS_LO12_NC"),
+        abi::R_AARCH64_LDST8_ABS_LO12_NC => Some("R_AARCH64_LDST8_ABS_LO12_NC"),
+        abi::R_AARCH64_TSTBR14 => Some("R_AARCH64_TSTBR14"),
+        abi::R_AARCH64_CONDBR19 => Some("R_AARCH64_CONDBR19"),
+        abi::R_AARCH64_JUMP26 => Some("R_AARCH64_JUMP26"),
+        abi::R_AARCH64_CALL26 => Some("R_AARCH64_CALL26"),
+        abi::R_AARCH64_LDST16_ABS_LO12_NC => Some("R_AARCH64_LDST16_ABS_LO12_NC"),
+        abi::R_AARCH64_LDST32_ABS_LO12_NC => Some("R_AARCH64_LDST32_ABS_LO12_NC"),
+        abi::R_AARCH64_LDST64_ABS_LO12_NC => Some("R_AARCH64_LDST64_ABS_LO12_NC"),
+        abi::R_AARCH64_MOVW_PREL_G0 => Some("R_AARCH64_MOVW_PREL_G0"),
+        abi::R_AARCH64_MOVW_PREL_G0_NC => Some("R_AARCH64_MOVW_PREL_G0_NC"),
+        abi::R_AARCH64_MOVW_PREL_G1 => Some("R_AARCH64_MOVW_PREL_G1"),
+        abi::R_AARCH64_MOVW_PREL_G1_NC => Some("R_AARCH64_MOVW_PREL_G1_NC"),
+        abi::R_AARCH64_MOVW_PREL_G2 => Some("R_AARCH64_MOVW_PREL_G2"),
+        abi::R_AARCH64_MOVW_PREL_G2_NC => Some("R_AARCH64_MOVW_PREL_G2_NC"),
+        abi::R_AARCH64_MOVW_PREL_G3 => Some("R_AARCH64_MOVW_PREL_G3"),
+        abi::R_AARCH64_LDST128_ABS_LO12_NC => Some("R_AARCH64_LDST128_ABS_LO12_NC"),
+        abi::R_AARCH64_MOVW_GOTOFF_G0 => Some("R_AARCH64_MOVW_GOTOFF_G0"),
+        abi::R_AARCH64_MOVW_GOTOFF_G0_NC => Some("R_AARCH64_MOVW_GOTOFF_G0_NC"),
+        abi::R_AARCH64_MOVW_GOTOFF_G1 => Some("R_AARCH64_MOVW_GOTOFF_G1"),
+        abi::R_AARCH64_MOVW_GOTOFF_G1_NC => Some("R_AARCH64_MOVW_GOTOFF_G1_NC"),
+        abi::R_AARCH64_MOVW_GOTOFF_G2 => Some("R_AARCH64_MOVW_GOTOFF_G2"),
+        abi::R_AARCH64_MOVW_GOTOFF_G2_NC => Some("R_AARCH64_MOVW_GOTOFF_G2_NC"),
+        abi::R_AARCH64_MOVW_GOTOFF_G3 => Some("R_AARCH64_MOVW_GOTOFF_G3"),
+        abi::R_AARCH64_GOTREL64 => Some("R_AARCH64_GOTREL64"),
+        abi::R_AARCH64_GOTREL32 => Some("R_AARCH64_GOTREL32"),
+        abi::R_AARCH64_GOT_LD_PREL19 => Some("R_AARCH64_GOT_LD_PREL19"),
+        abi::R_AARCH64_LD64_GOTOFF_LO15 => Some("R_AARCH64_LD64_GOTOFF_LO15"),
+        abi::R_AARCH64_ADR_GOT_PAGE => Some("R_AARCH64_ADR_GOT_PAGE"),
+        abi::R_AARCH64_LD64_GOT_LO12_NC => Some("R_AARCH64_LD64_GOT_LO12_NC"),
+        abi::R_AARCH64_LD64_GOTPAGE_LO15 => Some("R_AARCH64_LD64_GOTPAGE_LO15"),
+        abi::R_AARCH64_TLSGD_ADR_PREL21 => Some("R_AARCH64_TLSGD_ADR_PREL21"),
+        abi::R_AARCH64_TLSGD_ADR_PAGE21 => Some("R_AARCH64_TLSGD_ADR_PAGE21"),
+        abi::R_AARCH64_TLSGD_ADD_LO12_NC => Some("R_AARCH64_TLSGD_ADD_LO12_NC"),
+        abi::R_AARCH64_TLSGD_MOVW_G1 => Some("R_AARCH64_TLSGD_MOVW_G1"),
+        abi::R_AARCH64_TLSGD_MOVW_G0_NC => Some("R_AARCH64_TLSGD_MOVW_G0_NC"),
+        abi::R_AARCH64_TLSLD_ADR_PREL21 => Some("R_AARCH64_TLSLD_ADR_PREL21"),
+        abi::R_AARCH64_TLSLD_ADR_PAGE21 => Some("R_AARCH64_TLSLD_ADR_PAGE21"),
+        abi::R_AARCH64_TLSLD_ADD_LO12_NC => Some("R_AARCH64_TLSLD_ADD_LO12_NC"),
+        abi::R_AARCH64_TLSLD_MOVW_G1 => Some("R_AARCH64_TLSLD_MOVW_G1"),
+        abi::R_AARCH64_TLSLD_MOVW_G0_NC => Some("R_AARCH64_TLSLD_MOVW_G0_NC"),
+        abi::R_AARCH64_TLSLD_LD_PREL19 => Some("R_AARCH64_TLSLD_LD_PREL19"),
+        abi::R_AARCH64_TLSLD_MOVW_DTPREL_G2 => Some("R_AARCH64_TLSLD_MOVW_DTPREL_G2"),
+        abi::R_AARCH64_TLSLD_MOVW_DTPREL_G1 => Some("R_AARCH64_TLSLD_MOVW_DTPREL_G1"),
+        abi::R_AARCH64_TLSLD_MOVW_DTPREL_G1_NC => Some("R_AARCH64_TLSLD_MOVW_DTPREL_G1_NC"),
+        abi::R_AARCH64_TLSLD_MOVW_DTPREL_G0 => Some("R_AARCH64_TLSLD_MOVW_DTPREL_G0"),
+        abi::R_AARCH64_TLSLD_MOVW_DTPREL_G0_NC => Some("R_AARCH64_TLSLD_MOVW_DTPREL_G0_NC"),
+        abi::R_AARCH64_TLSLD_ADD_DTPREL_HI12 => Some("R_AARCH64_TLSLD_ADD_DTPREL_HI12"),
+        abi::R_AARCH64_TLSLD_ADD_DTPREL_LO12 => Some("R_AARCH64_TLSLD_ADD_DTPREL_LO12"),
+        abi::R_AARCH64_TLSLD_ADD_DTPREL_LO12_NC => Some("R_AARCH64_TLSLD_ADD_DTPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLD_LDST8_DTPREL_LO12 => Some("R_AARCH64_TLSLD_LDST8_DTPREL_LO12"),
+        abi::R_AARCH64_TLSLD_LDST8_DTPREL_LO12_NC => Some("R_AARCH64_TLSLD_LDST8_DTPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLD_LDST16_DTPREL_LO12 => Some("R_AARCH64_TLSLD_LDST16_DTPREL_LO12"),
+        abi::R_AARCH64_TLSLD_LDST16_DTPREL_LO12_NC => Some("R_AARCH64_TLSLD_LDST16_DTPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLD_LDST32_DTPREL_LO12 => Some("R_AARCH64_TLSLD_LDST32_DTPREL_LO12"),
+        abi::R_AARCH64_TLSLD_LDST32_DTPREL_LO12_NC => Some("R_AARCH64_TLSLD_LDST32_DTPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLD_LDST64_DTPREL_LO12 => Some("R_AARCH64_TLSLD_LDST64_DTPREL_LO12"),
+        abi::R_AARCH64_TLSLD_LDST64_DTPREL_LO12_NC => Some("R_AARCH64_TLSLD_LDST64_DTPREL_LO12_NC"),
+        abi::R_AARCH64_TLSIE_MOVW_GOTTPREL_G1 => Some("R_AARCH64_TLSIE_MOVW_GOTTPREL_G1"),
+        abi::R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC => Some("R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC"),
+        abi::R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21 => Some("R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21"),
+        abi::R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC => Some("R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC"),
+        abi::R_AARCH64_TLSIE_LD_GOTTPREL_PREL19 => Some("R_AARCH64_TLSIE_LD_GOTTPREL_PREL19"),
+        abi::R_AARCH64_TLSLE_MOVW_TPREL_G2 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G2"),
+        abi::R_AARCH64_TLSLE_MOVW_TPREL_G1 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G1"),
+        abi::R_AARCH64_TLSLE_MOVW_TPREL_G1_NC => Some("R_AARCH64_TLSLE_MOVW_TPREL_G1_NC"),
+        abi::R_AARCH64_TLSLE_MOVW_TPREL_G0 => Some("R_AARCH64_TLSLE_MOVW_TPREL_G0"),
+        abi::R_AARCH64_TLSLE_MOVW_TPREL_G0_NC => Some("R_AARCH64_TLSLE_MOVW_TPREL_G0_NC"),
+        abi::R_AARCH64_TLSLE_ADD_TPREL_HI12 => Some("R_AARCH64_TLSLE_ADD_TPREL_HI12"),
+        abi::R_AARCH64_TLSLE_ADD_TPREL_LO12 => Some("R_AARCH64_TLSLE_ADD_TPREL_LO12"),
+        abi::R_AARCH64_TLSLE_ADD_TPREL_LO12_NC => Some("R_AARCH64_TLSLE_ADD_TPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLE_LDST8_TPREL_LO12 => Some("R_AARCH64_TLSLE_LDST8_TPREL_LO12"),
+        abi::R_AARCH64_TLSLE_LDST8_TPREL_LO12_NC => Some("R_AARCH64_TLSLE_LDST8_TPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLE_LDST16_TPREL_LO12 => Some("R_AARCH64_TLSLE_LDST16_TPREL_LO12"),
+        abi::R_AARCH64_TLSLE_LDST16_TPREL_LO12_NC => Some("R_AARCH64_TLSLE_LDST16_TPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLE_LDST32_TPREL_LO12 => Some("R_AARCH64_TLSLE_LDST32_TPREL_LO12"),
+        abi::R_AARCH64_TLSLE_LDST32_TPREL_LO12_NC => Some("R_AARCH64_TLSLE_LDST32_TPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLE_LDST64_TPREL_LO12 => Some("R_AARCH64_TLSLE_LDST64_TPREL_LO12"),
+        abi::R_AARCH64_TLSLE_LDST64_TPREL_LO12_NC => Some("R_AARCH64_TLSLE_LDST64_TPREL_LO12_NC"),
+        abi::R_AARCH64_TLSDESC_LD_PREL19 => Some("R_AARCH64_TLSDESC_LD_PREL19"),
+        abi::R_AARCH64_TLSDESC_ADR_PREL21 => Some("R_AARCH64_TLSDESC_ADR_PREL21"),
+        abi::R_AARCH64_TLSDESC_ADR_PAGE21 => Some("R_AARCH64_TLSDESC_ADR_PAGE21"),
+        abi::R_AARCH64_TLSDESC_LD64_LO12 => Some("R_AARCH64_TLSDESC_LD64_LO12"),
+        abi::R_AARCH64_TLSDESC_ADD_LO12 => Some("R_AARCH64_TLSDESC_ADD_LO12"),
+        abi::R_AARCH64_TLSDESC_OFF_G1 => Some("R_AARCH64_TLSDESC_OFF_G1"),
+        abi::R_AARCH64_TLSDESC_OFF_G0_NC => Some("R_AARCH64_TLSDESC_OFF_G0_NC"),
+        abi::R_AARCH64_TLSDESC_LDR => Some("R_AARCH64_TLSDESC_LDR"),
+        abi::R_AARCH64_TLSDESC_ADD => Some("R_AARCH64_TLSDESC_ADD"),
+        abi::R_AARCH64_TLSDESC_CALL => Some("R_AARCH64_TLSDESC_CALL"),
+        abi::R_AARCH64_TLSLE_LDST128_TPREL_LO12 => Some("R_AARCH64_TLSLE_LDST128_TPREL_LO12"),
+        abi::R_AARCH64_TLSLE_LDST128_TPREL_LO12_NC => Some("R_AARCH64_TLSLE_LDST128_TPREL_LO12_NC"),
+        abi::R_AARCH64_TLSLD_LDST128_DTPREL_LO12 => Some("R_AARCH64_TLSLD_LDST128_DTPREL_LO12"),
+        abi::R_AARCH64_TLSLD_LDST128_DTPREL_LO12_NC => {
+            Some("R_AARCH64_TLSLD_LDST128_DTPREL_LO12_NC")
+        }
+        abi::R_AARCH64_COPY => Some("R_AARCH64_COPY"),
+        abi::R_AARCH64_GLOB_DAT => Some("R_AARCH64_GLOB_DAT"),
+        abi::R_AARCH64_JUMP_SLOT => Some("R_AARCH64_JUMP_SLOT"),
+        abi::R_AARCH64_RELATIVE => Some("R_AARCH64_RELATIVE"),
+        abi::R_AARCH64_TLS_DTPMOD => Some("R_AARCH64_TLS_DTPMOD"),
+        abi::R_AARCH64_TLS_DTPREL => Some("R_AARCH64_TLS_DTPREL"),
+        abi::R_AARCH64_TLS_TPREL => Some("R_AARCH64_TLS_TPREL"),
+        abi::R_AARCH64_TLSDESC => Some("R_AARCH64_TLSDESC"),
+        abi::R_AARCH64_IRELATIVE => Some("R_AARCH64_IRELATIVE"),
+        _ => None,
+    }
+}
+
+fn r_ppc64_to_str(r_type: u32) -> Option<&'static str> {
+    match r_type {
+        abi::R_PPC64_NONE => Some("R_PPC64_NONE"),
+        abi::R_PPC64_ADDR32 => Some("R_PPC64_ADDR32"),
+        abi::R_PPC64_ADDR24 => Some("R_PPC64_ADDR24"),
+        abi::R_PPC64_ADDR16 => Some("R_PPC64_ADDR16"),
+        abi::R_PPC64_ADDR16_LO => Some("R_PPC64_ADDR16_LO"),
+        abi::R_PPC64_ADDR16_HI => Some("R_PPC64_ADDR16_HI"),
+        abi::R_PPC64_ADDR16_HA => Some("R_PPC64_ADDR16_HA"),
+        abi::R_PPC64_ADDR14 => Some("R_PPC64_ADDR14"),
+        abi::R_PPC64_ADDR14_BRTAKEN => Some("R_PPC64_ADDR14_BRTAKEN"),
+        abi::R_PPC64_ADDR14_BRNTAKEN => Some("R_PPC64_ADDR14_BRNTAKEN"),
+        abi::R_PPC64_REL24 => Some("R_PPC64_REL24"),
+        abi::R_PPC64_REL14 => Some("R_PPC64_REL14"),
+        abi::R_PPC64_REL14_BRTAKEN => Some("R_PPC64_REL14_BRTAKEN"),
+        abi::R_PPC64_REL14_BRNTAKEN => Some("R_PPC64_REL14_BRNTAKEN"),
+        abi::R_PPC64_GOT16 => Some("R_PPC64_GOT16"),
+        abi::R_PPC64_GOT16_LO => Some("R_PPC64_GOT16_LO"),
+        abi::R_PPC64_GOT16_HI => Some("R_PPC64_GOT16_HI"),
+        abi::R_PPC64_GOT16_HA => Some("R_PPC64_GOT16_HA"),
+        abi::R_PPC64_COPY => Some("R_PPC64_COPY"),
+        abi::R_PPC64_GLOB_DAT => Some("R_PPC64_GLOB_DAT"),
+        abi::R_PPC64_JMP_SLOT => Some("R_PPC64_JMP_SLOT"),
+        abi::R_PPC64_RELATIVE => Some("R_PPC64_RELATIVE"),
+        abi::R_PPC64_UADDR32 => Some("R_PPC64_UADDR32"),
+        abi::R_PPC64_UADDR16 => Some("R_PPC64_UADDR16"),
+        abi::R_PPC64_REL32 => Some("R_PPC64_REL32"),
+        abi::R_PPC64_PLT32 => Some("R_PPC64_PLT32"),
+        abi::R_PPC64_PLTREL32 => Some("R_PPC64_PLTREL32"),
+        abi::R_PPC64_PLT16_LO => Some("R_PPC64_PLT16_LO"),
+        abi::R_PPC64_PLT16_HI => Some("R_PPC64_PLT16_HI"),
+        abi::R_PPC64_PLT16_HA => Some("R_PPC64_PLT16_HA"),
+        abi::R_PPC64_SECTOFF => Some("R_PPC64_SECTOFF"),
+        abi::R_PPC64_SECTOFF_LO => Some("R_PPC64_SECTOFF_LO"),
+        abi::R_PPC64_SECTOFF_HI => Some("R_PPC64_SECTOFF_HI"),
+        abi::R_PPC64_SECTOFF_HA => Some("R_PPC64_SECTOFF_HA"),
+        abi::R_PPC64_ADDR30 => Some("R_PPC64_ADDR30"),
+        abi::R_PPC64_ADDR64 => Some("R_PPC64_ADDR64"),
+        abi::R_PPC64_ADDR16_HIGHER => Some("R_PPC64_ADDR16_HIGHER"),
+        abi::R_PPC64_ADDR16_HIGHERA => Some("R_PPC64_ADDR16_HIGHERA"),
+        abi::R_PPC64_ADDR16_HIGHEST => Some("R_PPC64_ADDR16_HIGHEST"),
+        abi::R_PPC64_ADDR16_HIGHESTA => Some("R_PPC64_ADDR16_HIGHESTA"),
+        abi::R_PPC64_UADDR64 => Some("R_PPC64_UADDR64"),
+        abi::R_PPC64_REL64 => Some("R_PPC64_REL64"),
+        abi::R_PPC64_PLT64 => Some("R_PPC64_PLT64"),
+        abi::R_PPC64_PLTREL64 => Some("R_PPC64_PLTREL64"),
+        abi::R_PPC64_TOC16 => Some("R_PPC64_TOC16"),
+        abi::R_PPC64_TOC16_LO => Some("R_PPC64_TOC16_LO"),
+        abi::R_PPC64_TOC16_HI => Some("R_PPC64_TOC16_HI"),
+        abi::R_PPC64_TOC16_HA => Some("R_PPC64_TOC16_HA"),
+        abi::R_PPC64_TOC => Some("R_PPC64_TOC"),
+        abi::R_PPC64_PLTGOT16 => Some("R_PPC64_PLTGOT16"),
+        abi::R_PPC64_PLTGOT16_LO => Some("R_PPC64_PLTGOT16_LO"),
+        abi::R_PPC64_PLTGOT16_HI => Some("R_PPC64_PLTGOT16_HI"),
+        abi::R_PPC64_PLTGOT16_HA => Some("R_PPC64_PLTGOT16_HA"),
+        abi::R_PPC64_ADDR16_DS => Some("R_PPC64_ADDR16_DS"),
+        abi::R_PPC64_ADDR16_LO_DS => Some("R_PPC64_ADDR16_LO_DS"),
+        abi::R_PPC64_GOT16_DS => Some("R_PPC64_GOT16_DS"),
+        abi::R_PPC64_GOT16_LO_DS => Some("R_PPC64_GOT16_LO_DS"),
+        abi::R_PPC64_PLT16_LO_DS => Some("R_PPC64_PLT16_LO_DS"),
+        abi::R_PPC64_SECTOFF_DS => Some("R_PPC64_SECTOFF_DS"),
+        abi::R_PPC64_SECTOFF_LO_DS => Some("R_PPC64_SECTOFF_LO_DS"),
+        abi::R_PPC64_TOC16_DS => Some("R_PPC64_TOC16_DS"),
+        abi::R_PPC64_TOC16_LO_DS => Some("R_PPC64_TOC16_LO_DS"),
+        abi::R_PPC64_PLTGOT16_DS => Some("R_PPC64_PLTGOT16_DS"),
+        abi::R_PPC64_PLTGOT16_LO_DS => Some("R_PPC64_PLTGOT16_LO_DS"),
+        abi::R_PPC64_TLS => Some("R_PPC64_TLS"),
+        abi::R_PPC64_DTPMOD64 => Some("R_PPC64_DTPMOD64"),
+        abi::R_PPC64_TPREL16 => Some("R_PPC64_TPREL16"),
+        abi::R_PPC64_TPREL16_HI => Some("R_PPC64_TPREL16_HI"),
+        abi::R_PPC64_TPREL16_HA => Some("R_PPC64_TPREL16_HA"),
+        abi::R_PPC64_TPREL64 => Some("R_PPC64_TPREL64"),
+        abi::R_PPC64_DTPREL16 => Some("R_PPC64_DTPREL16"),
+        abi::R_PPC64_DTPREL16_LO => Some("R_PPC64_DTPREL16_LO"),
+        abi::R_PPC64_DTPREL16_HI => Some("R_PPC64_DTPREL16_HI"),
+        abi::R_PPC64_DTPREL16_HA => Some("R_PPC64_DTPREL16_HA"),
+        abi::R_PPC64_DTPREL64 => Some("R_PPC64_DTPREL64"),
+        abi::R_PPC64_GOT_TLSGD16 => Some("R_PPC64_GOT_TLSGD16"),
+        abi::R_PPC64_GOT_TLSGD16_LO => Some("R_PPC64_GOT_TLSGD16_LO"),
+        abi::R_PPC64_GOT_TLSGD16_HI => Some("R_PPC64_GOT_TLSGD16_HI"),
+        abi::R_PPC64_GOT_TLSGD16_HA => Some("R_PPC64_GOT_TLSGD16_HA"),
+        abi::R_PPC64_GOT_TLSLD16 => Some("R_PPC64_GOT_TLSLD16"),
+        abi::R_PPC64_GOT_TLSLD16_LO => Some("R_PPC64_GOT_TLSLD16_LO"),
+        abi::R_PPC64_GOT_TLSLD16_HI => Some("R_PPC64_GOT_TLSLD16_HI"),
+        abi::R_PPC64_GOT_TLSLD16_HA => Some("R_PPC64_GOT_TLSLD16_HA"),
+        abi::R_PPC64_GOT_TPREL16_DS => Some("R_PPC64_GOT_TPREL16_DS"),
+        abi::R_PPC64_GOT_TPREL16_LO_DS => Some("R_PPC64_GOT_TPREL16_LO_DS"),
+        abi::R_PPC64_GOT_TPREL16_HI => Some("R_PPC64_GOT_TPREL16_HI"),
+        abi::R_PPC64_GOT_TPREL16_HA => Some("R_PPC64_GOT_TPREL16_HA"),
+        abi::R_PPC64_GOT_DTPREL16_DS => Some("R_PPC64_GOT_DTPREL16_DS"),
+        abi::R_PPC64_GOT_DTPREL16_LO_DS => Some("R_PPC64_GOT_DTPREL16_LO_DS"),
+        abi::R_PPC64_GOT_DTPREL16_HI => Some("R_PPC64_GOT_DTPREL16_HI"),
+        abi::R_PPC64_GOT_DTPREL16_HA => Some("R_PPC64_GOT_DTPREL16_HA"),
+        abi::R_PPC64_TPREL16_DS => Some("R_PPC64_TPREL16_DS"),
+        abi::R_PPC64_TPREL16_LO_DS => Some("R_PPC64_TPREL16_LO_DS"),
+        abi::R_PPC64_TPREL16_HIGHER => Some("R_PPC64_TPREL16_HIGHER"),
+        abi::R_PPC64_TPREL16_HIGHERA => Some("R_PPC64_TPREL16_HIGHERA"),
+        abi::R_PPC64_TPREL16_HIGHEST => Some("R_PPC64_TPREL16_HIGHEST"),
+        abi::R_PPC64_TPREL16_HIGHESTA => Some("R_PPC64_TPREL16_HIGHESTA"),
+        abi::R_PPC64_DTPREL16_DS => Some("R_PPC64_DTPREL16_DS"),
+        abi::R_PPC64_DTPREL16_LO_DS => Some("R_PPC64_DTPREL16_LO_DS"),
+        abi::R_PPC64_DTPREL16_HIGHER => Some("R_PPC64_DTPREL16_HIGHER"),
+        abi::R_PPC64_DTPREL16_HIGHERA => Some("R_PPC64_DTPREL16_HIGHERA"),
+        abi::R_PPC64_DTPREL16_HIGHEST => Some("R_PPC64_DTPREL16_HIGHEST"),
+        abi::R_PPC64_DTPREL16_HIGHESTA => Some("R_PPC64_DTPREL16_HIGHESTA"),
+        abi::R_PPC64_TLSGD => Some("R_PPC64_TLSGD"),
+        abi::R_PPC64_TLSLD => Some("R_PPC64_TLSLD"),
+        abi::R_PPC64_TOCSAVE => Some("R_PPC64_TOCSAVE"),
+        abi::R_PPC64_ADDR16_HIGH => Some("R_PPC64_ADDR16_HIGH"),
+        abi::R_PPC64_ADDR16_HIGHA => Some("R_PPC64_ADDR16_HIGHA"),
+        abi::R_PPC64_TPREL16_HIGH => Some("R_PPC64_TPREL16_HIGH"),
+        abi::R_PPC64_TPREL16_HIGHA => Some("R_PPC64_TPREL16_HIGHA"),
+        abi::R_PPC64_DTPREL16_HIGH => Some("R_PPC64_DTPREL16_HIGH"),
+        abi::R_PPC64_DTPREL16_HIGHA => Some("R_PPC64_DTPREL16_HIGHA"),
+        abi::R_PPC64_JMP_IREL => Some("R_PPC64_JMP_IREL"),
+        abi::R_PPC64_IRELATIVE => Some("R_PPC64_IRELATIVE"),
+        abi::R_PPC64_REL16 => Some("R_PPC64_REL16"),
+        abi::R_PPC64_REL16_LO => Some("R_PPC64_REL16_LO"),
+        abi::R_PPC64_REL16_HI => Some("R_PPC64_REL16_HI"),
+        abi::R_PPC64_REL16_HA => Some("R_PPC64_REL16_HA"),
+        _ => None,
+    }
+}
+
+fn r_riscv_to_str(r_type: u32) -> Option<&'static str> {
+    match r_type {
+        abi::R_RISCV_NONE => Some("R_RISCV_NONE"),
+        abi::R_RISCV_32 => Some("R_RISCV_32"),
+        abi::R_RISCV_64 => Some("R_RISCV_64"),
+        abi::R_RISCV_RELATIVE => Some("R_RISCV_RELATIVE"),
+        abi::R_RISCV_COPY => Some("R_RISCV_COPY"),
+        abi::R_RISCV_JUMP_SLOT => Some("R_RISCV_JUMP_SLOT"),
+        abi::R_RISCV_TLS_DTPMOD32 => Some("R_RISCV_TLS_DTPMOD32"),
+        abi::R_RISCV_TLS_DTPMOD64 => Some("R_RISCV_TLS_DTPMOD64"),
+        abi::R_RISCV_TLS_DTPREL32 => Some("R_RISCV_TLS_DTPREL32"),
+        abi::R_RISCV_TLS_DTPREL64 => Some("R_RISCV_TLS_DTPREL64"),
+        abi::R_RISCV_TLS_TPREL32 => Some("R_RISCV_TLS_TPREL32"),
+        abi::R_RISCV_TLS_TPREL64 => Some("R_RISCV_TLS_TPREL64"),
+        abi::R_RISCV_BRANCH => Some("R_RISCV_BRANCH"),
+        abi::R_RISCV_JAL => Some("R_RISCV_JAL"),
+        abi::R_RISCV_CALL => Some("R_RISCV_CALL"),
+        abi::R_RISCV_CALL_PLT => Some("R_RISCV_CALL_PLT"),
+        abi::R_RISCV_GOT_HI20 => Some("R_RISCV_GOT_HI20"),
+        abi::R_RISCV_TLS_GOT_HI20 => Some("R_RISCV_TLS_GOT_HI20"),
+        abi::R_RISCV_TLS_GD_HI20 => Some("R_RISCV_TLS_GD_HI20"),
+        abi::R_RISCV_PCREL_HI20 => Some("R_RISCV_PCREL_HI20"),
+        abi::R_RISCV_PCREL_LO12_I => Some("R_RISCV_PCREL_LO12_I"),
+        abi::R_RISCV_PCREL_LO12_S => Some("R_RISCV_PCREL_LO12_S"),
+        abi::R_RISCV_HI20 => Some("R_RISCV_HI20"),
+        abi::R_RISCV_LO12_I => Some("R_RISCV_LO12_I"),
+        abi::R_RISCV_LO12_S => Some("R_RISCV_LO12_S"),
+        abi::R_RISCV_TPREL_HI20 => Some("R_RISCV_TPREL_HI20"),
+        abi::R_RISCV_TPREL_LO12_I => Some("R_RISCV_TPREL_LO12_I"),
+        abi::R_RISCV_TPREL_LO12_S => Some("R_RISCV_TPREL_LO12_S"),
+        abi::R_RISCV_TPREL_ADD => Some("R_RISCV_TPREL_ADD"),
+        abi::R_RISCV_ADD8 => Some("R_RISCV_ADD8"),
+        abi::R_RISCV_ADD16 => Some("R_RISCV_ADD16"),
+        abi::R_RISCV_ADD32 => Some("R_RISCV_ADD32"),
+        abi::R_RISCV_ADD64 => Some("R_RISCV_ADD64"),
+        abi::R_RISCV_SUB8 => Some("R_RISCV_SUB8"),
+        abi::R_RISCV_SUB16 => Some("R_RISCV_SUB16"),
+        abi::R_RISCV_SUB32 => Some("R_RISCV_SUB32"),
+        abi::R_RISCV_SUB64 => Some("R_RISCV_SUB64"),
+        abi::R_RISCV_ALIGN => Some("R_RISCV_ALIGN"),
+        abi::R_RISCV_RVC_BRANCH => Some("R_RISCV_RVC_BRANCH"),
+        abi::R_RISCV_RVC_JUMP => Some("R_RISCV_RVC_JUMP"),
+        abi::R_RISCV_RVC_LUI => Some("R_RISCV_RVC_LUI"),
+        abi::R_RISCV_RELAX => Some("R_RISCV_RELAX"),
+        abi::R_RISCV_SUB6 => Some("R_RISCV_SUB6"),
+        abi::R_RISCV_SET6 => Some("R_RISCV_SET6"),
+        abi::R_RISCV_SET8 => Some("R_RISCV_SET8"),
+        abi::R_RISCV_SET16 => Some("R_RISCV_SET16"),
+        abi::R_RISCV_SET32 => Some("R_RISCV_SET32"),
+        abi::R_RISCV_32_PCREL => Some("R_RISCV_32_PCREL"),
+        abi::R_RISCV_IRELATIVE => Some("R_RISCV_IRELATIVE"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_by_machine() {
+        // Numbers from each psABI, names as readelf -r prints them
+        let cases = [
+            (abi::EM_X86_64, 1, "R_X86_64_64"),
+            (abi::EM_X86_64, 2, "R_X86_64_PC32"),
+            (abi::EM_X86_64, 7, "R_X86_64_JUMP_SLOT"),
+            (abi::EM_X86_64, 8, "R_X86_64_RELATIVE"),
+            (abi::EM_X86_64, 37, "R_X86_64_IRELATIVE"),
+            (abi::EM_X86_64, 42, "R_X86_64_REX_GOTPCRELX"),
+            (abi::EM_386, 1, "R_386_32"),
+            (abi::EM_386, 6, "R_386_GLOB_DAT"),
+            (abi::EM_386, 7, "R_386_JUMP_SLOT"),
+            (abi::EM_386, 8, "R_386_RELATIVE"),
+            (abi::EM_386, 43, "R_386_GOT32X"),
+            (abi::EM_AARCH64, 257, "R_AARCH64_ABS64"),
+            (abi::EM_AARCH64, 283, "R_AARCH64_CALL26"),
+            (abi::EM_AARCH64, 1025, "R_AARCH64_GLOB_DAT"),
+            (abi::EM_AARCH64, 1027, "R_AARCH64_RELATIVE"),
+            (abi::EM_ARM, 2, "R_ARM_ABS32"),
+            (abi::EM_ARM, 22, "R_ARM_JUMP_SLOT"),
+            (abi::EM_ARM, 23, "R_ARM_RELATIVE"),
+            (abi::EM_ARM, 28, "R_ARM_CALL"),
+            (abi::EM_RISCV, 2, "R_RISCV_64"),
+            (abi::EM_RISCV, 3, "R_RISCV_RELATIVE"),
+            (abi::EM_RISCV, 5, "R_RISCV_JUMP_SLOT"),
+            (abi::EM_RISCV, 19, "R_RISCV_CALL_PLT"),
+            (abi::EM_PPC64, 22, "R_PPC64_RELATIVE"),
+        ];
+        for (e_machine, r_type, name) in cases {
+            assert_eq!(r_type_to_str(e_machine, r_type), Some(name), "{}", name);
+            assert_eq!(r_type_to_string(e_machine, r_type), name);
+        }
+        // The same number means something else on each machine
+        assert_eq!(r_type_to_str(abi::EM_X86_64, 6), Some("R_X86_64_GLOB_DAT"));
+        assert_eq!(r_type_to_str(abi::EM_ARM, 6), Some("R_ARM_ABS12"));
+        assert_eq!(
+            r_type_to_str(abi::EM_RISCV, 6),
+            Some("R_RISCV_TLS_DTPMOD32")
+        );
+    }
+
+    #[test]
+    fn unknown_types_are_numbers() {
+        assert_eq!(r_type_to_str(abi::EM_X86_64, 0xfff), None);
+        assert_eq!(r_type_to_string(abi::EM_X86_64, 0xfff), "0xfff (x86-64)");
+        // A machine with no table at all
+        assert_eq!(r_type_to_str(abi::EM_S390, 1), None);
+        assert_eq!(r_type_to_string(abi::EM_S390, 1), "0x1 (IBM S/390)");
+    }
+}
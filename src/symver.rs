@@ -0,0 +1,161 @@
+//! Raw parsing of the GNU symbol versioning sections.
+//!
+//! The elf crate's iterators hide the entry offsets and the VERSYM hidden bit
+//! handling we need to print these the way readelf does, so the records are
+//! walked here directly.
+
+use elf::endian::EndianParse;
+use elf::parse::ParseError;
+use elf::string_table::StringTable;
+
+/// One Elfxx_Verneed record and its Elfxx_Vernaux entries.
+#[derive(Debug)]
+pub struct VerNeed<'data> {
+    pub offset: usize,
+    pub vn_version: u16,
+    pub file: &'data str,
+    pub aux: Vec<VerNeedAux<'data>>,
+}
+
+#[derive(Debug)]
+pub struct VerNeedAux<'data> {
+    pub offset: usize,
+    pub vna_hash: u32,
+    pub vna_flags: u16,
+    pub vna_other: u16,
+    pub name: &'data str,
+}
+
+/// One Elfxx_Verdef record and its Elfxx_Verdaux entries; the first aux
+/// entry names the version itself, the rest its parents.
+#[derive(Debug)]
+pub struct VerDef<'data> {
+    pub offset: usize,
+    pub vd_version: u16,
+    pub vd_flags: u16,
+    pub vd_ndx: u16,
+    pub vd_hash: u32,
+    pub aux: Vec<VerDefAux<'data>>,
+}
+
+#[derive(Debug)]
+pub struct VerDefAux<'data> {
+    pub offset: usize,
+    pub name: &'data str,
+}
+
+/// The VERSYM entries, one per dynamic symbol.
+pub fn parse_versym<E: EndianParse>(endian: E, data: &[u8]) -> Result<Vec<u16>, ParseError> {
+    let mut offset = 0;
+    let mut entries = Vec::with_capacity(data.len() / 2);
+    while offset + 2 <= data.len() {
+        entries.push(endian.parse_u16_at(&mut offset, data)?);
+    }
+    Ok(entries)
+}
+
+/// Walks `count` (the section's sh_info) Verneed records along their vn_next
+/// links, naming them from `strtab` (the section's sh_link).
+pub fn parse_verneed<'data, E: EndianParse>(
+    endian: E,
+    data: &[u8],
+    count: u32,
+    strtab: &StringTable<'data>,
+) -> Result<Vec<VerNeed<'data>>, ParseError> {
+    let mut needs = Vec::new();
+    let mut start = 0;
+    for _ in 0..count {
+        let mut offset = start;
+        let vn_version = endian.parse_u16_at(&mut offset, data)?;
+        let vn_cnt = endian.parse_u16_at(&mut offset, data)?;
+        let vn_file = endian.parse_u32_at(&mut offset, data)?;
+        let vn_aux = endian.parse_u32_at(&mut offset, data)?;
+        let vn_next = endian.parse_u32_at(&mut offset, data)?;
+
+        let mut aux = Vec::new();
+        let mut aux_start = start + vn_aux as usize;
+        for _ in 0..vn_cnt {
+            let mut offset = aux_start;
+            let vna_hash = endian.parse_u32_at(&mut offset, data)?;
+            let vna_flags = endian.parse_u16_at(&mut offset, data)?;
+            let vna_other = endian.parse_u16_at(&mut offset, data)?;
+            let vna_name = endian.parse_u32_at(&mut offset, data)?;
+            let vna_next = endian.parse_u32_at(&mut offset, data)?;
+            aux.push(VerNeedAux {
+                offset: aux_start,
+                vna_hash,
+                vna_flags,
+                vna_other,
+                name: strtab.get(vna_name as usize)?,
+            });
+            if vna_next == 0 {
+                break;
+            }
+            aux_start += vna_next as usize;
+        }
+
+        needs.push(VerNeed {
+            offset: start,
+            vn_version,
+            file: strtab.get(vn_file as usize)?,
+            aux,
+        });
+        if vn_next == 0 {
+            break;
+        }
+        start += vn_next as usize;
+    }
+    Ok(needs)
+}
+
+/// Walks `count` (the section's sh_info) Verdef records along their vd_next
+/// links, naming them from `strtab` (the section's sh_link).
+pub fn parse_verdef<'data, E: EndianParse>(
+    endian: E,
+    data: &[u8],
+    count: u32,
+    strtab: &StringTable<'data>,
+) -> Result<Vec<VerDef<'data>>, ParseError> {
+    let mut defs = Vec::new();
+    let mut start = 0;
+    for _ in 0..count {
+        let mut offset = start;
+        let vd_version = endian.parse_u16_at(&mut offset, data)?;
+        let vd_flags = endian.parse_u16_at(&mut offset, data)?;
+        let vd_ndx = endian.parse_u16_at(&mut offset, data)?;
+        let vd_cnt = endian.parse_u16_at(&mut offset, data)?;
+        let vd_hash = endian.parse_u32_at(&mut offset, data)?;
+        let vd_aux = endian.parse_u32_at(&mut offset, data)?;
+        let vd_next = endian.parse_u32_at(&mut offset, data)?;
+
+        let mut aux = Vec::new();
+        let mut aux_start = start + vd_aux as usize;
+        for _ in 0..vd_cnt {
+            let mut offset = aux_start;
+            let vda_name = endian.parse_u32_at(&mut offset, data)?;
+            let vda_next = endian.parse_u32_at(&mut offset, data)?;
+            aux.push(VerDefAux {
+                offset: aux_start,
+                name: strtab.get(vda_name as usize)?,
+            });
+            if vda_next == 0 {
+                break;
+            }
+            aux_start += vda_next as usize;
+        }
+
+        defs.push(VerDef {
+            offset: start,
+            vd_version,
+            vd_flags,
+            vd_ndx,
+            vd_hash,
+            aux,
+        });
+        if vd_next == 0 {
+            break;
+        }
+        start += vd_next as usize;
+    }
+    Ok(defs)
+}
@@ -0,0 +1,797 @@
+//! Human readable names for ELF constants, extending [elf::to_str] with the
+//! lookups the elf crate does not provide.
+
+pub use elf::to_str::*;
+
+use crate::unwind;
+use elf::abi;
+
+/// The e_ident[EI_DATA] byte as readelf describes it.
+pub fn ei_data_to_str(ei_data: u8) -> Option<&'static str> {
+    match ei_data {
+        abi::ELFDATA2LSB => Some("2's complement, little endian"),
+        abi::ELFDATA2MSB => Some("2's complement, big endian"),
+        _ => None,
+    }
+}
+
+pub fn ei_data_to_string(ei_data: u8) -> String {
+    match ei_data_to_str(ei_data) {
+        Some(s) => s.to_string(),
+        None => format!("<unknown>: {ei_data:#x}"),
+    }
+}
+
+pub fn e_type_to_human_string(e_type: u16) -> String {
+    match e_type_to_human_str(e_type) {
+        Some(s) => s.to_string(),
+        None => format!("<unknown>: {e_type:#x}"),
+    }
+}
+
+pub fn e_machine_to_human_string(e_machine: u16) -> String {
+    match e_machine_to_human_str(e_machine) {
+        Some(s) => s.to_string(),
+        None => format!("<unknown>: {e_machine:#x}"),
+    }
+}
+
+/// The short machine names of file(1), for --summary.
+pub fn e_machine_to_short_str(e_machine: u16) -> Option<&'static str> {
+    match e_machine {
+        abi::EM_386 => Some("Intel 80386"),
+        abi::EM_X86_64 => Some("x86-64"),
+        abi::EM_ARM => Some("ARM"),
+        abi::EM_AARCH64 => Some("ARM aarch64"),
+        abi::EM_RISCV => Some("RISC-V"),
+        abi::EM_MIPS => Some("MIPS"),
+        abi::EM_PPC => Some("PowerPC"),
+        abi::EM_PPC64 => Some("64-bit PowerPC"),
+        abi::EM_S390 => Some("IBM S/390"),
+        abi::EM_SPARCV9 => Some("SPARC V9"),
+        _ => None,
+    }
+}
+
+pub fn e_machine_to_short_string(e_machine: u16) -> String {
+    match e_machine_to_short_str(e_machine) {
+        Some(s) => s.to_string(),
+        None => e_machine_to_human_string(e_machine),
+    }
+}
+
+/// The reserved st_shndx values with a name of their own.
+pub fn st_shndx_to_str(st_shndx: u16) -> Option<&'static str> {
+    match st_shndx {
+        abi::SHN_UNDEF => Some("UND"),
+        abi::SHN_ABS => Some("ABS"),
+        abi::SHN_COMMON => Some("COM"),
+        abi::SHN_XINDEX => Some("XINDEX"),
+        _ => None,
+    }
+}
+
+/// st_shndx as readelf's Ndx column shows it: section indices as numbers,
+/// and the rest of the reserved range (from 0xff00) by what it's set aside for.
+pub fn st_shndx_to_string(st_shndx: u16) -> String {
+    match st_shndx_to_str(st_shndx) {
+        Some(s) => s.to_string(),
+        None => match st_shndx {
+            0xff00..=0xff1f => format!("PRC[{st_shndx:#06x}]"),
+            0xff20..=0xff3f => format!("OS [{st_shndx:#06x}]"),
+            0xff40.. => format!("RSV[{st_shndx:#06x}]"),
+            _ => st_shndx.to_string(),
+        },
+    }
+}
+
+/// e_flags in hex followed by the bits readelf decodes for the machine,
+/// as in "0x5, RVC, double-float ABI". Zero flags and machines without a
+/// decoding are shown as the hex alone.
+pub fn e_flags_to_string(e_machine: u16, e_flags: u32) -> String {
+    let words = match e_machine {
+        _ if e_flags == 0 => Vec::new(),
+        abi::EM_ARM => arm_e_flags(e_flags),
+        abi::EM_RISCV => riscv_e_flags(e_flags),
+        abi::EM_MIPS => mips_e_flags(e_flags),
+        _ => Vec::new(),
+    };
+    let mut string = format!("{e_flags:#x}");
+    for word in words {
+        string.push_str(", ");
+        string.push_str(&word);
+    }
+    string
+}
+
+fn arm_e_flags(e_flags: u32) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut known = abi::EF_ARM_EABIMASK;
+    match e_flags & abi::EF_ARM_EABIMASK {
+        abi::EF_ARM_EABI_UNKNOWN => words.push("GNU EABI".to_string()),
+        abi::EF_ARM_EABI_VER1 => words.push("Version1 EABI".to_string()),
+        abi::EF_ARM_EABI_VER2 => words.push("Version2 EABI".to_string()),
+        abi::EF_ARM_EABI_VER3 => words.push("Version3 EABI".to_string()),
+        abi::EF_ARM_EABI_VER4 => words.push("Version4 EABI".to_string()),
+        abi::EF_ARM_EABI_VER5 => {
+            words.push("Version5 EABI".to_string());
+            known |= abi::EF_ARM_ABI_FLOAT_SOFT | abi::EF_ARM_ABI_FLOAT_HARD;
+            if e_flags & abi::EF_ARM_ABI_FLOAT_SOFT != 0 {
+                words.push("soft-float ABI".to_string());
+            }
+            if e_flags & abi::EF_ARM_ABI_FLOAT_HARD != 0 {
+                words.push("hard-float ABI".to_string());
+            }
+        }
+        _ => words.push("<unrecognized EABI>".to_string()),
+    }
+    known |= abi::EF_ARM_BE8;
+    if e_flags & abi::EF_ARM_BE8 != 0 {
+        words.push("BE8".to_string());
+    }
+    if e_flags & !known != 0 {
+        words.push("<unknown>".to_string());
+    }
+    words
+}
+
+fn riscv_e_flags(e_flags: u32) -> Vec<String> {
+    let mut words = Vec::new();
+    if e_flags & abi::EF_RISCV_RVC != 0 {
+        words.push("RVC".to_string());
+    }
+    if e_flags & abi::EF_RISCV_RVE != 0 {
+        words.push("RVE".to_string());
+    }
+    if e_flags & abi::EF_RISCV_TSO != 0 {
+        words.push("TSO".to_string());
+    }
+    words.push(
+        match e_flags & abi::EF_RISCV_FLOAT_ABI_MASK {
+            abi::EF_RISCV_FLOAT_ABI_SOFT => "soft-float ABI",
+            abi::EF_RISCV_FLOAT_ABI_SINGLE => "single-float ABI",
+            abi::EF_RISCV_FLOAT_ABI_DOUBLE => "double-float ABI",
+            _ => "quad-float ABI",
+        }
+        .to_string(),
+    );
+    let known =
+        abi::EF_RISCV_RVC | abi::EF_RISCV_RVE | abi::EF_RISCV_TSO | abi::EF_RISCV_FLOAT_ABI_MASK;
+    if e_flags & !known != 0 {
+        words.push("<unknown>".to_string());
+    }
+    words
+}
+
+/// elf::abi has no EF_MIPS_* constants.
+const MIPS_FLAGS: [(u32, &str); 6] = [
+    (0x0000_0001, "noreorder"),
+    (0x0000_0002, "pic"),
+    (0x0000_0004, "cpic"),
+    (0x0000_0020, "abi2"),
+    (0x0000_0200, "fp64"),
+    (0x0000_0400, "nan2008"),
+];
+const EF_MIPS_ABI: u32 = 0x0000_f000;
+const EF_MIPS_ARCH: u32 = 0xf000_0000;
+
+fn mips_e_flags(e_flags: u32) -> Vec<String> {
+    let mut words: Vec<String> = MIPS_FLAGS
+        .iter()
+        .filter(|(flag, _)| e_flags & flag != 0)
+        .map(|(_, word)| word.to_string())
+        .collect();
+    match (e_flags & EF_MIPS_ABI) >> 12 {
+        0 => (),
+        1 => words.push("o32".to_string()),
+        2 => words.push("o64".to_string()),
+        3 => words.push("eabi32".to_string()),
+        4 => words.push("eabi64".to_string()),
+        _ => words.push("unknown ABI".to_string()),
+    }
+    let arch = match (e_flags & EF_MIPS_ARCH) >> 28 {
+        0 => "mips1",
+        1 => "mips2",
+        2 => "mips3",
+        3 => "mips4",
+        4 => "mips5",
+        5 => "mips32",
+        6 => "mips64",
+        7 => "mips32r2",
+        8 => "mips64r2",
+        9 => "mips32r6",
+        10 => "mips64r6",
+        _ => "unknown ISA",
+    };
+    words.push(arch.to_string());
+    words
+}
+
+/// Filter tags from Solaris that glibc honours but the elf crate doesn't name.
+pub const DT_AUXILIARY: i64 = 0x7fff_fffd;
+pub const DT_FILTER: i64 = 0x7fff_ffff;
+
+/// The packed relative relocation tags, newer than the elf crate.
+pub const DT_RELRSZ: i64 = 35;
+pub const DT_RELR: i64 = 36;
+pub const DT_RELRENT: i64 = 37;
+
+pub fn d_tag_to_str(d_tag: i64) -> Option<&'static str> {
+    match d_tag {
+        DT_AUXILIARY => Some("DT_AUXILIARY"),
+        DT_FILTER => Some("DT_FILTER"),
+        DT_RELRSZ => Some("DT_RELRSZ"),
+        DT_RELR => Some("DT_RELR"),
+        DT_RELRENT => Some("DT_RELRENT"),
+        _ => elf::to_str::d_tag_to_str(d_tag),
+    }
+}
+
+pub fn d_tag_to_string(d_tag: i64) -> String {
+    match d_tag_to_str(d_tag) {
+        Some(s) => s.to_string(),
+        None => format!("{d_tag:#x}"),
+    }
+}
+
+/// DT_FLAGS bits, in readelf's words.
+const DF_FLAGS: [(i64, &str); 5] = [
+    (abi::DF_ORIGIN, "ORIGIN"),
+    (abi::DF_SYMBOLIC, "SYMBOLIC"),
+    (abi::DF_TEXTREL, "TEXTREL"),
+    (abi::DF_BIND_NOW, "BIND_NOW"),
+    (abi::DF_STATIC_TLS, "STATIC_TLS"),
+];
+
+/// DT_FLAGS_1 bits, in readelf's words.
+const DF_1_FLAGS: [(i64, &str); 31] = [
+    (abi::DF_1_NOW, "NOW"),
+    (abi::DF_1_GLOBAL, "GLOBAL"),
+    (abi::DF_1_GROUP, "GROUP"),
+    (abi::DF_1_NODELETE, "NODELETE"),
+    (abi::DF_1_LOADFLTR, "LOADFLTR"),
+    (abi::DF_1_INITFIRST, "INITFIRST"),
+    (abi::DF_1_NOOPEN, "NOOPEN"),
+    (abi::DF_1_ORIGIN, "ORIGIN"),
+    (abi::DF_1_DIRECT, "DIRECT"),
+    (abi::DF_1_TRANS, "TRANS"),
+    (abi::DF_1_INTERPOSE, "INTERPOSE"),
+    (abi::DF_1_NODEFLIB, "NODEFLIB"),
+    (abi::DF_1_NODUMP, "NODUMP"),
+    (abi::DF_1_CONFALT, "CONFALT"),
+    (abi::DF_1_ENDFILTEE, "ENDFILTEE"),
+    (abi::DF_1_DISPRELDNE, "DISPRELDNE"),
+    (abi::DF_1_DISPRELPND, "DISPRELPND"),
+    (abi::DF_1_NODIRECT, "NODIRECT"),
+    (abi::DF_1_IGNMULDEF, "IGNMULDEF"),
+    (abi::DF_1_NOKSYMS, "NOKSYMS"),
+    (abi::DF_1_NOHDR, "NOHDR"),
+    (abi::DF_1_EDITED, "EDITED"),
+    (abi::DF_1_NORELOC, "NORELOC"),
+    (abi::DF_1_SYMINTPOSE, "SYMINTPOSE"),
+    (abi::DF_1_GLOBAUDIT, "GLOBAUDIT"),
+    (abi::DF_1_SINGLETON, "SINGLETON"),
+    (abi::DF_1_STUB, "STUB"),
+    (abi::DF_1_PIE, "PIE"),
+    (abi::DF_1_KMOD, "KMOD"),
+    (abi::DF_1_WEAKFILTER, "WEAKFILTER"),
+    (abi::DF_1_NOCOMMON, "NOCOMMON"),
+];
+
+/// The names of the DT_FLAGS bits set in `d_val`, with any bits left over
+/// in hex so a flag newer than this table still shows.
+pub fn df_flags_to_string(d_val: u64) -> String {
+    flag_names(d_val, &DF_FLAGS)
+}
+
+/// As [`df_flags_to_string`], for DT_FLAGS_1.
+pub fn df_1_flags_to_string(d_val: u64) -> String {
+    flag_names(d_val, &DF_1_FLAGS)
+}
+
+fn flag_names(value: u64, table: &[(i64, &str)]) -> String {
+    let mut names: Vec<String> = table
+        .iter()
+        .filter(|&&(flag, _)| value & flag as u64 != 0)
+        .map(|&(_, name)| name.to_string())
+        .collect();
+    let unknown = value
+        & !table
+            .iter()
+            .fold(0, |known, &(flag, _)| known | flag as u64);
+    if unknown != 0 {
+        names.push(format!("{:#x}", unknown));
+    }
+    names.join(" ")
+}
+
+pub fn note_type_to_str(owner: &str, n_type: u64) -> Option<&'static str> {
+    match owner {
+        abi::ELF_NOTE_GNU => match n_type {
+            abi::NT_GNU_ABI_TAG => Some("NT_GNU_ABI_TAG (ABI version tag)"),
+            abi::NT_GNU_HWCAP => Some("NT_GNU_HWCAP (DSO-supplied software HWCAP info)"),
+            abi::NT_GNU_BUILD_ID => Some("NT_GNU_BUILD_ID (unique build ID bitstring)"),
+            abi::NT_GNU_GOLD_VERSION => Some("NT_GNU_GOLD_VERSION (gold version)"),
+            abi::NT_GNU_PROPERTY_TYPE_0 => Some("NT_GNU_PROPERTY_TYPE_0"),
+            _ => None,
+        },
+        // The process state notes of core dumps
+        "CORE" | "LINUX" => match n_type {
+            abi::NT_PRSTATUS => Some("NT_PRSTATUS (prstatus structure)"),
+            abi::NT_FPREGSET => Some("NT_FPREGSET (floating point registers)"),
+            abi::NT_PRPSINFO => Some("NT_PRPSINFO (prpsinfo structure)"),
+            abi::NT_AUXV => Some("NT_AUXV (auxiliary vector)"),
+            abi::NT_SIGINFO => Some("NT_SIGINFO (siginfo_t data)"),
+            abi::NT_FILE => Some("NT_FILE (mapped files)"),
+            abi::NT_X86_XSTATE => Some("NT_X86_XSTATE (x86 XSAVE extended state)"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn note_type_to_string(owner: &str, n_type: u64) -> String {
+    match note_type_to_str(owner, n_type) {
+        Some(s) => s.to_string(),
+        None => format!("Unknown note type: ({n_type:#010x})"),
+    }
+}
+
+pub fn ver_flags_to_string(flags: u16) -> String {
+    if flags == 0 {
+        return "none".to_string();
+    }
+    let mut names = Vec::new();
+    if flags & abi::VER_FLG_BASE != 0 {
+        names.push("BASE");
+    }
+    if flags & abi::VER_FLG_WEAK != 0 {
+        names.push("WEAK");
+    }
+    if flags & abi::VER_FLG_INFO != 0 {
+        names.push("INFO");
+    }
+    if flags & !(abi::VER_FLG_BASE | abi::VER_FLG_WEAK | abi::VER_FLG_INFO) != 0 {
+        names.push("<unknown>");
+    }
+    names.join(" | ")
+}
+
+/// GNU's SHF_EXCLUDE, which the elf crate doesn't define.
+const SHF_EXCLUDE: u64 = 0x8000_0000;
+
+/// The sh_flags bits with the letters readelf -S shows them as and the
+/// words readelf -t spells them as.
+const SH_FLAGS: [(u64, char, &str); 12] = [
+    (abi::SHF_WRITE as u64, 'W', "WRITE"),
+    (abi::SHF_ALLOC as u64, 'A', "ALLOC"),
+    (abi::SHF_EXECINSTR as u64, 'X', "EXECINSTR"),
+    (abi::SHF_MERGE as u64, 'M', "MERGE"),
+    (abi::SHF_STRINGS as u64, 'S', "STRINGS"),
+    (abi::SHF_INFO_LINK as u64, 'I', "INFO LINK"),
+    (abi::SHF_LINK_ORDER as u64, 'L', "LINK ORDER"),
+    (abi::SHF_OS_NONCONFORMING as u64, 'O', "OS NONCONF"),
+    (abi::SHF_GROUP as u64, 'G', "GROUP"),
+    (abi::SHF_TLS as u64, 'T', "TLS"),
+    (abi::SHF_COMPRESSED as u64, 'C', "COMPRESSED"),
+    (SHF_EXCLUDE, 'E', "EXCLUDE"),
+];
+
+const SHF_MASKOS: u64 = 0x0ff0_0000;
+const SHF_MASKPROC: u64 = 0xf000_0000;
+
+/// What the sh_flags letters stand for, as readelf explains them below -S.
+pub const SH_FLAGS_KEY: &str = "\
+Key to Flags:
+  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),
+  L (link order), O (extra OS processing required), G (group), T (TLS),
+  C (compressed), x (unknown), o (OS specific), E (exclude),
+  p (processor specific)";
+
+/// The set sh_flags bits as readelf's letters, e.g. "WA". Bits without a
+/// letter of their own show as o (OS specific), p (processor specific) or
+/// x (unknown).
+pub fn sh_flags_to_string(sh_flags: u64) -> String {
+    let mut letters: String = SH_FLAGS
+        .iter()
+        .filter(|(flag, _, _)| sh_flags & flag != 0)
+        .map(|(_, letter, _)| *letter)
+        .collect();
+    let unknown = sh_flags & !SH_FLAGS.iter().fold(0, |known, (flag, _, _)| known | flag);
+    if unknown & SHF_MASKOS != 0 {
+        letters.push('o');
+    }
+    if unknown & SHF_MASKPROC != 0 {
+        letters.push('p');
+    }
+    if unknown & !(SHF_MASKOS | SHF_MASKPROC) != 0 {
+        letters.push('x');
+    }
+    letters
+}
+
+/// The sh_flags bits without a letter of their own, by kind, as in
+/// "OS specific: 0x100000"; None when every set bit has a letter.
+pub fn sh_flags_note(sh_flags: u64) -> Option<String> {
+    let extra = sh_flags & !SH_FLAGS.iter().fold(0, |known, (flag, _, _)| known | flag);
+    let notes: Vec<String> = [
+        (SHF_MASKOS, "OS specific"),
+        (SHF_MASKPROC, "processor specific"),
+        (!(SHF_MASKOS | SHF_MASKPROC), "unknown"),
+    ]
+    .iter()
+    .filter(|&&(mask, _)| extra & mask != 0)
+    .map(|&(mask, kind)| format!("{}: {:#x}", kind, extra & mask))
+    .collect();
+    (!notes.is_empty()).then(|| notes.join(", "))
+}
+
+/// The set sh_flags bits as comma separated words, with any bits left over
+/// shown as UNKNOWN (0x..).
+pub fn sh_flags_to_words(sh_flags: u64) -> String {
+    let mut words: Vec<String> = SH_FLAGS
+        .iter()
+        .filter(|(flag, _, _)| sh_flags & flag != 0)
+        .map(|(_, _, word)| word.to_string())
+        .collect();
+    let known = SH_FLAGS.iter().fold(0, |known, (flag, _, _)| known | flag);
+    if sh_flags & !known != 0 {
+        words.push(format!("UNKNOWN ({:#x})", sh_flags & !known));
+    }
+    words.join(", ")
+}
+
+/// The p_flags permissions as readelf's fixed three columns, e.g. "R E"
+/// or "RW ". Other bits don't show here; see [`p_flags_note`].
+pub fn p_flags_to_string(p_flags: u32) -> String {
+    [(abi::PF_R, 'R'), (abi::PF_W, 'W'), (abi::PF_X, 'E')]
+        .iter()
+        .map(|&(flag, letter)| if p_flags & flag != 0 { letter } else { ' ' })
+        .collect()
+}
+
+/// The p_flags bits beyond R, W and E, e.g. "OS specific: 0x100000", or
+/// None when there are none.
+pub fn p_flags_note(p_flags: u32) -> Option<String> {
+    let extra = p_flags & !(abi::PF_R | abi::PF_W | abi::PF_X);
+    let notes: Vec<String> = [
+        (abi::PF_MASKOS, "OS specific"),
+        (abi::PF_MASKPROC, "processor specific"),
+        (!(abi::PF_MASKOS | abi::PF_MASKPROC), "unknown"),
+    ]
+    .iter()
+    .filter(|&&(mask, _)| extra & mask != 0)
+    .map(|&(mask, kind)| format!("{}: {:#x}", kind, extra & mask))
+    .collect();
+    (!notes.is_empty()).then(|| notes.join(", "))
+}
+
+pub fn ch_type_to_str(ch_type: u32) -> Option<&'static str> {
+    match ch_type {
+        abi::ELFCOMPRESS_ZLIB => Some("ZLIB"),
+        abi::ELFCOMPRESS_ZSTD => Some("ZSTD"),
+        _ => None,
+    }
+}
+
+pub fn ch_type_to_string(ch_type: u32) -> String {
+    match ch_type_to_str(ch_type) {
+        Some(s) => s.to_string(),
+        None => format!("<unknown>: {ch_type:#x}"),
+    }
+}
+
+/// A DW_EH_PE pointer encoding as its application and format, e.g.
+/// "pcrel sdata4".
+pub fn eh_pe_to_string(encoding: u8) -> String {
+    if encoding == unwind::DW_EH_PE_OMIT {
+        return "omit".to_string();
+    }
+    let format = match encoding & 0x0f {
+        unwind::DW_EH_PE_ABSPTR => "absptr".to_string(),
+        unwind::DW_EH_PE_ULEB128 => "uleb128".to_string(),
+        unwind::DW_EH_PE_UDATA2 => "udata2".to_string(),
+        unwind::DW_EH_PE_UDATA4 => "udata4".to_string(),
+        unwind::DW_EH_PE_UDATA8 => "udata8".to_string(),
+        unwind::DW_EH_PE_SLEB128 => "sleb128".to_string(),
+        unwind::DW_EH_PE_SDATA2 => "sdata2".to_string(),
+        unwind::DW_EH_PE_SDATA4 => "sdata4".to_string(),
+        unwind::DW_EH_PE_SDATA8 => "sdata8".to_string(),
+        format => format!("<unknown format: {format:#x}>"),
+    };
+    let application = match encoding & 0x70 {
+        0 => None,
+        unwind::DW_EH_PE_PCREL => Some("pcrel".to_string()),
+        unwind::DW_EH_PE_TEXTREL => Some("textrel".to_string()),
+        unwind::DW_EH_PE_DATAREL => Some("datarel".to_string()),
+        unwind::DW_EH_PE_FUNCREL => Some("funcrel".to_string()),
+        unwind::DW_EH_PE_ALIGNED => Some("aligned".to_string()),
+        application => Some(format!("<unknown application: {application:#x}>")),
+    };
+    let mut words: Vec<String> = Vec::new();
+    if encoding & unwind::DW_EH_PE_INDIRECT != 0 {
+        words.push("indirect".to_string());
+    }
+    words.extend(application);
+    words.push(format);
+    words.join(" ")
+}
+
+pub fn dw_ut_to_str(unit_type: u8) -> Option<&'static str> {
+    match unit_type {
+        0x01 => Some("DW_UT_compile"),
+        0x02 => Some("DW_UT_type"),
+        0x03 => Some("DW_UT_partial"),
+        0x04 => Some("DW_UT_skeleton"),
+        0x05 => Some("DW_UT_split_compile"),
+        0x06 => Some("DW_UT_split_type"),
+        _ => None,
+    }
+}
+
+pub fn dw_ut_to_string(unit_type: u8) -> String {
+    match dw_ut_to_str(unit_type) {
+        Some(s) => s.to_string(),
+        None => format!("<unknown>: {unit_type:#x}"),
+    }
+}
+
+/// A DW_LANG_* code as readelf names the language.
+pub fn dw_lang_to_str(language: u64) -> Option<&'static str> {
+    match language {
+        0x0001 => Some("ANSI C"),
+        0x0002 => Some("K&R C"),
+        0x0003 => Some("Ada 83"),
+        0x0004 => Some("C++"),
+        0x0005 => Some("Cobol 74"),
+        0x0006 => Some("Cobol 85"),
+        0x0007 => Some("FORTRAN 77"),
+        0x0008 => Some("Fortran 90"),
+        0x0009 => Some("Pascal 83"),
+        0x000a => Some("Modula 2"),
+        0x000b => Some("Java"),
+        0x000c => Some("ANSI C99"),
+        0x000d => Some("ADA 95"),
+        0x000e => Some("Fortran 95"),
+        0x000f => Some("PLI"),
+        0x0010 => Some("Objective C"),
+        0x0011 => Some("Objective C++"),
+        0x0012 => Some("Unified Parallel C"),
+        0x0013 => Some("D"),
+        0x0014 => Some("Python"),
+        0x0015 => Some("OpenCL"),
+        0x0016 => Some("Go"),
+        0x0017 => Some("Modula 3"),
+        0x0018 => Some("Haskell"),
+        0x0019 => Some("C++03"),
+        0x001a => Some("C++11"),
+        0x001b => Some("OCaml"),
+        0x001c => Some("Rust"),
+        0x001d => Some("C11"),
+        0x001e => Some("Swift"),
+        0x001f => Some("Julia"),
+        0x0020 => Some("Dylan"),
+        0x0021 => Some("C++14"),
+        0x0022 => Some("Fortran 03"),
+        0x0023 => Some("Fortran 08"),
+        0x0024 => Some("RenderScript"),
+        0x0025 => Some("BLISS"),
+        0x8001 => Some("MIPS assembler"),
+        _ => None,
+    }
+}
+
+pub fn dw_lang_to_string(language: u64) -> String {
+    match dw_lang_to_str(language) {
+        Some(s) => s.to_string(),
+        None => format!("<unknown>: {language:#x}"),
+    }
+}
+
+pub fn arm_attr_tag_to_str(tag: u64) -> Option<&'static str> {
+    match tag {
+        4 => Some("Tag_CPU_raw_name"),
+        5 => Some("Tag_CPU_name"),
+        6 => Some("Tag_CPU_arch"),
+        7 => Some("Tag_CPU_arch_profile"),
+        8 => Some("Tag_ARM_ISA_use"),
+        9 => Some("Tag_THUMB_ISA_use"),
+        10 => Some("Tag_FP_arch"),
+        11 => Some("Tag_WMMX_arch"),
+        12 => Some("Tag_Advanced_SIMD_arch"),
+        13 => Some("Tag_PCS_config"),
+        14 => Some("Tag_ABI_PCS_R9_use"),
+        15 => Some("Tag_ABI_PCS_RW_data"),
+        16 => Some("Tag_ABI_PCS_RO_data"),
+        17 => Some("Tag_ABI_PCS_GOT_use"),
+        18 => Some("Tag_ABI_PCS_wchar_t"),
+        19 => Some("Tag_ABI_FP_rounding"),
+        20 => Some("Tag_ABI_FP_denormal"),
+        21 => Some("Tag_ABI_FP_exceptions"),
+        22 => Some("Tag_ABI_FP_user_exceptions"),
+        23 => Some("Tag_ABI_FP_number_model"),
+        24 => Some("Tag_ABI_align_needed"),
+        25 => Some("Tag_ABI_align_preserved"),
+        26 => Some("Tag_ABI_enum_size"),
+        27 => Some("Tag_ABI_HardFP_use"),
+        28 => Some("Tag_ABI_VFP_args"),
+        29 => Some("Tag_ABI_WMMX_args"),
+        30 => Some("Tag_ABI_optimization_goals"),
+        31 => Some("Tag_ABI_FP_optimization_goals"),
+        32 => Some("Tag_compatibility"),
+        34 => Some("Tag_CPU_unaligned_access"),
+        36 => Some("Tag_FP_HP_extension"),
+        38 => Some("Tag_ABI_FP_16bit_format"),
+        42 => Some("Tag_MPextension_use"),
+        44 => Some("Tag_DIV_use"),
+        46 => Some("Tag_DSP_extension"),
+        64 => Some("Tag_nodefaults"),
+        65 => Some("Tag_also_compatible_with"),
+        66 => Some("Tag_T2EE_use"),
+        67 => Some("Tag_conformance"),
+        68 => Some("Tag_Virtualization_use"),
+        70 => Some("Tag_MPextension_use_legacy"),
+        _ => None,
+    }
+}
+
+/// The names readelf gives the values of the enumerated "aeabi" tags.
+fn arm_attr_values(tag: u64) -> &'static [&'static str] {
+    const NOT_ALLOWED: &[&str] = &["Not Allowed", "Allowed"];
+    const UNUSED_NEEDED: &[&str] = &["Unused", "Needed"];
+    match tag {
+        6 => &[
+            "Pre-v4",
+            "v4",
+            "v4T",
+            "v5T",
+            "v5TE",
+            "v5TEJ",
+            "v6",
+            "v6KZ",
+            "v6T2",
+            "v6K",
+            "v7",
+            "v6-M",
+            "v6S-M",
+            "v7E-M",
+            "v8",
+            "v8-R",
+            "v8-M.baseline",
+            "v8-M.mainline",
+            "v8.1-A",
+            "v8.2-A",
+            "v8.3-A",
+            "v8.1-M.mainline",
+            "v9",
+        ],
+        8 => &["No", "Yes"],
+        9 => &["No", "Thumb-1", "Thumb-2", "Yes"],
+        10 => &[
+            "No",
+            "VFPv1",
+            "VFPv2",
+            "VFPv3",
+            "VFPv3-D16",
+            "VFPv4",
+            "VFPv4-D16",
+            "FP for ARMv8",
+            "FPv5/FP-D16 for ARMv8",
+        ],
+        11 => &["No", "WMMXv1", "WMMXv2"],
+        12 => &[
+            "No",
+            "NEONv1",
+            "NEONv1 with Fused-MAC",
+            "NEON for ARMv8",
+            "NEON for ARMv8.1",
+        ],
+        13 => &[
+            "None",
+            "Bare platform",
+            "Linux application",
+            "Linux DSO",
+            "PalmOS 2004",
+            "PalmOS (reserved)",
+            "SymbianOS 2004",
+            "SymbianOS (reserved)",
+        ],
+        14 => &["V6", "SB", "TLS", "Unused"],
+        15 => &["Absolute", "PC-relative", "SB-relative", "None"],
+        16 => &["Absolute", "PC-relative", "None"],
+        17 => &["None", "direct", "GOT-indirect"],
+        18 => &["None", "??? 1", "2", "??? 3", "4"],
+        19 | 21 | 22 => UNUSED_NEEDED,
+        20 => &["Unused", "Needed", "Sign only"],
+        23 => &["Unused", "Finite", "RTABI", "IEEE 754"],
+        24 => &["None", "8-byte", "4-byte", "??? 3"],
+        25 => &["None", "8-byte, except leaf SP", "8-byte", "??? 3"],
+        26 => &["Unused", "small", "int", "forced to int"],
+        27 => &["As Tag_FP_arch", "SP only", "Reserved", "Deprecated"],
+        28 => &["AAPCS", "VFP registers", "custom", "compatible"],
+        29 => &["AAPCS", "WMMX registers", "custom"],
+        30 => &[
+            "None",
+            "Prefer Speed",
+            "Aggressive Speed",
+            "Prefer Size",
+            "Aggressive Size",
+            "Prefer Debug",
+            "Aggressive Debug",
+        ],
+        31 => &[
+            "None",
+            "Prefer Speed",
+            "Aggressive Speed",
+            "Prefer Size",
+            "Aggressive Size",
+            "Prefer Accuracy",
+            "Aggressive Accuracy",
+        ],
+        34 => &["None", "v6"],
+        36 | 42 | 66 | 70 => NOT_ALLOWED,
+        38 => &["None", "IEEE 754", "Alternative Format"],
+        44 => &[
+            "Allowed in Thumb-ISA, v7-R or v7-M",
+            "Not allowed",
+            "Allowed in v7-A with integer division extension",
+        ],
+        46 => &["Follow architecture", "Allowed"],
+        68 => &[
+            "Not Allowed",
+            "TrustZone",
+            "Virtualization Extensions",
+            "TrustZone and Virtualization Extensions",
+        ],
+        _ => &[],
+    }
+}
+
+pub fn arm_attr_value_to_str(tag: u64, value: u64) -> Option<&'static str> {
+    match (tag, value) {
+        // Tag_CPU_arch_profile holds a character rather than an index
+        (7, 0) => Some("None"),
+        (7, 0x41) => Some("Application"),
+        (7, 0x52) => Some("Realtime"),
+        (7, 0x4d) => Some("Microcontroller"),
+        (7, 0x53) => Some("Application or Realtime"),
+        (7, _) => None,
+        _ => arm_attr_values(tag).get(value as usize).copied(),
+    }
+}
+
+pub fn arm_attr_value_to_string(tag: u64, value: u64) -> String {
+    match arm_attr_value_to_str(tag, value) {
+        Some(s) => s.to_string(),
+        // Tag_ABI_align_needed/_preserved encode larger alignments as log2
+        None if matches!(tag, 24 | 25) && (4..=12).contains(&value) => {
+            format!("8-byte and up to {}-byte extended", 1u64 << value)
+        }
+        None if tag == 7 || !arm_attr_values(tag).is_empty() => format!("??? ({})", value),
+        None => format!("{} ({:#x})", value, value),
+    }
+}
+
+pub fn riscv_attr_tag_to_str(tag: u64) -> Option<&'static str> {
+    match tag {
+        4 => Some("Tag_RISCV_stack_align"),
+        5 => Some("Tag_RISCV_arch"),
+        6 => Some("Tag_RISCV_unaligned_access"),
+        8 => Some("Tag_RISCV_priv_spec"),
+        10 => Some("Tag_RISCV_priv_spec_minor"),
+        12 => Some("Tag_RISCV_priv_spec_revision"),
+        14 => Some("Tag_RISCV_atomic_abi"),
+        16 => Some("Tag_RISCV_x3_reg_usage"),
+        _ => None,
+    }
+}
+
+pub fn riscv_attr_value_to_string(tag: u64, value: u64) -> String {
+    match (tag, value) {
+        (4, _) => format!("{}-bytes", value),
+        (6, 0) => "No unaligned access".to_string(),
+        (6, 1) => "Unaligned access".to_string(),
+        (8 | 10 | 12, _) => value.to_string(),
+        _ => format!("{} ({:#x})", value, value),
+    }
+}
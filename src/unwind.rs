@@ -0,0 +1,137 @@
+//! Parsing of .eh_frame_hdr, the sorted lookup table from function start
+//! addresses to their .eh_frame FDEs that -u prints.
+//!
+//! Every pointer in it is stored with a DW_EH_PE encoding byte: the low
+//! nibble gives the value's format, the high one what it is relative to.
+
+use crate::attributes;
+use elf::endian::EndianParse;
+use elf::file::Class;
+use elf::parse::ParseError;
+
+/// The only .eh_frame_hdr version in use.
+const VERSION: u8 = 1;
+
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0a;
+pub const DW_EH_PE_SDATA4: u8 = 0x0b;
+pub const DW_EH_PE_SDATA8: u8 = 0x0c;
+
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_TEXTREL: u8 = 0x20;
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+pub const DW_EH_PE_FUNCREL: u8 = 0x40;
+pub const DW_EH_PE_ALIGNED: u8 = 0x50;
+/// The value is the address of the pointer rather than the pointer itself.
+pub const DW_EH_PE_INDIRECT: u8 = 0x80;
+/// No value follows.
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+
+#[derive(Debug)]
+pub struct EhFrameHdr {
+    pub version: u8,
+    pub eh_frame_ptr_enc: u8,
+    pub fde_count_enc: u8,
+    pub table_enc: u8,
+    /// The address of .eh_frame, with the encoding applied.
+    pub eh_frame_ptr: u64,
+    pub fde_count: u64,
+    /// (initial PC, FDE address) pairs sorted by PC, both absolute.
+    pub table: Vec<(u64, u64)>,
+}
+
+/// Parses the .eh_frame_hdr section loaded at `address`.
+pub fn parse_eh_frame_hdr<E: EndianParse>(
+    endian: E,
+    class: Class,
+    data: &[u8],
+    address: u64,
+) -> Result<EhFrameHdr, ParseError> {
+    let header = data.get(..4).ok_or(ParseError::BadOffset(0))?;
+    let (version, eh_frame_ptr_enc, fde_count_enc, table_enc) =
+        (header[0], header[1], header[2], header[3]);
+    if version != VERSION {
+        return Err(ParseError::UnsupportedVersion((
+            version as u64,
+            VERSION as u64,
+        )));
+    }
+    let pointer = Pointer {
+        endian,
+        class,
+        data,
+        address,
+    };
+    let mut offset = 4;
+    let eh_frame_ptr = pointer.read(eh_frame_ptr_enc, &mut offset)?.unwrap_or(0);
+    let fde_count = pointer.read(fde_count_enc, &mut offset)?.unwrap_or(0);
+    let mut table = Vec::new();
+    if table_enc != DW_EH_PE_OMIT {
+        for _ in 0..fde_count {
+            let pc = pointer.read(table_enc, &mut offset)?.unwrap_or(0);
+            let fde = pointer.read(table_enc, &mut offset)?.unwrap_or(0);
+            table.push((pc, fde));
+        }
+    }
+    Ok(EhFrameHdr {
+        version,
+        eh_frame_ptr_enc,
+        fde_count_enc,
+        table_enc,
+        eh_frame_ptr,
+        fde_count,
+        table,
+    })
+}
+
+/// What encoded pointers are read from and relative to.
+struct Pointer<'data, E: EndianParse> {
+    endian: E,
+    class: Class,
+    data: &'data [u8],
+    address: u64,
+}
+
+impl<E: EndianParse> Pointer<'_, E> {
+    /// The value at `offset` with `encoding` applied, or None for DW_EH_PE_omit.
+    fn read(&self, encoding: u8, offset: &mut usize) -> Result<Option<u64>, ParseError> {
+        if encoding == DW_EH_PE_OMIT {
+            return Ok(None);
+        }
+        let start = *offset;
+        let (endian, data) = (self.endian, self.data);
+        let value = match encoding & 0x0f {
+            DW_EH_PE_ABSPTR => match self.class {
+                Class::ELF32 => endian.parse_u32_at(offset, data)? as u64,
+                Class::ELF64 => endian.parse_u64_at(offset, data)?,
+            },
+            DW_EH_PE_ULEB128 => attributes::read_uleb128(data, offset)?,
+            DW_EH_PE_UDATA2 => endian.parse_u16_at(offset, data)? as u64,
+            DW_EH_PE_UDATA4 => endian.parse_u32_at(offset, data)? as u64,
+            DW_EH_PE_UDATA8 => endian.parse_u64_at(offset, data)?,
+            DW_EH_PE_SLEB128 => attributes::read_sleb128(data, offset)? as u64,
+            DW_EH_PE_SDATA2 => endian.parse_u16_at(offset, data)? as i16 as u64,
+            DW_EH_PE_SDATA4 => endian.parse_i32_at(offset, data)? as u64,
+            DW_EH_PE_SDATA8 => endian.parse_i64_at(offset, data)? as u64,
+            _ => return Err(ParseError::BadOffset(start as u64)),
+        };
+        let base = match encoding & 0x70 {
+            0 => 0,
+            DW_EH_PE_PCREL => self.address.wrapping_add(start as u64),
+            // In .eh_frame_hdr data-relative means from the start of the section
+            DW_EH_PE_DATAREL => self.address,
+            // Text- and function-relative need a base the header doesn't give,
+            // and indirect values need memory; linkers emit none of them here
+            _ => return Err(ParseError::BadOffset(start as u64)),
+        };
+        if encoding & DW_EH_PE_INDIRECT != 0 {
+            return Err(ParseError::BadOffset(start as u64));
+        }
+        Ok(Some(base.wrapping_add(value)))
+    }
+}
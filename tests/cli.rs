@@ -0,0 +1,796 @@
+//! Tests of the hrelf binary's output on the files in tests/fixtures,
+//! which generate.py there rebuilds.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Runs hrelf with `args` followed by the fixture `name`.
+fn hrelf(args: &[&str], name: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_hrelf"))
+        .args(args)
+        .arg(fixture(name))
+        .output()
+        .expect("failed to run hrelf")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn overflowing_load_segment_fails_the_full_report_cleanly() {
+    for name in ["hugeoffset", "hugefilesz"] {
+        let output = hrelf(&["-a"], name);
+        let stderr = stderr(&output);
+        assert_eq!(output.status.code(), Some(1), "{}", stderr);
+        assert!(!stderr.contains("panicked"), "{}", stderr);
+        assert!(
+            stderr.contains("failed to parse the dynamic section: Integer overflow"),
+            "{}",
+            stderr
+        );
+        // The reports before the dynamic section still print
+        assert!(stdout(&output).contains("Section Headers:"));
+    }
+}
+
+#[test]
+fn overflowing_load_segment_fails_the_dynamic_reports_cleanly() {
+    for args in [&["-d"][..], &["--needed"], &["--init-fini"]] {
+        let output = hrelf(args, "hugeoffset");
+        assert_eq!(output.status.code(), Some(1), "{:?}", args);
+        assert!(stderr(&output).starts_with("hrelf: "), "{:?}", args);
+    }
+    // The entry point is named from .symtab, which is intact
+    let output = hrelf(&["--entry-point"], "hugeoffset");
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn segment_dump_addresses_wrap_past_the_top() {
+    let output = hrelf(&["--segment-contents"], "highvaddr");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    let rows: Vec<_> = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Hex dump of segment 2 "))
+        .filter_map(|line| line.strip_prefix("  0x"))
+        .map(|row| row.split(' ').next().unwrap())
+        .collect();
+    assert_eq!(rows[0], "fffffffffffffe00");
+    assert_eq!(rows[31], "fffffffffffffff0");
+    assert_eq!(rows[32], "00000000");
+    assert_eq!(rows[33], "00000010");
+}
+
+#[test]
+fn hex_dump_addresses_wrap_past_the_top() {
+    let output = hrelf(&["-x", ".interp"], "highaddr");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout
+        .contains("\n  0xfffffffffffffff0 2f6c6962 36342f6c 642d6c69 6e75782d /lib64/ld-linux-\n"));
+    assert!(stdout.contains("\n  0x00000000 7838362d 36342e73 6f2e3200          x86-64.so.2.\n"));
+}
+
+#[test]
+fn check_warnings_go_to_stderr() {
+    let output = hrelf(&["--check"], "execstack");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    let warnings = stderr(&output);
+    assert!(
+        warnings.ends_with(
+            "execstack: warning: program header 11 (PT_GNU_STACK) asks for an executable stack\n"
+        ),
+        "{}",
+        warnings
+    );
+    // Combined with a report, only the report is on stdout
+    let output = hrelf(&["--check", "-h"], "execstack");
+    assert!(stdout(&output).starts_with("ELF Header:\n"));
+    assert!(!stdout(&output).contains("warning"));
+
+    let output = hrelf(&["--check"], "hello");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn check_names_a_section_header_table_past_the_end() {
+    let output = hrelf(&["--check"], "hugeshoff");
+    assert_eq!(output.status.code(), Some(1));
+    let warnings = stderr(&output);
+    let mut lines = warnings.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        format!(
+            "{}: warning: the section header table claims 0x7c0 bytes at offset \
+             0xffffffffffffff00, past the end of the file",
+            fixture("hugeshoff").display()
+        )
+    );
+    // Which is as far as the elf crate gets with it
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .contains("failed to parse the ELF header"),
+        "{}",
+        warnings
+    );
+}
+
+#[test]
+fn dynamic_section_of_static_pie_and_shared_library() {
+    let output = hrelf(&["-d"], "static");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "There is no dynamic section in this file.\n\n"
+    );
+
+    let dump = stdout(&hrelf(&["-d"], "hello"));
+    assert!(dump.starts_with("Dynamic section at offset 0x2de0 contains 26 entries:\n"));
+    assert!(dump.contains(" (NEEDED)             Shared library: [libc.so.6]\n"));
+
+    let dump = stdout(&hrelf(&["-d"], "libhello.so"));
+    assert!(dump.contains(" (SONAME)             Library soname: [libhello.so]\n"));
+    assert!(!dump.contains("Relocation section"));
+}
+
+#[test]
+fn full_report_of_a_static_executable() {
+    let output = hrelf(&["-a"], "static");
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+    let dump = stdout(&output);
+    let reports = [
+        "ELF Header:\n",
+        "Section Headers:\n",
+        "Program Headers:\n",
+        "There is no dynamic section in this file.\n",
+        "Symbol table '.symtab' contains 6 entries:\n",
+    ];
+    let offsets: Vec<_> = reports
+        .iter()
+        .map(|report| dump.find(report).unwrap_or_else(|| panic!("{:?}", report)))
+        .collect();
+    assert!(offsets.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(dump.contains(
+        "   2  : 0000000000401000 16    STT_FUNC   STB_GLOBAL STV_DEFAULT 2      _start\n"
+    ));
+    assert!(!dump.contains(".dynsym"));
+}
+
+#[test]
+fn histogram_of_a_sysv_hash_only_library() {
+    let output = hrelf(&["-I"], "libsysv.so");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "\
+There is no .gnu.hash section in this file, only a SysV .hash.
+
+Histogram for bucket list length (total of 3 buckets):
+ Length  Number     % of total  Coverage
+      0  0          (  0.0%)
+      1  1          ( 33.3%)     16.7%
+      2  1          ( 33.3%)     50.0%
+      3  1          ( 33.3%)    100.0%
+
+"
+    );
+
+    // Nor does the missing .gnu.hash cut the full report short
+    let output = hrelf(&["-a"], "libsysv.so");
+    assert!(output.status.success());
+    let dump = stdout(&output);
+    assert!(dump.contains("Symbol table '.dynsym' contains 7 entries:\n"));
+    assert!(dump.contains("Hash table `.hash': 3 buckets, 6 symbols, longest chain 3"));
+    assert!(dump.ends_with("There are no arch-specific attributes in this file.\n\n"));
+}
+
+#[test]
+fn histogram_of_an_oversized_sysv_hash_fails_cleanly() {
+    for args in [&["-I"][..], &["-a"]] {
+        let output = hrelf(args, "badhash.so");
+        let stderr = stderr(&output);
+        assert_eq!(output.status.code(), Some(1), "{:?}: {}", args, stderr);
+        assert!(
+            stderr.contains("failed to parse the hash table: Could not read bytes in range"),
+            "{:?}: {}",
+            args,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn oversized_gnu_hash_fails_cleanly() {
+    // -D sizes .dynsym from the .gnu.hash chains
+    for (args, table) in [
+        (&["-I"][..], "hash table"),
+        (&["-D", "--dyn-syms"], "dynamic symbol table"),
+    ] {
+        let output = hrelf(args, "badgnuhash.so");
+        let stderr = stderr(&output);
+        assert_eq!(output.status.code(), Some(1), "{:?}: {}", args, stderr);
+        assert!(
+            stderr.contains(&format!(
+                "failed to parse the {}: Could not read bytes in range",
+                table
+            )),
+            "{:?}: {}",
+            args,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn use_dynamic_without_section_headers() {
+    let output = hrelf(&["-D", "-s"], "noshdrs.so");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let dump = stdout(&output);
+    assert!(
+        dump.starts_with("Symbol table '.dynsym' contains 7 entries:\n"),
+        "{}",
+        dump
+    );
+    assert!(
+        dump.contains("STT_OBJECT STB_GLOBAL STV_DEFAULT 19     counter\n"),
+        "{}",
+        dump
+    );
+
+    // The relocations name their symbols from the same table
+    let output = hrelf(&["-D", "-r"], "noshdrs.so");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains(" R_X86_64_GLOB_DAT  000000000000400c counter"));
+
+    // And -s without -D has only the section headers to go on
+    let output = hrelf(&["-s"], "noshdrs.so");
+    assert_eq!(stdout(&output), "No symbol table found.\n\n");
+}
+
+#[test]
+fn dynamic_section_ends_at_the_first_null() {
+    // libhello.so pads .dynamic out with four more DT_NULLs, libnopad.so
+    // with none
+    let dump = stdout(&hrelf(&["-d"], "libhello.so"));
+    assert_eq!(stdout(&hrelf(&["-d"], "libnopad.so")), dump);
+    assert!(dump.starts_with("Dynamic section at offset 0x2e60 contains 18 entries:\n"));
+    assert_eq!(dump.matches("(NULL)").count(), 1);
+    assert!(dump.ends_with(
+        " 0x000000006ffffff9 (RELACOUNT)          3\n 0x0000000000000000 (NULL)               0x0\n\n"
+    ));
+}
+
+#[test]
+fn full_report_of_a_core_dump() {
+    let output = hrelf(&["-a"], "core");
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+    let dump = stdout(&output);
+    assert!(dump.starts_with("ELF Header:\n"));
+    assert!(dump.contains("  Type:                       Core file\n"));
+    assert!(dump.contains(
+        "\
+Program Headers:
+  Type            Offset           VirtAddr         PhysAddr
+                  FileSiz          MemSiz           Flags  Align
+  PT_NOTE         00000000000000b0 0000000000000000 0000000000000000
+                  0000000000000234 0000000000000234        1
+  PT_LOAD         0000000000001000 00007ffffffde000 00007ffffffde000
+                  0000000000001000 0000000000001000 RW     4096
+
+Displaying notes found at file offset 0x000000b0 with length 0x00000234:
+"
+    ));
+    // Nothing that needs sections or a dynamic section
+    for table in [
+        "Section Headers",
+        "section groups",
+        "dynamic section",
+        "Symbol table",
+        "relocations",
+    ] {
+        assert!(!dump.contains(table), "{}", table);
+    }
+}
+
+#[test]
+fn full_report_of_an_object_file() {
+    let output = hrelf(&["-a"], "hello.o");
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+    let dump = stdout(&output);
+    assert!(dump.contains("  Type:                       Relocatable file\n"));
+    assert!(dump.contains("\nThere are no program headers in this file.\n"));
+    assert!(dump.contains(
+        "\
+Relocation section '.rela.text' at offset 0x1d0 contains 4 entries:
+  Offset           Info         Type               Sym. Value       Sym. Name + Addend
+  0000000000000006 000600000002 R_X86_64_PC32      0000000000000000 g - 4
+  000000000000000c 000300000002 R_X86_64_PC32      0000000000000000 .bss - 4
+  0000000000000017 000500000002 R_X86_64_PC32      0000000000000000 .rodata - 4
+  0000000000000024 000800000004 R_X86_64_PLT32     0000000000000000 printf - 4
+
+Relocation section '.rela.eh_frame' at offset 0x230 contains 1 entry:
+  Offset           Info         Type               Sym. Value       Sym. Name + Addend
+  0000000000000020 000200000002 R_X86_64_PC32      0000000000000000 .text + 0
+"
+    ));
+    assert!(dump.contains("Symbol table '.symtab' contains 9 entries:\n"));
+    assert!(!dump.contains("Dynamic section"));
+    assert!(!dump.contains(".dynsym"));
+}
+
+#[test]
+fn dynamic_section_combines_with_other_reports() {
+    let dump = stdout(&hrelf(&["-d", "-r"], "libhello.so"));
+    let dynamic = dump.find("Dynamic section at offset").unwrap();
+    let relocs = dump.find("Relocation section '.rela.dyn'").unwrap();
+    assert!(dynamic < relocs);
+    assert!(!dump.contains("Section Headers:"));
+}
+
+#[test]
+fn symbol_tables_are_selected_separately() {
+    let dump = stdout(&hrelf(&["-s"], "libhello.so"));
+    assert!(dump.starts_with("Symbol table '.symtab' contains 26 entries:\n"));
+    assert!(!dump.contains(".dynsym"));
+
+    let dump = stdout(&hrelf(&["--dyn-syms"], "libhello.so"));
+    assert!(dump.starts_with("Symbol table '.dynsym' contains 7 entries:\n"));
+    assert!(!dump.contains(".symtab"));
+
+    let dump = stdout(&hrelf(&["-s", "--dyn-syms"], "libhello.so"));
+    let dynsym = dump.find("Symbol table '.dynsym'").unwrap();
+    let symtab = dump.find("Symbol table '.symtab'").unwrap();
+    assert!(dynsym < symtab);
+
+    // Only --dyn-syms mentions a missing .dynsym
+    assert_eq!(
+        stdout(&hrelf(&["--dyn-syms"], "static")),
+        "Dynamic symbol information is not available for displaying symbols.\n\n"
+    );
+    let dump = stdout(&hrelf(&["-s"], "static"));
+    assert!(dump.starts_with("Symbol table '.symtab' contains 6 entries:\n"));
+    assert!(!dump.contains("Dynamic symbol information"));
+}
+
+#[test]
+fn extended_section_index_of_a_symbol() {
+    let dump = stdout(&hrelf(&["-s"], "xindex.o"));
+    assert!(dump.contains(
+        "   2  : 0000000000000000 8     STT_OBJECT STB_GLOBAL STV_DEFAULT 2      counter\n"
+    ));
+    let dump = stdout(&hrelf(&["--symbol", "counter"], "xindex.o"));
+    assert!(
+        dump.ends_with("  .symtab       2: 0000000000000000 8     STT_OBJECT STB_GLOBAL .data\n\n")
+    );
+}
+
+#[test]
+fn extended_section_count_and_string_table_index() {
+    let dump = stdout(&hrelf(&["-h"], "extnum.o"));
+    assert!(dump.contains("  Number of section headers:  0 (13)\n"));
+    assert!(dump.contains("  Section header string table index: 65535 (12)\n"));
+
+    // Every section is still listed by name, as in the unpatched object
+    let names = |name| {
+        stdout(&hrelf(&["-S"], name))
+            .lines()
+            .filter(|line| line.starts_with("  ["))
+            .map(|line| line[7..26].trim_end().to_string())
+            .collect::<Vec<_>>()
+    };
+    let extended = names("extnum.o");
+    assert_eq!(extended.len(), 14);
+    assert_eq!(extended[13], ".shstrtab");
+    assert_eq!(extended, names("hello.o"));
+}
+
+#[test]
+fn notes_of_a_core_dump() {
+    assert_eq!(
+        stdout(&hrelf(&["-n"], "core")),
+        "\
+Displaying notes found at file offset 0x000000b0 with length 0x00000234:
+  Owner                Data size \tDescription
+  CORE                 0x00000150\tNT_PRSTATUS (prstatus structure)
+  CORE                 0x00000088\tNT_PRPSINFO (prpsinfo structure)
+  CORE                 0x00000020\tNT_AUXV (auxiliary vector)
+
+"
+    );
+}
+
+#[test]
+fn corrupt_note_is_a_warning() {
+    let output = hrelf(&["-n"], "badnote");
+    assert!(output.status.success());
+    assert_eq!(
+        stderr(&output),
+        "hrelf: Warning: corrupt note found at offset 0x358\n"
+    );
+    // The notes before and after it still print
+    let dump = stdout(&output);
+    assert!(dump.contains("\tNT_GNU_PROPERTY_TYPE_0\n"), "{}", dump);
+    assert!(
+        dump.ends_with(
+            "\
+Displaying notes found in: .note.ABI-tag
+  Owner                Data size \tDescription
+  GNU                  0x00000010\tNT_GNU_ABI_TAG (ABI version tag)
+    OS: Linux, ABI: 3.2.0
+
+"
+        ),
+        "{}",
+        dump
+    );
+}
+
+#[test]
+fn selectors_the_file_lacks_are_errors() {
+    let output = hrelf(&["-x", ".nope"], "riscv.o");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).ends_with(
+        "riscv.o: no section '.nope'; available sections: .text, .riscv.attributes, .symtab, .strtab, .shstrtab\n"
+    ));
+
+    let output = hrelf(&["--symbol", "nope"], "riscv.o");
+    assert!(!output.status.success());
+    assert!(stderr(&output).ends_with("riscv.o: no symbol 'nope'\n"));
+}
+
+#[test]
+fn rel_relocations_of_an_i386_library() {
+    assert_eq!(
+        stdout(&hrelf(&["-r"], "lib32.so")),
+        "\
+Relocation section '.rel.dyn' at offset 0x234 contains 3 entries:
+  Offset   Info     Type               Sym. Value Sym. Name
+  0000400c 00000008 R_386_RELATIVE
+  00003ff0 00000106 R_386_GLOB_DAT     00000000   ext
+  00004008 00000401 R_386_32           00004004   g
+
+Relocation section '.rel.plt' at offset 0x24c contains 1 entry:
+  Offset   Info     Type               Sym. Value Sym. Name
+  00004000 00000207 R_386_JUMP_SLOT    00000000   f
+
+"
+    );
+}
+
+#[test]
+fn rel_relocations_of_an_arm_library() {
+    assert_eq!(
+        stdout(&hrelf(&["-r"], "libarm.so")),
+        "\
+Relocation section '.rel.dyn' at offset 0x58 contains 3 entries:
+  Offset   Info     Type               Sym. Value Sym. Name
+  0000007c 00000017 R_ARM_RELATIVE
+  00000070 00000215 R_ARM_GLOB_DAT     00000078   counter
+  00000074 00000302 R_ARM_ABS32        00000000   ext
+
+"
+    );
+}
+
+#[test]
+fn big_endian_headers_sections_and_symbols() {
+    let dump = stdout(&hrelf(&["-h"], "ppc64"));
+    assert!(dump.contains("  Data:                       2's complement, big endian\n"));
+    assert!(dump.contains("  Machine:                    64-bit PowerPC\n"));
+    assert!(dump.contains("  Entry point address:        0x100000d4\n"));
+    assert!(dump.contains("  Start of section headers:   416 (bytes into file)\n"));
+
+    let dump = stdout(&hrelf(&["-S", "-W"], "ppc64"));
+    for row in [
+        "  [ 2] .text              SHT_PROGBITS      00000000100000d4   000000d4   000000000000000c",
+        "  [ 3] .rela.dyn          SHT_RELA          00000000100000e0   000000e0   0000000000000018   0000000000000018",
+        "  [ 4] .data              SHT_PROGBITS      00000000100000f8   000000f8   0000000000000008",
+    ] {
+        assert!(dump.contains(row), "{}", row);
+    }
+
+    let dump = stdout(&hrelf(&["-s"], "ppc64"));
+    assert!(dump.contains(
+        "   1  : 00000000100000d4 12    STT_FUNC   STB_GLOBAL STV_DEFAULT 2      _start\n"
+    ));
+    assert!(dump.contains(
+        "   2  : 00000000100000f8 8     STT_OBJECT STB_GLOBAL STV_DEFAULT 4      entry\n"
+    ));
+
+    let dump = stdout(&hrelf(&["-r"], "ppc64"));
+    assert!(dump.contains(
+        "  00000000100000f8 000000000016 R_PPC64_RELATIVE                    100000d4\n"
+    ));
+}
+
+/// The section lists of -l's section to segment mapping, one per segment.
+fn segment_sections(name: &str) -> Vec<String> {
+    let dump = stdout(&hrelf(&["-l"], name));
+    let mapping = dump.split(" Section to Segment mapping:\n").nth(1).unwrap();
+    mapping
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .map(|line| line[10..].to_string())
+        .collect()
+}
+
+#[test]
+fn section_to_segment_mapping_matches_readelf() {
+    // As readelf -l lists them: only SHF_ALLOC sections, .bss in the RW
+    // PT_LOAD but not in PT_GNU_RELRO, .tbss only in PT_TLS
+    assert_eq!(
+        segment_sections("hello"),
+        [
+            "",
+            ".interp",
+            ".interp .note.gnu.property .note.gnu.build-id .note.ABI-tag .gnu.hash .dynsym .dynstr .gnu.version .gnu.version_r .rela.dyn .rela.plt",
+            ".init .plt .plt.got .text .fini",
+            ".rodata .eh_frame_hdr .eh_frame",
+            ".init_array .fini_array .dynamic .got .got.plt .data .bss",
+            ".dynamic",
+            ".note.gnu.property",
+            ".note.gnu.build-id .note.ABI-tag",
+            ".note.gnu.property",
+            ".eh_frame_hdr",
+            "",
+            ".init_array .fini_array .dynamic .got",
+        ]
+    );
+    assert_eq!(
+        segment_sections("tls"),
+        [
+            "",
+            ".interp",
+            ".interp .note.gnu.property .note.gnu.build-id .note.ABI-tag .gnu.hash .dynsym .dynstr .gnu.version .gnu.version_r .rela.dyn",
+            ".init .plt .plt.got .text .fini",
+            ".rodata .eh_frame_hdr .eh_frame",
+            ".tdata .init_array .fini_array .dynamic .got .got.plt .data .bss",
+            ".dynamic",
+            ".note.gnu.property",
+            ".note.gnu.build-id .note.ABI-tag",
+            ".tdata .tbss",
+            ".note.gnu.property",
+            ".eh_frame_hdr",
+            "",
+            ".tdata .init_array .fini_array .dynamic .got .got.plt",
+        ]
+    );
+}
+
+/// The keys of a JSON document's top-level object, in order.
+fn top_level_keys(json: &str) -> Vec<String> {
+    let (mut keys, mut depth, mut string, mut escaped) = (Vec::new(), 0, None, false);
+    for (i, c) in json.char_indices() {
+        match (string, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(start), '"') => {
+                // A key is a depth 1 string followed by a colon
+                if depth == 1 && json[i + 1..].starts_with(':') {
+                    keys.push(json[start..i].to_string());
+                }
+                string = None;
+            }
+            (Some(_), _) => {}
+            (None, '"') => string = Some(i + 1),
+            (None, '{' | '[') => depth += 1,
+            (None, '}' | ']') => depth -= 1,
+            (None, _) => {}
+        }
+    }
+    keys
+}
+
+#[test]
+fn json_nests_each_table_under_its_key() {
+    let output = hrelf(&["--format", "json"], "hello");
+    assert!(output.status.success());
+    let json = stdout(&output);
+    assert_eq!(
+        top_level_keys(&json),
+        [
+            "header",
+            "sections",
+            "segments",
+            "dynamic",
+            "relocations",
+            "symbols",
+            "notes"
+        ]
+    );
+    assert!(json.starts_with(concat!(
+        r#"{"header":{"class":"ELF64","endianness":"Little","version":1,"#,
+        r#""osabi":{"raw":0,"name":"ELFOSABI_SYSV"},"abiversion":0,"#,
+        r#""e_type":{"raw":3,"name":"ET_DYN"},"#
+    )));
+    assert!(json.contains(concat!(
+        r#""sections":[{"name":"","sh_name":0,"sh_type":{"raw":0,"name":"SHT_NULL"},"#,
+        r#""sh_flags":0,"sh_addr":0,"sh_offset":0,"sh_size":0,"sh_link":0,"sh_info":0,"#,
+        r#""sh_addralign":0,"sh_entsize":0},{"name":".interp","#
+    )));
+    assert!(json.contains(concat!(
+        r#""symbols":{"dynsym":[{"name":"","st_name":0,"#,
+        r#""st_value":0,"st_size":0,"st_symtype":{"raw":0,"name":"STT_NOTYPE"},"#
+    )));
+    assert!(json.contains(r#"{"name":"__libc_start_main","st_name":1,"#));
+    assert!(json.ends_with("}\n"));
+}
+
+#[test]
+fn out_of_range_section_name_is_corrupt() {
+    let output = hrelf(&["-S", "-l"], "badname");
+    assert!(output.status.success());
+    let dump = stdout(&output);
+    // The bad name shows its offset, and the rows after it still print
+    assert!(dump
+        .contains("  [17] <corrupt: 0xfffffff0> SHT_PROGBITS      0000000000002000   00002000\n"));
+    assert!(
+        dump.contains("  [18] .eh_frame_hdr      SHT_PROGBITS      000000000000200c   0000200c\n")
+    );
+    assert!(dump.contains("  04      <corrupt: 0xfffffff0> .eh_frame_hdr .eh_frame\n"));
+}
+
+#[test]
+fn program_interpreter() {
+    let dump = stdout(&hrelf(&["-l"], "hello"));
+    assert!(dump.contains(
+        "\
+  PT_INTERP       0000000000000318 0000000000000318 0000000000000318
+                  000000000000001c 000000000000001c R      1
+      [Requesting program interpreter: /lib64/ld-linux-x86-64.so.2]
+"
+    ));
+    // Without its NUL the path may not have ended where the segment does
+    let dump = stdout(&hrelf(&["-l"], "badinterp"));
+    assert!(dump.contains("      [Requesting program interpreter: <corrupt>]\n"));
+}
+
+#[test]
+fn json_names_a_non_utf8_symbol_corrupt() {
+    let output = hrelf(&["--format", "json"], "badutf8");
+    // The document stays valid UTF-8, with the offset that didn't resolve
+    // alongside the placeholder
+    let json = std::str::from_utf8(&output.stdout).unwrap();
+    assert!(json.contains(r#"{"name":"<corrupt>","st_name":399,"st_value":4409,"#));
+}
+
+#[test]
+fn version_info_of_a_version_script_library() {
+    assert_eq!(
+        stdout(&hrelf(&["-V"], "libv.so")),
+        "\
+Version symbols section '.gnu.version' contains 9 entries:
+ Addr: 0x00000000000003e8  Offset: 0x000003e8  Link: 3 (.dynsym)
+  000:   0 (*local*)       1 (*global*)      1 (*global*)      1 (*global*)
+  004:   1 (*global*)      2 (VERS_1.0)      3 (VERS_2.0)      3 (VERS_2.0)
+  008:   2 (VERS_1.0)
+
+Version definition section '.gnu.version_d' contains 3 entries:
+ Addr: 0x0000000000000400  Offset: 0x00000400  Link: 4 (.dynstr)
+  000000: Rev: 1  Flags: BASE  Index: 1  Cnt: 1  Name: libv.so
+  0x001c: Rev: 1  Flags: none  Index: 2  Cnt: 1  Name: VERS_1.0
+  0x0038: Rev: 1  Flags: none  Index: 3  Cnt: 2  Name: VERS_2.0
+  0x0054: Parent 1: VERS_1.0
+
+"
+    );
+}
+
+#[test]
+fn symbol_versions_with_and_without_use_dynamic() {
+    for args in [&["--dyn-syms"][..], &["-D", "--dyn-syms"]] {
+        // Needed versions carry their index, defined ones @@ unless hidden
+        let dump = stdout(&hrelf(args, "hello"));
+        assert!(
+            dump.contains(" UND    printf@GLIBC_2.2.5 (3)\n"),
+            "{:?}: {}",
+            args,
+            dump
+        );
+        assert!(
+            dump.contains(" UND    __gmon_start__\n"),
+            "{:?}: {}",
+            args,
+            dump
+        );
+        let dump = stdout(&hrelf(args, "libv.so"));
+        assert!(
+            dump.contains(" 11     foo@@VERS_1.0\n"),
+            "{:?}: {}",
+            args,
+            dump
+        );
+        assert!(
+            dump.contains(" 11     bar@@VERS_2.0\n"),
+            "{:?}: {}",
+            args,
+            dump
+        );
+        // The linker's marker symbol for a version is left bare
+        assert!(dump.contains(" ABS    VERS_2.0\n"), "{:?}: {}", args, dump);
+    }
+}
+
+#[test]
+fn version_info_of_a_glibc_binary() {
+    assert_eq!(
+        stdout(&hrelf(&["-V"], "hello")),
+        "\
+Version symbols section '.gnu.version' contains 7 entries:
+ Addr: 0x0000000000000500  Offset: 0x00000500  Link: 6 (.dynsym)
+  000:   0 (*local*)       2 (GLIBC_2.34)    1 (*global*)      3 (GLIBC_2.2.5)
+  004:   1 (*global*)      1 (*global*)      3 (GLIBC_2.2.5)
+
+Version needs section '.gnu.version_r' contains 1 entry:
+ Addr: 0x0000000000000510  Offset: 0x00000510  Link: 7 (.dynstr)
+  000000: Version: 1  File: libc.so.6  Cnt: 2
+  0x0010:   Name: GLIBC_2.2.5  Flags: none  Version: 3
+  0x0020:   Name: GLIBC_2.34  Flags: none  Version: 2
+
+"
+    );
+}
+
+#[test]
+fn riscv_attributes() {
+    assert_eq!(
+        stdout(&hrelf(&["-A"], "riscv.o")),
+        "\
+Attribute Section: riscv
+File Attributes
+  Tag_RISCV_stack_align: 16-bytes
+  Tag_RISCV_arch: \"rv64i2p1_m2p0_a2p1_f2p2_d2p2_c2p0_zicsr2p0_zifencei2p0\"
+  Tag_RISCV_unaligned_access: No unaligned access
+
+"
+    );
+}
+
+#[test]
+fn section_groups_of_template_instantiations() {
+    assert_eq!(
+        stdout(&hrelf(&["-g"], "templates.o")),
+        "
+COMDAT group section [    1] `.group' [_ZZ5twiceIiET_S0_E5calls] contains 1 sections:
+   [Index]    Name
+   [    9]   .bss._ZZ5twiceIiET_S0_E5calls
+
+COMDAT group section [    2] `.group' [_Z5twiceIiET_S0_] contains 2 sections:
+   [Index]    Name
+   [   10]   .text._Z5twiceIiET_S0_
+   [   11]   .rela.text._Z5twiceIiET_S0_
+
+COMDAT group section [    3] `.group' [_ZZ5twiceIlET_S0_E5calls] contains 1 sections:
+   [Index]    Name
+   [   12]   .bss._ZZ5twiceIlET_S0_E5calls
+
+COMDAT group section [    4] `.group' [_Z5twiceIlET_S0_] contains 2 sections:
+   [Index]    Name
+   [   13]   .text._Z5twiceIlET_S0_
+   [   14]   .rela.text._Z5twiceIlET_S0_
+
+"
+    );
+}
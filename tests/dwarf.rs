@@ -0,0 +1,171 @@
+//! Tests of the DWARF decoding on the -g builds of hello in
+//! tests/fixtures, which generate.py there rebuilds.
+
+use hrelf::dwarf::{self, StringSections};
+use hrelf::elf::endian::AnyEndian;
+use hrelf::ElfReport;
+use std::path::Path;
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+fn section<'data>(report: &ElfReport<'data>, name: &str) -> &'data [u8] {
+    match report.find_section(name).unwrap() {
+        Some((_, section)) => report.section_data(&section).unwrap(),
+        None => &[],
+    }
+}
+
+fn strings<'data>(report: &ElfReport<'data>) -> StringSections<'data> {
+    StringSections {
+        debug_str: section(report, ".debug_str"),
+        debug_line_str: section(report, ".debug_line_str"),
+        debug_str_offsets: section(report, ".debug_str_offsets"),
+    }
+}
+
+/// (address, line, column, end_sequence) of each row main's code gives.
+const MAIN_ROWS: [(u64, u64, u64, bool); 5] = [
+    (0x1139, 2, 34, false),
+    (0x113d, 2, 35, false),
+    (0x1161, 2, 64, false),
+    (0x1166, 2, 66, false),
+    (0x1168, 2, 66, true),
+];
+
+fn rows(program: &dwarf::LineProgram) -> Vec<(u64, u64, u64, bool)> {
+    program
+        .rows
+        .iter()
+        .map(|row| (row.address, row.line, row.column, row.end_sequence))
+        .collect()
+}
+
+fn files<'data>(program: &dwarf::LineProgram<'data>) -> Vec<(&'data str, u64)> {
+    program
+        .files
+        .iter()
+        .map(|file| (file.name, file.directory))
+        .collect()
+}
+
+#[test]
+fn dwarf5_line_program() {
+    let data = fixture("hello-g");
+    let report = ElfReport::parse(&data).unwrap();
+    let programs = report.line_programs().unwrap();
+    assert_eq!(programs.len(), 1);
+    let program = &programs[0];
+    assert_eq!((program.version, program.dwarf64), (5, false));
+    assert_eq!(program.address_size, 8);
+    assert_eq!((program.line_base, program.line_range), (-5, 14));
+    // The tables are indexed from 0, entry 0 being the unit's own
+    assert_eq!(program.directories, ["/fixtures", "/usr/include"]);
+    assert_eq!(files(program), [("src.c", 0), ("src.c", 0), ("stdio.h", 1)]);
+    assert_eq!(rows(program), MAIN_ROWS);
+    assert!(program.rows.iter().all(|row| row.is_stmt && row.file == 1));
+}
+
+#[test]
+fn dwarf4_line_program() {
+    let data = fixture("hello-g4");
+    let report = ElfReport::parse(&data).unwrap();
+    let programs = report.line_programs().unwrap();
+    assert_eq!(programs.len(), 1);
+    let program = &programs[0];
+    assert_eq!(program.version, 4);
+    // Before DWARF 5 the tables are indexed from 1 and the compilation
+    // directory is implied
+    assert_eq!(program.directories, ["/usr/include"]);
+    assert_eq!(files(program), [("src.c", 0), ("stdio.h", 1)]);
+    assert_eq!(rows(program), MAIN_ROWS);
+}
+
+#[test]
+fn truncated_line_program_is_an_error() {
+    let data = fixture("hello-g");
+    let report = ElfReport::parse(&data).unwrap();
+    let line = section(&report, ".debug_line");
+    // Cut inside the header, and inside the opcodes after it
+    for end in [20, line.len() - 4] {
+        assert!(
+            dwarf::parse_line_programs(AnyEndian::Little, &line[..end], strings(&report), 8)
+                .is_err(),
+            "cut at {}",
+            end
+        );
+    }
+}
+
+#[test]
+fn compile_unit_summary() {
+    for (name, version, unit_type, language) in
+        [("hello-g", 5, Some(1), 0x1d), ("hello-g4", 4, None, 0x0c)]
+    {
+        let data = fixture(name);
+        let report = ElfReport::parse(&data).unwrap();
+        let units = report.compile_units().unwrap();
+        assert_eq!(units.len(), 1, "{}", name);
+        let (offset, unit) = &units[0];
+        let unit = unit.as_ref().unwrap();
+        assert_eq!(*offset, 0);
+        assert_eq!((unit.version, unit.unit_type), (version, unit_type));
+        assert!(!unit.dwarf64);
+        assert_eq!((unit.abbrev_offset, unit.address_size), (0, 8));
+        assert_eq!(unit.name, Some("src.c"));
+        assert_eq!(unit.comp_dir, Some("/fixtures"));
+        assert!(unit.producer.unwrap().starts_with("GNU C17 "));
+        assert_eq!(unit.language, Some(language));
+    }
+}
+
+#[test]
+fn every_unit_is_listed() {
+    let data = fixture("hello-g");
+    let report = ElfReport::parse(&data).unwrap();
+    let info = section(&report, ".debug_info");
+    let two_units = [info, info].concat();
+    let units = dwarf::parse_compile_units(
+        AnyEndian::Little,
+        &two_units,
+        section(&report, ".debug_abbrev"),
+        strings(&report),
+    );
+    let offsets: Vec<_> = units.iter().map(|(offset, _)| *offset).collect();
+    assert_eq!(offsets, [0, info.len()]);
+    assert!(units.iter().all(|(_, unit)| unit.is_ok()));
+}
+
+#[test]
+fn truncated_abbrev_table_fails_each_unit() {
+    let data = fixture("hello-g");
+    let report = ElfReport::parse(&data).unwrap();
+    let info = section(&report, ".debug_info");
+    let abbrev = section(&report, ".debug_abbrev");
+    let two_units = [info, info].concat();
+    // The root DIE's abbreviation, code 2, is declared at 0xb..0x1e; cut
+    // before it and inside its attribute list. Each unit fails on its own
+    // rather than ending the listing.
+    for end in [0, 3, 0xb, 0x10, 0x1c] {
+        let units = dwarf::parse_compile_units(
+            AnyEndian::Little,
+            &two_units,
+            &abbrev[..end],
+            strings(&report),
+        );
+        assert_eq!(units.len(), 2, "cut at {}", end);
+        assert!(
+            units.iter().all(|(_, unit)| unit.is_err()),
+            "cut at {}",
+            end
+        );
+    }
+    // As does .debug_info itself, cut inside the root DIE
+    let units =
+        dwarf::parse_compile_units(AnyEndian::Little, &info[..16], abbrev, strings(&report));
+    assert!(matches!(units[..], [(0, Err(_))]));
+}
@@ -0,0 +1,669 @@
+#!/usr/bin/env python3
+"""Rebuilds the ELF files the tests run hrelf on.
+
+Most are built from the small C sources below with the host gcc, and
+some are those with a header field patched to something a broken or
+hostile file would hold. Files for targets with no toolchain here are
+laid out from scratch by ElfWriter. Run it from anywhere, optionally naming the
+fixtures to rebuild:
+
+    python3 tests/fixtures/generate.py [name...]
+
+A different gcc or binutils lays files out differently, so after
+rebuilding, regenerate the golden files too (see tests/golden.rs).
+"""
+
+import os
+import struct
+import subprocess
+import sys
+import tempfile
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+HELLO_C = """\
+#include <stdio.h>
+int g=3; static int s; int main(){printf("hi %d\\n",g+s);return 0;}
+"""
+
+LIB_C = """\
+int counter; int next(void){return ++counter;}
+"""
+
+LIB32_C = """\
+extern int ext; extern int f(int); static int s; int g=1; int *p=&g, *q=&s;
+int call(int x){return f(x)+ext;}
+"""
+
+TLS_C = """\
+__thread int t=1; __thread int u; int main(){return t+u;}
+"""
+
+VERSIONED_C = """\
+int foo(void){return 1;}
+int bar(void){return 2;}
+"""
+
+VERSION_SCRIPT = """\
+VERS_1.0 { global: foo; local: *; };
+VERS_2.0 { global: bar; } VERS_1.0;
+"""
+
+TEMPLATES_CPP = """\
+template <typename T> T twice(T x) { static T calls; calls++; return x + x; }
+int use(int a, long b) { return twice(a) + twice(b); }
+"""
+
+START_C = """\
+void _start(void){__asm__ volatile("mov $60,%eax; xor %edi,%edi; syscall");}
+"""
+
+FIXTURES = {}
+
+
+def fixture(function):
+    FIXTURES[function.__name__] = function
+    return function
+
+
+def path(name):
+    return os.path.join(HERE, name)
+
+
+def gcc(output, source, *flags, lang="c"):
+    with tempfile.TemporaryDirectory() as tmp:
+        src = "src." + lang
+        with open(os.path.join(tmp, src), "w") as f:
+            f.write(source)
+        # Debug info names the build directory; keep it the same every time
+        prefix_map = "-fdebug-prefix-map=" + tmp + "=/fixtures"
+        subprocess.run(["gcc", *flags, prefix_map, "-o", path(output), src], check=True, cwd=tmp)
+
+
+class Elf64:
+    """A little-endian ELF64 file's bytes, for patching header fields."""
+
+    def __init__(self, name):
+        with open(path(name), "rb") as f:
+            self.data = bytearray(f.read())
+
+    def save(self, name):
+        with open(path(name), "wb") as f:
+            f.write(self.data)
+
+    def u16(self, offset, value=None):
+        return self._field("<H", offset, value)
+
+    def u32(self, offset, value=None):
+        return self._field("<I", offset, value)
+
+    def u64(self, offset, value=None):
+        return self._field("<Q", offset, value)
+
+    def _field(self, fmt, offset, value):
+        if value is not None:
+            struct.pack_into(fmt, self.data, offset, value)
+        return struct.unpack_from(fmt, self.data, offset)[0]
+
+    def phdr(self, index):
+        """The file offset of program header `index`."""
+        return self.u64(0x20) + index * self.u16(0x36)
+
+    def phdrs(self, p_type):
+        return [
+            self.phdr(i) for i in range(self.u16(0x38)) if self.u32(self.phdr(i)) == p_type
+        ]
+
+    def shdr(self, index):
+        """The file offset of section header `index`."""
+        return self.u64(0x28) + index * self.u16(0x3A)
+
+    def section(self, name):
+        """The file offset of the header of the section called `name`."""
+        strtab = self.u64(self.shdr(self.u16(0x3E)) + 0x18)
+        for i in range(self.u16(0x3C)):
+            start = strtab + self.u32(self.shdr(i))
+            end = self.data.index(b"\0", start)
+            if self.data[start:end].decode() == name:
+                return self.shdr(i)
+        raise KeyError(name)
+
+
+class ElfWriter:
+    """Lays out an ELF file from scratch: the header, the program headers,
+    any segment-only contents, the sections and last the section headers.
+    With a base address, allocated sections are addressed at base plus
+    their file offset, so one PT_LOAD from offset 0 can map them all.
+    Without any sections there's no section header table at all, as in a
+    core dump."""
+
+    def __init__(self, bits, big_endian, e_type, e_machine, e_flags=0, base=None):
+        self.bits = bits
+        self.order = ">" if big_endian else "<"
+        self.e_type = e_type
+        self.e_machine = e_machine
+        self.e_flags = e_flags
+        self.base = base
+        self.entry = None
+        self.sections = [dict(name="", sh_type=0, data=b"", flags=0, link=0, info=0, align=0, entsize=0)]
+        self.segments = []
+
+    def pack(self, fmt, *values):
+        return struct.pack(self.order + fmt, *values)
+
+    @property
+    def word(self):
+        return "Q" if self.bits == 64 else "I"
+
+    def add_section(self, name, sh_type, data=b"", flags=0, link=0, info=0, align=1, entsize=0):
+        self.sections.append(
+            dict(name=name, sh_type=sh_type, data=data, flags=flags, link=link, info=info, align=align, entsize=entsize)
+        )
+        return len(self.sections) - 1
+
+    def add_symbols(self, symbols):
+        """Adds .symtab and .strtab for (name, value, size, type, bind,
+        shndx) tuples, locals first, after the null symbol."""
+        strtab = b"\0"
+        entries = [self.symbol(0, 0, 0, 0, 0)]
+        for name, value, size, st_type, bind, shndx in symbols:
+            entries.append(self.symbol(len(strtab), value, size, bind << 4 | st_type, shndx))
+            strtab += name.encode() + b"\0"
+        first_global = 1 + sum(1 for symbol in symbols if symbol[4] == STB_LOCAL)
+        symtab = self.add_section(
+            ".symtab",
+            SHT_SYMTAB,
+            b"".join(entries),
+            link=len(self.sections) + 1,
+            info=first_global,
+            align=self.bits // 8,
+            entsize=len(entries[0]),
+        )
+        self.add_section(".strtab", SHT_STRTAB, strtab)
+        return symtab
+
+    def symbol(self, name, value, size, info, shndx):
+        if self.bits == 64:
+            return self.pack("IBBHQQ", name, info, 0, shndx, value, size)
+        return self.pack("IIIBBH", name, value, size, info, 0, shndx)
+
+    def rel(self, offset, sym, r_type):
+        if self.bits == 64:
+            return self.pack("QQ", offset, sym << 32 | r_type)
+        return self.pack("II", offset, sym << 8 | r_type)
+
+    def rela(self, offset, sym, r_type, addend):
+        if self.bits == 64:
+            return self.pack("QQq", offset, sym << 32 | r_type, addend)
+        return self.pack("IIi", offset, sym << 8 | r_type, addend)
+
+    def add_segment(self, p_type, p_flags, data=None, sections=(), align=1, vaddr=0):
+        """A segment holding `data` of its own, loaded at `vaddr`, or else
+        spanning the named sections; a PT_LOAD spanning sections starts at
+        offset 0."""
+        self.segments.append(dict(p_type=p_type, p_flags=p_flags, data=data, sections=sections, align=align, vaddr=vaddr))
+
+    def address(self, name):
+        """Where the section called `name` ends up; only valid after build."""
+        return self.laid_out[name][1]
+
+    def build(self):
+        ehsize, phentsize, shentsize = (64, 56, 64) if self.bits == 64 else (52, 32, 40)
+        if len(self.sections) == 1:
+            self.sections = []
+        shstrtab = b"\0"
+        for section in self.sections[1:]:
+            section["sh_name"] = len(shstrtab)
+            shstrtab += section["name"].encode() + b"\0"
+        shstrtab_name = len(shstrtab)
+        shstrtab += b".shstrtab\0"
+        if self.sections:
+            self.sections.append(dict(name=".shstrtab", sh_type=SHT_STRTAB, data=shstrtab, flags=0, link=0, info=0, align=1, entsize=0))
+            self.sections[-1]["sh_name"] = shstrtab_name
+            self.sections[0]["sh_name"] = 0
+
+        body = bytearray()
+        offset = ehsize + len(self.segments) * phentsize
+
+        def place(data, align):
+            nonlocal offset
+            padding = -offset % max(align, 1)
+            body.extend(b"\0" * padding)
+            offset += padding
+            start = offset
+            body.extend(data)
+            offset += len(data)
+            return start
+
+        for segment in self.segments:
+            if segment["data"] is not None:
+                segment["offset"] = place(segment["data"], segment["align"])
+        self.laid_out = {}
+        for section in self.sections[1:]:
+            data = b"" if section["sh_type"] == SHT_NOBITS else section["data"]
+            section["offset"] = place(data, section["align"])
+            allocated = section["flags"] & SHF_ALLOC and self.base is not None
+            section["addr"] = self.base + section["offset"] if allocated else 0
+            self.laid_out[section["name"]] = (section["offset"], section["addr"])
+        shoff = place(b"", 8) if self.sections else 0
+
+        phdrs = b""
+        for segment in self.segments:
+            if segment["data"] is not None:
+                start, vaddr = segment["offset"], segment["vaddr"]
+                filesz = memsz = len(segment["data"])
+            else:
+                spanned = [s for s in self.sections if s["name"] in segment["sections"]]
+                start = 0 if segment["p_type"] == PT_LOAD else spanned[0]["offset"]
+                ends = [(s["offset"] + len(s["data"]), s["sh_type"] == SHT_NOBITS) for s in spanned]
+                filesz = max(end for end, nobits in ends if not nobits) - start
+                memsz = max(end for end, _ in ends) - start
+                vaddr = self.base + start
+            if self.bits == 64:
+                phdrs += self.pack("IIQQQQQQ", segment["p_type"], segment["p_flags"], start, vaddr, vaddr, filesz, memsz, segment["align"])
+            else:
+                phdrs += self.pack("IIIIIIII", segment["p_type"], start, vaddr, vaddr, filesz, memsz, segment["p_flags"], segment["align"])
+
+        shdrs = b""
+        for section in self.sections:
+            size = len(section["data"])
+            offset_field = section.get("offset", 0)
+            if self.bits == 64:
+                shdrs += self.pack(
+                    "IIQQQQIIQQ", section["sh_name"], section["sh_type"], section["flags"], section.get("addr", 0),
+                    offset_field, size, section["link"], section["info"], section["align"], section["entsize"])
+            else:
+                shdrs += self.pack(
+                    "IIIIIIIIII", section["sh_name"], section["sh_type"], section["flags"], section.get("addr", 0),
+                    offset_field, size, section["link"], section["info"], section["align"], section["entsize"])
+
+        ident = b"\x7fELF" + bytes([1 if self.bits == 32 else 2, 2 if self.order == ">" else 1, 1]) + b"\0" * 9
+        entry = self.entry() if callable(self.entry) else (self.entry or 0)
+        header = ident + self.pack(
+            "HHI" + self.word * 3 + "IHHHHHH",
+            self.e_type, self.e_machine, 1, entry,
+            ehsize if self.segments else 0, shoff, self.e_flags, ehsize,
+            phentsize if self.segments else 0, len(self.segments), shentsize, len(self.sections), max(len(self.sections) - 1, 0),
+        )
+        return header + phdrs + bytes(body) + shdrs
+
+    def save(self, name):
+        with open(path(name), "wb") as f:
+            f.write(self.build())
+
+
+def uleb128(value):
+    out = bytearray()
+    while True:
+        byte, value = value & 0x7F, value >> 7
+        out.append(byte | (0x80 if value else 0))
+        if not value:
+            return bytes(out)
+
+
+PT_LOAD = 1
+PT_DYNAMIC = 2
+PT_INTERP = 3
+PT_NOTE = 4
+SHT_PROGBITS = 1
+SHT_SYMTAB = 2
+SHT_STRTAB = 3
+SHT_RELA = 4
+SHT_NOTE = 7
+SHT_NOBITS = 8
+SHT_REL = 9
+SHT_SYMTAB_SHNDX = 18
+SHT_RISCV_ATTRIBUTES = 0x7000_0003
+SHF_WRITE = 0x1
+SHF_ALLOC = 0x2
+SHF_EXECINSTR = 0x4
+STB_LOCAL = 0
+STB_GLOBAL = 1
+STT_OBJECT = 1
+STT_FUNC = 2
+STT_SECTION = 3
+
+
+@fixture
+def hello():
+    gcc("hello", HELLO_C)
+
+
+@fixture
+def hello_o():
+    gcc("hello.o", HELLO_C, "-c")
+
+
+@fixture
+def hello_g():
+    """hello with DWARF 5 debug info, as gcc -g emits by default."""
+    gcc("hello-g", HELLO_C, "-g", "-gdwarf-5")
+
+
+@fixture
+def hello_g4():
+    gcc("hello-g4", HELLO_C, "-g", "-gdwarf-4")
+
+
+@fixture
+def libhello_so():
+    gcc("libhello.so", LIB_C, "-shared", "-fPIC", "-Wl,-soname,libhello.so")
+
+
+@fixture
+def lib32_so():
+    """An i386 library, whose relocations are SHT_REL, without addends.
+    There's no 32-bit libc to link against, nor a need for one."""
+    gcc("lib32.so", LIB32_C, "-m32", "-shared", "-fPIC", "-nostdlib", "-Wl,-soname,lib32.so")
+
+
+@fixture
+def libsysv_so():
+    """libhello.so with only a SysV .hash, no .gnu.hash."""
+    gcc("libsysv.so", LIB_C, "-shared", "-fPIC", "-Wl,-soname,libsysv.so", "-Wl,--hash-style=sysv")
+
+
+@fixture
+def libv_so():
+    """A library defining two versions, one inheriting from the other."""
+    with tempfile.NamedTemporaryFile("w", suffix=".map") as script:
+        script.write(VERSION_SCRIPT)
+        script.flush()
+        gcc("libv.so", VERSIONED_C, "-shared", "-fPIC", "-Wl,--version-script," + script.name)
+
+
+@fixture
+def templates_o():
+    """A C++ object whose template instantiations each get a COMDAT group."""
+    gcc("templates.o", TEMPLATES_CPP, "-c", lang="cpp")
+
+
+@fixture
+def static():
+    """A static executable without libc: no PT_DYNAMIC, no .dynsym."""
+    gcc("static", START_C, "-static", "-nostdlib")
+
+
+@fixture
+def tls():
+    """An executable with .tdata and .tbss, which only PT_TLS holds all of."""
+    gcc("tls", TLS_C)
+
+
+@fixture
+def execstack():
+    gcc("execstack", HELLO_C, "-z", "execstack")
+
+
+@fixture
+def riscv_o():
+    """An rv64gc object with a .riscv.attributes section, as GNU as writes
+    for -march=rv64gc."""
+    elf = ElfWriter(64, False, 1, 243, e_flags=0x5)
+    text = elf.add_section(".text", SHT_PROGBITS, bytes.fromhex("8280"), SHF_ALLOC | SHF_EXECINSTR, align=2)
+    attributes = (
+        uleb128(4) + uleb128(16)
+        + uleb128(5) + b"rv64i2p1_m2p0_a2p1_f2p2_d2p2_c2p0_zicsr2p0_zifencei2p0\0"
+        + uleb128(6) + uleb128(0)
+    )
+    # One "riscv" subsection holding a single Tag_File group
+    tag_file = b"\x01" + struct.pack("<I", 5 + len(attributes)) + attributes
+    subsection = struct.pack("<I", 4 + 6 + len(tag_file)) + b"riscv\0" + tag_file
+    elf.add_section(".riscv.attributes", SHT_RISCV_ATTRIBUTES, b"A" + subsection)
+    elf.add_symbols([("f", 0, 2, STT_FUNC, STB_GLOBAL, text)])
+    elf.save("riscv.o")
+
+
+@fixture
+def ppc64():
+    """A big-endian ppc64 ELFv2 executable: _start making the exit system
+    call, a pointer in .data to it with an R_PPC64_RELATIVE relocation to
+    match, and a build ID note."""
+
+    def layout(relocations, text_address=0, data_address=0):
+        elf = ElfWriter(64, True, 2, 21, e_flags=0x2, base=0x1000_0000)
+        build_id = bytes(range(0x10, 0x24))
+        note = elf.pack("III", 4, len(build_id), 3) + b"GNU\0" + build_id
+        elf.add_section(".note.gnu.build-id", SHT_NOTE, note, SHF_ALLOC, align=4)
+        text = elf.add_section(
+            ".text", SHT_PROGBITS, bytes.fromhex("38600000 38000001 44000002"), SHF_ALLOC | SHF_EXECINSTR, align=4
+        )
+        elf.add_section(".rela.dyn", SHT_RELA, relocations, SHF_ALLOC, align=8, entsize=24)
+        data = elf.add_section(".data", SHT_PROGBITS, b"\0" * 8, SHF_WRITE | SHF_ALLOC, align=8)
+        elf.add_symbols(
+            [("_start", text_address, 12, STT_FUNC, STB_GLOBAL, text), ("entry", data_address, 8, 1, STB_GLOBAL, data)]
+        )
+        elf.add_segment(PT_LOAD, 0x7, sections=(".note.gnu.build-id", ".text", ".rela.dyn", ".data"), align=0x10000)
+        elf.add_segment(PT_NOTE, 0x4, sections=(".note.gnu.build-id",), align=4)
+        elf.entry = lambda: elf.address(".text")
+        return elf
+
+    # Lay it out once to learn where .text and .data go
+    elf = layout(b"\0" * 24)
+    elf.build()
+    text, data = elf.address(".text"), elf.address(".data")
+    elf = layout(elf.rela(data, 0, 22, text), text, data)
+    elf.save("ppc64")
+
+
+@fixture
+def libarm_so():
+    """A 32-bit ARM EABI5 shared library, whose .rel.dyn holds REL
+    relocations without addends: a RELATIVE one for a pointer in .data,
+    a GLOB_DAT one for counter's GOT slot and an ABS32 one for ext."""
+
+    def layout(relocations, data_address=0):
+        elf = ElfWriter(32, False, 3, 40, e_flags=0x0500_0400, base=0)
+        text = elf.add_section(".text", SHT_PROGBITS, bytes.fromhex("1eff2fe1"), SHF_ALLOC | SHF_EXECINSTR, align=4)
+        elf.add_section(".rel.dyn", SHT_REL, relocations, SHF_ALLOC, link=5, align=4, entsize=8)
+        elf.add_section(".got", SHT_PROGBITS, b"\0" * 8, SHF_WRITE | SHF_ALLOC, align=4)
+        data = elf.add_section(".data", SHT_PROGBITS, b"\0" * 8, SHF_WRITE | SHF_ALLOC, align=4)
+        elf.add_symbols(
+            [
+                ("next", 0, 4, STT_FUNC, STB_GLOBAL, text),
+                ("counter", data_address, 4, STT_OBJECT, STB_GLOBAL, data),
+                ("ext", 0, 0, STT_OBJECT, STB_GLOBAL, 0),
+            ]
+        )
+        elf.add_segment(PT_LOAD, 0x7, sections=(".text", ".rel.dyn", ".got", ".data"), align=0x10000)
+        return elf
+
+    # Lay it out once to learn where .got and .data go
+    elf = layout(b"\0" * 24)
+    elf.build()
+    got, data = elf.address(".got"), elf.address(".data")
+    relocations = elf.rel(data + 4, 0, 23) + elf.rel(got, 2, 21) + elf.rel(got + 4, 3, 2)
+    elf = layout(relocations, data)
+    elf.save("libarm.so")
+
+
+@fixture
+def xindex_o():
+    """An x86-64 object with a symbol whose st_shndx is SHN_XINDEX, its
+    real section in .symtab_shndx, as assemblers write for one with too
+    many sections for st_shndx to index."""
+    elf = ElfWriter(64, False, 1, 62)
+    text = elf.add_section(".text", SHT_PROGBITS, bytes.fromhex("c3"), SHF_ALLOC | SHF_EXECINSTR)
+    data = elf.add_section(".data", SHT_PROGBITS, bytes(8), SHF_WRITE | SHF_ALLOC, align=8)
+    symtab = elf.add_symbols(
+        [("f", 0, 1, STT_FUNC, STB_GLOBAL, text), ("counter", 0, 8, 1, STB_GLOBAL, 0xFFFF)]
+    )
+    # One entry per symbol, the null one included; 0 where st_shndx is real
+    shndx = elf.pack("III", 0, 0, data)
+    elf.add_section(".symtab_shndx", SHT_SYMTAB_SHNDX, shndx, link=symtab, align=4, entsize=4)
+    elf.save("xindex.o")
+
+
+@fixture
+def core():
+    """An x86-64 core dump as Linux writes it: no sections, a PT_NOTE with
+    the process's status, info and auxiliary vector, and a PT_LOAD for
+    each mapping, here just the one page of stack."""
+    elf = ElfWriter(64, False, 4, 62)
+
+    def note(n_type, desc):
+        return elf.pack("III", 5, len(desc), n_type) + b"CORE\0\0\0\0" + desc
+
+    prstatus = bytearray(336)
+    struct.pack_into("<i", prstatus, 32, 4242)  # pr_pid
+    prpsinfo = bytearray(136)
+    prpsinfo[40:45] = b"hello"  # pr_fname
+    prpsinfo[56:63] = b"./hello"  # pr_psargs
+    auxv = elf.pack("QQQQ", 6, 0x1000, 0, 0)  # AT_PAGESZ, then AT_NULL
+    notes = note(1, bytes(prstatus)) + note(3, bytes(prpsinfo)) + note(6, auxv)
+    elf.add_segment(PT_NOTE, 0, data=notes)
+    elf.add_segment(PT_LOAD, 0x6, data=bytes(0x1000), align=0x1000, vaddr=0x7FFF_FFFD_E000)
+    elf.save("core")
+
+
+@fixture
+def hugeoffset():
+    """hello with its first PT_LOAD, which holds .dynstr, claiming to
+    start so far into the file that offset plus size wraps around."""
+    elf = Elf64("hello")
+    elf.u64(elf.phdrs(PT_LOAD)[0] + 0x08, 0xFFFF_FFFF_FFFF_FF00)
+    elf.save("hugeoffset")
+
+
+@fixture
+def hugefilesz():
+    """hello with its first PT_LOAD claiming so much file data that the
+    end of it wraps around."""
+    elf = Elf64("hello")
+    load = elf.phdrs(PT_LOAD)[0]
+    elf.u64(load + 0x08, 0x10)
+    elf.u64(load + 0x20, 0xFFFF_FFFF_FFFF_FFFF)
+    elf.save("hugefilesz")
+
+
+@fixture
+def highvaddr():
+    """hello with its first PT_LOAD loaded so near the top of the address
+    space that its contents run past it."""
+    elf = Elf64("hello")
+    load = elf.phdrs(PT_LOAD)[0]
+    elf.u64(load + 0x10, 0xFFFF_FFFF_FFFF_FE00)
+    elf.u64(load + 0x18, 0xFFFF_FFFF_FFFF_FE00)
+    elf.save("highvaddr")
+
+
+@fixture
+def highaddr():
+    """hello with .interp addressed so near the top of the address space
+    that its contents run past it."""
+    elf = Elf64("hello")
+    elf.u64(elf.section(".interp") + 0x10, 0xFFFF_FFFF_FFFF_FFF0)
+    elf.save("highaddr")
+
+
+@fixture
+def libnopad_so():
+    """libhello.so with .dynamic, and PT_DYNAMIC, ending at its first
+    DT_NULL rather than padded out with more."""
+    elf = Elf64("libhello.so")
+    dynamic = elf.section(".dynamic")
+    start = elf.u64(dynamic + 0x18)
+    end = start
+    while elf.u64(end) != 0:
+        end += 16
+    size = end + 16 - start
+    elf.u64(dynamic + 0x20, size)
+    (segment,) = elf.phdrs(PT_DYNAMIC)
+    elf.u64(segment + 0x20, size)
+    elf.u64(segment + 0x28, size)
+    elf.save("libnopad.so")
+
+
+@fixture
+def extnum_o():
+    """hello.o with its section count and string table index moved into
+    section header 0, as a file with too many sections for e_shnum
+    stores them."""
+    elf = Elf64("hello.o")
+    shnum, shstrndx = elf.u16(0x3C), elf.u16(0x3E)
+    elf.u64(elf.shdr(0) + 0x20, shnum)
+    elf.u32(elf.shdr(0) + 0x28, shstrndx)
+    elf.u16(0x3C, 0)
+    elf.u16(0x3E, 0xFFFF)
+    elf.save("extnum.o")
+
+
+@fixture
+def badname():
+    """hello with .rodata's sh_name far past the end of .shstrtab."""
+    elf = Elf64("hello")
+    elf.u32(elf.section(".rodata"), 0xFFFF_FFF0)
+    elf.save("badname")
+
+
+@fixture
+def badinterp():
+    """hello with PT_INTERP cut short of the path's terminating NUL."""
+    elf = Elf64("hello")
+    (interp,) = elf.phdrs(PT_INTERP)
+    elf.u64(interp + 0x20, elf.u64(interp + 0x20) - 1)
+    elf.save("badinterp")
+
+
+@fixture
+def badutf8():
+    """hello with a byte of main's name in .strtab that isn't UTF-8."""
+    elf = Elf64("hello")
+    strtab = elf.u64(elf.section(".strtab") + 0x18)
+    main = elf.data.index(b"\0main\0", strtab) + 1
+    elf.data[main + 2] = 0xFF
+    elf.save("badutf8")
+
+
+@fixture
+def badhash_so():
+    """libsysv.so with a .hash nbucket of 0xffffffff, far more buckets than
+    the section holds."""
+    elf = Elf64("libsysv.so")
+    elf.u32(elf.u64(elf.section(".hash") + 0x18), 0xFFFF_FFFF)
+    elf.save("badhash.so")
+
+
+@fixture
+def badgnuhash_so():
+    """libhello.so with a .gnu.hash nbucket of 0xffffffff."""
+    elf = Elf64("libhello.so")
+    elf.u32(elf.u64(elf.section(".gnu.hash") + 0x18), 0xFFFF_FFFF)
+    elf.save("badgnuhash.so")
+
+
+@fixture
+def noshdrs_so():
+    """libhello.so with no section header table, as sstrip leaves it."""
+    elf = Elf64("libhello.so")
+    elf.u64(0x28, 0)
+    elf.u16(0x3C, 0)
+    elf.u16(0x3E, 0)
+    elf.save("noshdrs.so")
+
+
+@fixture
+def hugeshoff():
+    """hello with e_shoff so near the top of the address space that the
+    section header table's end wraps around."""
+    elf = Elf64("hello")
+    elf.u64(0x28, 0xFFFF_FFFF_FFFF_FF00)
+    elf.save("hugeshoff")
+
+
+@fixture
+def badnote():
+    """hello with the build ID note's descsz running past its section."""
+    elf = Elf64("hello")
+    note = elf.u64(elf.section(".note.gnu.build-id") + 0x18)
+    elf.u32(note + 4, 0x1000)
+    elf.save("badnote")
+
+
+def main(names):
+    for name in names or FIXTURES:
+        FIXTURES[name.replace(".", "_")]()
+
+
+if __name__ == "__main__":
+    main(sys.argv[1:])
@@ -0,0 +1,73 @@
+//! Whole reports compared against the files in tests/golden, one for each
+//! fixture and set of flags below.
+//!
+//! After a deliberate change to the output, or after rebuilding the
+//! fixtures, rewrite the golden files with
+//!
+//!     HRELF_BLESS=1 cargo test --test golden
+//!
+//! and review the diff.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The golden file, the flags and the fixture of each report.
+const GOLDEN: &[(&str, &[&str], &str)] = &[
+    ("ppc64-a.txt", &["-a"], "ppc64"),
+    // readelf -d's, but for the blank line coming after rather than before
+    ("hello-d.txt", &["-d"], "hello"),
+    ("libhello.so-d.txt", &["-d"], "libhello.so"),
+    ("lib32.so-d.txt", &["-d"], "lib32.so"),
+    // readelf -rW's rows, zero addends and all, in narrower columns
+    ("hello.o-r.txt", &["-r"], "hello.o"),
+    ("libhello.so-r.txt", &["-r"], "libhello.so"),
+];
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+#[test]
+fn reports_match_golden_files() {
+    let bless = std::env::var_os("HRELF_BLESS").is_some();
+    let mut mismatched = Vec::new();
+    for &(golden, args, name) in GOLDEN {
+        let output = Command::new(env!("CARGO_BIN_EXE_hrelf"))
+            .args(args)
+            .arg(root().join("fixtures").join(name))
+            .output()
+            .expect("failed to run hrelf");
+        assert!(output.status.success(), "{} {:?}", name, args);
+        let dump = String::from_utf8(output.stdout).unwrap();
+
+        let path = root().join("golden").join(golden);
+        if bless {
+            std::fs::write(&path, &dump).unwrap();
+            continue;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        if dump != expected {
+            eprintln!("--- {}\n+++ hrelf {} {}", golden, args.join(" "), name);
+            // The first differing line, or else where one of them ends
+            let mut expected_lines = expected.lines();
+            let mut lines = dump.lines();
+            for line in 1.. {
+                match (expected_lines.next(), lines.next()) {
+                    (Some(want), Some(got)) if want == got => continue,
+                    (want, got) => {
+                        eprintln!(
+                            "@@ line {}\n-{}\n+{}",
+                            line,
+                            want.unwrap_or(""),
+                            got.unwrap_or("")
+                        );
+                        break;
+                    }
+                }
+            }
+            mismatched.push(golden);
+        }
+    }
+    assert!(mismatched.is_empty(), "differs from {:?}", mismatched);
+}
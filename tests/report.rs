@@ -0,0 +1,282 @@
+//! Tests of the parsing library on the files in tests/fixtures, which
+//! generate.py there rebuilds.
+
+use hrelf::elf::abi;
+use hrelf::elf::endian::AnyEndian;
+use hrelf::elf::file::Class;
+use hrelf::elf::note::Note;
+use hrelf::elf::parse::ParseError;
+use hrelf::{hash, r_type, ElfReport, Relocation};
+use std::path::Path;
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+#[test]
+fn load_segment_overflow_is_an_error() {
+    // One wraps computing where DT_STRTAB starts, the other where its
+    // segment's data ends
+    for name in ["hugeoffset", "hugefilesz"] {
+        let data = fixture(name);
+        let report = ElfReport::parse(&data).unwrap();
+        let dynamic = report.dynamic().unwrap().unwrap();
+        assert!(
+            matches!(
+                report.dynamic_string_table(&dynamic),
+                Err(ParseError::IntegerOverflow)
+            ),
+            "{}",
+            name
+        );
+        assert!(matches!(
+            report.dynamic_symbols_from_dynamic(),
+            Err(ParseError::IntegerOverflow)
+        ));
+    }
+}
+
+#[test]
+fn header() {
+    let data = fixture("hello");
+    let report = ElfReport::parse(&data).unwrap();
+    let ehdr = report.header();
+    assert_eq!(ehdr.class, Class::ELF64);
+    assert_eq!(ehdr.e_type, abi::ET_DYN);
+    assert_eq!(ehdr.e_machine, abi::EM_X86_64);
+    assert_eq!(ehdr.e_entry, 0x1050);
+    assert_eq!(&report.ident()[..4], b"\x7fELF");
+    let counts = report.header_counts();
+    assert_eq!((counts.phnum, counts.shnum, counts.shstrndx), (13, 31, 30));
+}
+
+#[test]
+fn sections() {
+    let data = fixture("hello");
+    let report = ElfReport::parse(&data).unwrap();
+    let sections = report.sections().unwrap().unwrap();
+    assert_eq!(sections.len(), 31);
+    assert_eq!(sections[0].name, "");
+    assert_eq!(sections[0].shdr.sh_type, abi::SHT_NULL);
+    let text = &sections[15];
+    assert_eq!(text.name, ".text");
+    assert_eq!(
+        (text.shdr.sh_addr, text.shdr.sh_offset, text.shdr.sh_size),
+        (0x1050, 0x1050, 0x118)
+    );
+
+    // Selectors are an index or else a name
+    let (index, rodata) = report.find_section(".rodata").unwrap().unwrap();
+    assert_eq!(index, 17);
+    assert_eq!(
+        report.section_data(&rodata).unwrap(),
+        b"\x01\0\x02\0hi %d\n\0"
+    );
+    assert_eq!(
+        report.find_section("17").unwrap().unwrap().1.name,
+        ".rodata"
+    );
+    assert!(report.find_section(".nonesuch").unwrap().is_none());
+
+    let (_, bss) = report.find_section(".bss").unwrap().unwrap();
+    assert_eq!(report.section_data(&bss).unwrap(), b"");
+}
+
+#[test]
+fn symbols() {
+    let data = fixture("hello");
+    let report = ElfReport::parse(&data).unwrap();
+
+    let symtab = report.symbols().unwrap().unwrap();
+    assert_eq!(symtab.name, ".symtab");
+    let main = symtab
+        .entries
+        .iter()
+        .find(|entry| entry.name == "main")
+        .unwrap();
+    assert_eq!((main.symbol.st_value, main.symbol.st_size), (0x1139, 47));
+    assert_eq!(main.symbol.st_symtype(), abi::STT_FUNC);
+    assert_eq!(main.symbol.st_bind(), abi::STB_GLOBAL);
+    assert_eq!(main.section_index(), Some(15));
+
+    let dynsym = report.dynamic_symbols().unwrap().unwrap();
+    assert_eq!(dynsym.name, ".dynsym");
+    assert_eq!(dynsym.entries.len(), 7);
+    let printf = &dynsym.entries[3];
+    assert_eq!(printf.name, "printf");
+    assert_eq!(printf.section_index(), None);
+    let version = printf.version.unwrap();
+    assert_eq!((version.index, version.name), (3, Some("GLIBC_2.2.5")));
+    assert!(version.needed);
+}
+
+#[test]
+fn extended_section_index() {
+    let data = fixture("xindex.o");
+    let report = ElfReport::parse(&data).unwrap();
+    let symbols = report.symbols().unwrap().unwrap();
+    let counter = &symbols.entries[2];
+    assert_eq!(counter.name, "counter");
+    assert_eq!(counter.symbol.st_shndx, abi::SHN_XINDEX);
+    assert_eq!(counter.xindex, Some(2));
+    assert_eq!(counter.section_index(), Some(2));
+    // Only SHN_XINDEX symbols take their index from .symtab_shndx
+    assert_eq!(symbols.entries[1].section_index(), Some(1));
+}
+
+#[test]
+fn rel_relocations() {
+    let data = fixture("lib32.so");
+    let report = ElfReport::parse(&data).unwrap();
+    let sections = report.relocations().unwrap();
+    let names: Vec<_> = sections.iter().map(|section| section.name).collect();
+    assert_eq!(names, [".rel.dyn", ".rel.plt"]);
+    assert!(sections[0]
+        .relocations
+        .iter()
+        .all(|rel| rel.r_addend.is_none()));
+
+    let relative = &sections[0].relocations[0];
+    assert_eq!((relative.r_offset, relative.r_sym), (0x400c, 0));
+    let r_type = |rel: &Relocation| r_type::r_type_to_str(abi::EM_386, rel.r_type);
+    assert_eq!(r_type(relative), Some("R_386_RELATIVE"));
+    assert!(relative.symbol.is_none());
+    let absolute = &sections[0].relocations[2];
+    assert_eq!(r_type(absolute), Some("R_386_32"));
+    let symbol = absolute.symbol.unwrap();
+    assert_eq!((symbol.name, symbol.value), ("g", 0x4004));
+}
+
+#[test]
+fn big_endian_fields() {
+    let data = fixture("ppc64");
+    let report = ElfReport::parse(&data).unwrap();
+    let ehdr = report.header();
+    assert_eq!(ehdr.endianness, AnyEndian::Big);
+    assert_eq!((ehdr.e_machine, ehdr.e_entry), (abi::EM_PPC64, 0x1000_00d4));
+    let (_, data_section) = report.find_section(".data").unwrap().unwrap();
+    assert_eq!(data_section.shdr.sh_addr, 0x1000_00f8);
+
+    let relocations = report.relocations().unwrap();
+    let relative = &relocations[0].relocations[0];
+    assert_eq!(relative.r_offset, data_section.shdr.sh_addr);
+    assert_eq!(relative.r_type, abi::R_PPC64_RELATIVE);
+    assert_eq!(relative.r_addend, Some(0x1000_00d4));
+
+    // Notes are read through the file's byte order too
+    let notes = report.notes().unwrap();
+    match &notes[0].notes[..] {
+        [Note::GnuBuildId(build_id)] => assert_eq!(build_id.0[..3], [0x10, 0x11, 0x12]),
+        notes => panic!("{:?}", notes),
+    }
+}
+
+#[test]
+fn truncated_input_is_an_error() {
+    let data = fixture("hello");
+    assert!(matches!(
+        ElfReport::parse(&[0; 64]),
+        Err(ParseError::BadMagic(_))
+    ));
+    // Short of a whole ELF header
+    assert!(ElfReport::parse(&data[..0x30]).is_err());
+    // Short of the section header table at the end
+    assert!(matches!(
+        ElfReport::parse(&data[..0x1000]),
+        Err(ParseError::SliceReadError(_))
+    ));
+}
+
+#[test]
+fn truncated_table_only_fails_its_own_accessor() {
+    let mut data = fixture("hello");
+    let report = ElfReport::parse(&data).unwrap();
+    let (index, _) = report.find_section(".symtab").unwrap().unwrap();
+    let sh_size = report.header().e_shoff as usize + index * 64 + 0x20;
+    // .symtab now runs past the end of the file
+    data[sh_size..sh_size + 8].copy_from_slice(&0x10_0000u64.to_le_bytes());
+
+    let report = ElfReport::parse(&data).unwrap();
+    assert!(matches!(
+        report.symbols(),
+        Err(ParseError::SliceReadError(_))
+    ));
+    assert_eq!(report.sections().unwrap().unwrap().len(), 31);
+    assert_eq!(report.dynamic_symbols().unwrap().unwrap().entries.len(), 7);
+}
+
+#[test]
+fn sysv_hash_symbol_count() {
+    let data = fixture("libsysv.so");
+    let report = ElfReport::parse(&data).unwrap();
+    let (_, section) = report.find_section(".hash").unwrap().unwrap();
+    let table = report.section_data(&section).unwrap();
+    assert_eq!(
+        hash::sysv_symbol_count(AnyEndian::Little, table).unwrap(),
+        7
+    );
+    assert_eq!(
+        hash::sysv_chain_lengths(AnyEndian::Little, table).unwrap(),
+        [2, 3, 1]
+    );
+    let symbols = report.dynamic_symbols_from_dynamic().unwrap().unwrap();
+    assert_eq!(symbols.entries.len(), 7);
+}
+
+#[test]
+fn gnu_hash_symbol_count() {
+    let data = fixture("libhello.so");
+    let report = ElfReport::parse(&data).unwrap();
+    let (_, section) = report.find_section(".gnu.hash").unwrap().unwrap();
+    let table = report.section_data(&section).unwrap();
+    assert_eq!(
+        hash::gnu_symbol_count(AnyEndian::Little, Class::ELF64, table).unwrap(),
+        7
+    );
+    assert_eq!(
+        hash::gnu_chain_lengths(AnyEndian::Little, Class::ELF64, table).unwrap(),
+        [1, 1]
+    );
+    let symbols = report.dynamic_symbols_from_dynamic().unwrap().unwrap();
+    assert_eq!(symbols.entries.len(), 7);
+}
+
+#[test]
+fn truncated_hash_table_is_an_error() {
+    let data = fixture("libsysv.so");
+    let report = ElfReport::parse(&data).unwrap();
+    let (_, section) = report.find_section(".hash").unwrap().unwrap();
+    let table = report.section_data(&section).unwrap();
+    // Short of nchain, then of the buckets, then of the last chain entry
+    assert!(hash::sysv_symbol_count(AnyEndian::Little, &table[..4]).is_err());
+    for end in [4, 12, table.len() - 4] {
+        assert!(
+            hash::sysv_chain_lengths(AnyEndian::Little, &table[..end]).is_err(),
+            "cut at {}",
+            end
+        );
+    }
+
+    let data = fixture("libhello.so");
+    let report = ElfReport::parse(&data).unwrap();
+    let (_, section) = report.find_section(".gnu.hash").unwrap().unwrap();
+    let table = report.section_data(&section).unwrap();
+    // Short of the bloom filter's end, then of the buckets, then of the
+    // chains
+    for end in [12, 0x18, table.len() - 4] {
+        let table = &table[..end];
+        assert!(
+            hash::gnu_symbol_count(AnyEndian::Little, Class::ELF64, table).is_err(),
+            "cut at {}",
+            end
+        );
+        assert!(
+            hash::gnu_chain_lengths(AnyEndian::Little, Class::ELF64, table).is_err(),
+            "cut at {}",
+            end
+        );
+    }
+}